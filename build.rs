@@ -4,22 +4,38 @@ use std::path::Path;
 use std::process::{Command, ExitStatus, Stdio};
 use std::str;
 
-// This code exercises the surface area that we expect of the Provider API. If
-// the current toolchain is able to compile it, then thiserror is able to use
-// providers for backtrace support.
+// This code exercises the surface area that we expect of the Error generic
+// member access API. If the current toolchain is able to compile it, then
+// thiserror is able to provide backtraces and other data through Error::provide.
 const PROBE: &str = r#"
-    #![feature(provide_any)]
+    #![feature(error_generic_member_access)]
 
-    use std::any::{Demand, Provider};
+    use std::error::{Error, Request};
 
-    fn _f<'a, P: Provider>(p: &'a P, demand: &mut Demand<'a>) {
-        p.provide(demand);
+    fn _f<'a, E: Error + ?Sized>(e: &'a E, request: &mut Request<'a>) {
+        e.provide(request);
     }
 "#;
 
 fn main() {
+    println!("cargo:rustc-check-cfg=cfg(error_generic_member_access)");
+    println!("cargo:rustc-check-cfg=cfg(thiserror_nightly_testing)");
+    println!("cargo:rustc-check-cfg=cfg(thiserror_no_diagnostic_namespace)");
+
+    match rustc_minor_version() {
+        // #[diagnostic::on_unimplemented]
+        Some(minor) if minor < 78 => println!("cargo:rustc-cfg=thiserror_no_diagnostic_namespace"),
+        _ => {}
+    }
+
+    // Generated provide() methods need the feature enabled in the crate that
+    // derives Error, so they are opt-in even on a compiler that has the API.
+    if env::var_os("CARGO_FEATURE_PROVIDE").is_none() {
+        return;
+    }
+
     match compile_probe() {
-        Some(status) if status.success() => println!("cargo:rustc-cfg=provide_any"),
+        Some(status) if status.success() => println!("cargo:rustc-cfg=error_generic_member_access"),
         _ => {}
     }
 }
@@ -64,3 +80,14 @@ fn compile_probe() -> Option<ExitStatus> {
 
     cmd.status().ok()
 }
+
+fn rustc_minor_version() -> Option<u32> {
+    let rustc = env::var_os("RUSTC")?;
+    let output = Command::new(rustc).arg("--version").output().ok()?;
+    let version = str::from_utf8(&output.stdout).ok()?;
+    let mut pieces = version.split('.');
+    if pieces.next() != Some("rustc 1") {
+        return None;
+    }
+    pieces.next()?.parse().ok()
+}
@@ -1,9 +1,10 @@
-use crate::attr::{self, Attrs};
+use crate::attr::{self, Attrs, Transparent};
 use crate::generics::ParamsInScope;
-use proc_macro2::Span;
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, ToTokens};
 use syn::{
-    Data, DataEnum, DataStruct, DeriveInput, Error, Fields, Generics, Ident, Index, Member, Result,
-    Type,
+    Data, DataEnum, DataStruct, DeriveInput, Error, Fields, GenericArgument, Generics, Ident,
+    Index, Member, PathArguments, Result, Type,
 };
 
 pub enum Input<'a> {
@@ -40,6 +41,8 @@ pub struct Field<'a> {
     pub member: Member,
     pub ty: &'a Type,
     pub contains_generic: bool,
+    // Box<Self>, Arc<Self> or Rc<Self>, possibly inside Option.
+    pub recursive: bool,
 }
 
 impl<'a> Input<'a> {
@@ -47,10 +50,14 @@ impl<'a> Input<'a> {
         match &node.data {
             Data::Struct(data) => Struct::from_syn(node, data).map(Input::Struct),
             Data::Enum(data) => Enum::from_syn(node, data).map(Input::Enum),
-            Data::Union(_) => Err(Error::new_spanned(
-                node,
-                "union as errors are not supported",
-            )),
+            Data::Union(data) => {
+                let union_token = data.union_token;
+                let ident = &node.ident;
+                Err(Error::new_spanned(
+                    quote!(#union_token #ident),
+                    "Error cannot be derived for unions",
+                ))
+            }
         }
     }
 }
@@ -60,9 +67,37 @@ impl<'a> Struct<'a> {
         let mut attrs = attr::get(&node.attrs)?;
         let scope = ParamsInScope::new(&node.generics);
         let span = attrs.span().unwrap_or_else(Span::call_site);
-        let fields = Field::multiple_from_syn(&data.fields, &scope, span)?;
+        let mut fields = Field::multiple_from_syn(&data.fields, node, &scope, span)?;
+        if attrs.config.related.is_none() {
+            ignore_related(&mut fields);
+        }
+        if let Some(span) = attrs.config.auto_transparent {
+            // A newtype without a message of its own forwards to its field.
+            let is_newtype = match &data.fields {
+                Fields::Unnamed(fields) => fields.unnamed.len() == 1,
+                Fields::Named(_) | Fields::Unit => false,
+            };
+            if is_newtype
+                && attrs.display.is_none()
+                && attrs.transparent.is_none()
+                && attrs.transparent_str.is_none()
+            {
+                let original = node
+                    .attrs
+                    .iter()
+                    .find(|attr| attr.path.is_ident("thiserror"))
+                    .unwrap();
+                attrs.transparent = Some(Transparent { original, span });
+            }
+        }
         if let Some(display) = &mut attrs.display {
-            display.expand_shorthand(&fields);
+            if attrs.config.defmt.is_some() {
+                display.expand_defmt(&fields)?;
+            }
+            if attrs.config.ufmt.is_some() {
+                display.expand_ufmt(&fields)?;
+            }
+            display.expand_shorthand(&fields, &scope);
         }
         Ok(Struct {
             original: node,
@@ -76,6 +111,7 @@ impl<'a> Struct<'a> {
 
 impl<'a> Enum<'a> {
     fn from_syn(node: &'a DeriveInput, data: &'a DataEnum) -> Result<Self> {
+        let container = node;
         let attrs = attr::get(&node.attrs)?;
         let scope = ParamsInScope::new(&node.generics);
         let span = attrs.span().unwrap_or_else(Span::call_site);
@@ -83,12 +119,35 @@ impl<'a> Enum<'a> {
             .variants
             .iter()
             .map(|node| {
-                let mut variant = Variant::from_syn(node, &scope, span)?;
+                let mut variant = Variant::from_syn(node, container, &scope, span)?;
+                if attrs.config.related.is_none() {
+                    ignore_related(&mut variant.fields);
+                }
+                if variant.attrs.transparent_str.is_some() {
+                    return Ok(variant);
+                }
+                if let Some(flatten) = &variant.attrs.flatten {
+                    // A flattened variant shows, and continues the chain of
+                    // sources from, the error it wraps.
+                    if variant.attrs.display.is_none() && variant.attrs.transparent.is_none() {
+                        variant.attrs.transparent = Some(Transparent {
+                            original: flatten.original,
+                            span: flatten.original.path.get_ident().unwrap().span(),
+                        });
+                    }
+                    return Ok(variant);
+                }
                 if let display @ None = &mut variant.attrs.display {
                     *display = attrs.display.clone();
                 }
                 if let Some(display) = &mut variant.attrs.display {
-                    display.expand_shorthand(&variant.fields);
+                    if attrs.config.defmt.is_some() {
+                        display.expand_defmt(&variant.fields)?;
+                    }
+                    if attrs.config.ufmt.is_some() {
+                        display.expand_ufmt(&variant.fields)?;
+                    }
+                    display.expand_shorthand(&variant.fields, &scope);
                 } else if variant.attrs.transparent.is_none() {
                     variant.attrs.transparent = attrs.transparent;
                 }
@@ -106,14 +165,19 @@ impl<'a> Enum<'a> {
 }
 
 impl<'a> Variant<'a> {
-    fn from_syn(node: &'a syn::Variant, scope: &ParamsInScope<'a>, span: Span) -> Result<Self> {
+    fn from_syn(
+        node: &'a syn::Variant,
+        container: &DeriveInput,
+        scope: &ParamsInScope<'a>,
+        span: Span,
+    ) -> Result<Self> {
         let attrs = attr::get(&node.attrs)?;
         let span = attrs.span().unwrap_or(span);
         Ok(Variant {
             original: node,
             attrs,
             ident: node.ident.clone(),
-            fields: Field::multiple_from_syn(&node.fields, scope, span)?,
+            fields: Field::multiple_from_syn(&node.fields, container, scope, span)?,
         })
     }
 }
@@ -121,19 +185,21 @@ impl<'a> Variant<'a> {
 impl<'a> Field<'a> {
     fn multiple_from_syn(
         fields: &'a Fields,
+        container: &DeriveInput,
         scope: &ParamsInScope<'a>,
         span: Span,
     ) -> Result<Vec<Self>> {
         fields
             .iter()
             .enumerate()
-            .map(|(i, field)| Field::from_syn(i, field, scope, span))
+            .map(|(i, field)| Field::from_syn(i, field, container, scope, span))
             .collect()
     }
 
     fn from_syn(
         i: usize,
         node: &'a syn::Field,
+        container: &DeriveInput,
         scope: &ParamsInScope<'a>,
         span: Span,
     ) -> Result<Self> {
@@ -148,6 +214,7 @@ impl<'a> Field<'a> {
             }),
             ty: &node.ty,
             contains_generic: scope.intersects(&node.ty),
+            recursive: type_is_recursive(&node.ty, container),
         })
     }
 }
@@ -163,3 +230,53 @@ impl Attrs<'_> {
         }
     }
 }
+
+// Without #[thiserror(related)], #[related] is left to other derives that use
+// it, such as miette's Diagnostic.
+fn ignore_related(fields: &mut [Field]) {
+    for field in fields {
+        field.attrs.related = None;
+    }
+}
+
+fn type_is_recursive(ty: &Type, container: &DeriveInput) -> bool {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return false,
+    };
+    let last = path.segments.last().unwrap();
+    let inner = match &last.arguments {
+        PathArguments::AngleBracketed(bracketed) if bracketed.args.len() == 1 => {
+            match &bracketed.args[0] {
+                GenericArgument::Type(inner) => inner,
+                _ => return false,
+            }
+        }
+        _ => return false,
+    };
+    if last.ident == "Option" {
+        return type_is_recursive(inner, container);
+    }
+    if last.ident != "Box" && last.ident != "Arc" && last.ident != "Rc" {
+        return false;
+    }
+    let inner = match inner {
+        Type::Path(inner) if inner.qself.is_none() => &inner.path,
+        _ => return false,
+    };
+    if inner.is_ident("Self") {
+        return true;
+    }
+    // A longer path such as `other::Error<T>` may name a different type that
+    // happens to share the container's name.
+    if inner.leading_colon.is_some() || inner.segments.len() != 1 {
+        return false;
+    }
+    let (_, ty_generics, _) = container.generics.split_for_impl();
+    let inner = &inner.segments[0];
+    let args = match &inner.arguments {
+        PathArguments::None => TokenStream::new(),
+        args => args.to_token_stream(),
+    };
+    inner.ident == container.ident && args.to_string() == ty_generics.to_token_stream().to_string()
+}
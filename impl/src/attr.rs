@@ -1,33 +1,131 @@
 use proc_macro2::{Delimiter, Group, Span, TokenStream, TokenTree};
-use quote::{format_ident, quote, ToTokens};
+use quote::{format_ident, quote, quote_spanned, ToTokens};
 use std::collections::BTreeSet as Set;
-use std::iter::FromIterator;
-use syn::parse::{Nothing, ParseStream};
+use std::iter::{self, FromIterator};
+use syn::parse::{Nothing, Parse, ParseStream};
 use syn::{
-    braced, bracketed, parenthesized, token, Attribute, Error, Ident, Index, LitInt, LitStr,
-    Result, Token,
+    braced, bracketed, parenthesized, token, Attribute, Error, Expr, Ident, Index, LitBool, LitInt,
+    LitStr, Member, Path, PredicateType, Result, Token, Type, WherePredicate,
 };
 
 pub struct Attrs<'a> {
     pub config: Config,
+    pub aggregate: Option<Aggregate<'a>>,
+    pub bounds: Vec<Bound<'a>>,
+    pub code: Option<Code<'a>>,
+    pub constructor: Option<Constructor<'a>>,
     pub display: Option<Display<'a>>,
-    pub source: Option<&'a Attribute>,
-    pub backtrace: Option<&'a Attribute>,
-    pub from: Option<&'a Attribute>,
+    pub exit_code: Option<ExitCode<'a>>,
+    pub flatten: Option<Flatten<'a>>,
+    pub kind: Option<Kind<'a>>,
+    pub level: Option<Level<'a>>,
+    pub name: Option<Name<'a>>,
+    pub no_bound: Option<&'a Attribute>,
+    pub source: Option<Source<'a>>,
+    pub span_trace: Option<&'a Attribute>,
+    pub backtrace: Option<Backtrace<'a>>,
+    pub from: Option<From<'a>>,
+    pub provide: Option<Provide<'a>>,
+    pub related: Option<&'a Attribute>,
+    // #[redact]
+    pub redact: Option<&'a Attribute>,
+    // #[sensitive]
+    pub sensitive: Option<&'a Attribute>,
+    // #[error(serialize)]
+    pub serialize: Option<&'a Attribute>,
     pub transparent: Option<Transparent<'a>>,
+    // #[error(transparent_str)]
+    pub transparent_str: Option<&'a Attribute>,
+    pub try_from: Option<TryFrom<'a>>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Default)]
 pub struct Config {
     pub generics_err_as_ref: bool,
-}
-
-impl Default for Config {
-    fn default() -> Self {
-        Self {
-            generics_err_as_ref: false,
-        }
-    }
+    // #[thiserror(accessors)]
+    pub accessors: Option<Span>,
+    // #[thiserror(auto_transparent)]
+    pub auto_transparent: Option<Span>,
+    // #[thiserror(clone = "arc_sources")]
+    pub clone: Option<Span>,
+    // #[thiserror(codes)]
+    pub codes: Option<Span>,
+    // #[thiserror(constructors)]
+    pub constructors: Option<Span>,
+    // #[thiserror(context)]
+    pub context: Option<Span>,
+    // #[thiserror(context_setter)]
+    pub context_setter: Option<Span>,
+    // #[thiserror(crate = "path::to::thiserror")]
+    pub crate_path: Option<TokenStream>,
+    // #[thiserror(debug)]
+    pub debug: Option<Span>,
+    // #[thiserror(debug_transparent)]
+    pub debug_transparent: Option<Span>,
+    // #[thiserror(defmt)]
+    pub defmt: Option<Span>,
+    // #[thiserror(deserialize)]
+    pub deserialize: Option<Span>,
+    // #[thiserror(discriminant_code)]
+    pub discriminant_code: Option<Span>,
+    // #[thiserror(document_messages)]
+    pub document_messages: Option<Span>,
+    // #[thiserror(downcast)]
+    pub downcast: Option<Span>,
+    // #[thiserror(eq)]
+    pub eq: Option<Span>,
+    // #[thiserror(fields)]
+    pub fields: Option<Span>,
+    // #[thiserror(hash_ignore_source)]
+    pub hash_ignore_source: Option<Span>,
+    // #[thiserror(hide_generated)]
+    pub hide_generated: Option<Span>,
+    // #[thiserror(inner_accessors)]
+    pub inner_accessors: Option<Span>,
+    // #[thiserror(kind)]
+    pub kind: Option<Span>,
+    // #[thiserror(kind = "FrobKind")]
+    pub kind_ident: Option<Ident>,
+    // #[thiserror(legacy_description)]
+    pub legacy_description: Option<Span>,
+    // #[thiserror(localize = path::to::Localizer)]
+    pub localize: Option<TokenStream>,
+    // #[thiserror(no_error_impl)]
+    pub no_error_impl: Option<Span>,
+    // #[thiserror(no_std)]
+    pub no_std: Option<Span>,
+    // #[thiserror(partial_eq)]
+    pub partial_eq: Option<Span>,
+    // #[thiserror(partial_eq = "message")]
+    pub partial_eq_message: bool,
+    // #[thiserror(predicates)]
+    pub predicates: Option<Span>,
+    // #[thiserror(related)]
+    pub related: Option<Span>,
+    // #[thiserror(report)]
+    pub report: Option<Span>,
+    // #[thiserror(repr_c_codes)]
+    pub repr_c_codes: Option<Span>,
+    // #[thiserror(require_debug)]
+    pub require_debug: Option<Span>,
+    // #[thiserror(result_alias)]
+    pub result_alias: Option<Span>,
+    // #[thiserror(result_alias = "StoreResult")]
+    pub result_alias_ident: Option<Ident>,
+    // #[thiserror(root_cause)]
+    pub root_cause: Option<Span>,
+    // #[thiserror(serialize)]
+    pub serialize: Option<Span>,
+    // #[thiserror(setters)]
+    pub setters: Option<Span>,
+    // #[thiserror(static_messages)]
+    pub static_messages: Option<Span>,
+    // #[thiserror(ufmt)]
+    pub ufmt: Option<Span>,
+    // #[thiserror(variant_names)]
+    pub variant_names: Option<Span>,
+    // #[thiserror(variants)]
+    pub variants: Option<Span>,
 }
 
 #[derive(Clone)]
@@ -37,6 +135,189 @@ pub struct Display<'a> {
     pub args: TokenStream,
     pub has_bonus_display: bool,
     pub implied_bounds: Set<(usize, Trait)>,
+    // Bounds on the type at the end of a path like `{inner.0}`, by the field
+    // the path starts from.
+    pub leaf_bounds: Vec<(usize, &'a Type, Trait)>,
+    // Paths like `{inner.code}` into a field of generic type, whose leaf type
+    // cannot be seen and so cannot be bounded.
+    pub opaque_paths: Vec<(usize, String)>,
+    // The same message for defmt::write!, under #[thiserror(defmt)].
+    pub defmt: Option<DefmtMessage>,
+    // The same message for ufmt::uwrite!, under #[thiserror(ufmt)].
+    pub ufmt: Option<UfmtMessage>,
+    // A message per arm instead of `fmt`, under #[error(match = ...)].
+    pub matching: Option<DisplayMatch<'a>>,
+    // The message written under `{:#}`, from #[error(alt = "...")].
+    pub alternate: Option<Box<Display<'a>>>,
+}
+
+// #[error(match = .kind, Kind::A => "...", Kind::B => "...")]
+#[derive(Clone)]
+pub struct DisplayMatch<'a> {
+    // The field matched on.
+    pub member: Member,
+    // Each pattern, kept as written, with the message it selects.
+    pub arms: Vec<(TokenStream, Display<'a>)>,
+}
+
+#[derive(Clone)]
+pub struct DefmtMessage {
+    pub fmt: LitStr,
+    // One place per `{}` placeholder, in order, each with the span of its
+    // field.
+    pub args: Vec<TokenStream>,
+    // Fields interpolated whole into the message, which must implement Format.
+    pub fields: Set<usize>,
+}
+
+#[derive(Clone)]
+pub struct UfmtMessage {
+    pub fmt: LitStr,
+    // One place per placeholder, in order.
+    pub args: Vec<TokenStream>,
+    // Fields interpolated whole into the message, with the trait their
+    // placeholder formats them by.
+    pub bounds: Set<(usize, UfmtTrait)>,
+}
+
+#[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub enum UfmtTrait {
+    Display,
+    Debug,
+    DisplayHex,
+}
+
+// #[aggregate] or #[aggregate(separator = "; ", max = 10)]
+pub struct Aggregate<'a> {
+    pub original: &'a Attribute,
+    pub separator: Option<LitStr>,
+    pub max: Option<LitInt>,
+}
+
+// #[backtrace] or #[backtrace(force, disabled_unless = "feature", from_source)]
+pub struct Backtrace<'a> {
+    pub original: &'a Attribute,
+    // Capture with force_capture() instead of capture().
+    pub force: bool,
+    // Skip the capture in From impls if the source provides a backtrace.
+    pub from_source: bool,
+    // Store Backtrace::disabled() unless this feature of the deriving crate is
+    // enabled.
+    pub disabled_unless: Option<LitStr>,
+}
+
+// #[provide] or #[provide(value)]
+#[derive(Copy, Clone)]
+pub struct Provide<'a> {
+    pub original: &'a Attribute,
+    // Provide a copy of the field with provide_value instead of a reference.
+    pub value: bool,
+}
+
+// #[error(bound(T: Trait))], #[error(display_bound(...))] or
+// #[error(error_bound(...))]
+pub struct Bound<'a> {
+    pub original: &'a Attribute,
+    // Whether the predicates apply to the Display impl, the Error impl, or both.
+    pub display: bool,
+    pub error: bool,
+    // Each bounds a type parameter, replacing the bounds inferred for it.
+    pub predicates: Vec<PredicateType>,
+}
+
+// #[error(code = 404)]
+pub struct Code<'a> {
+    pub original: &'a Attribute,
+    // The value returned by code() for the variant, under
+    // #[thiserror(discriminant_code)] or #[thiserror(codes)].
+    pub expr: Expr,
+}
+
+// #[error(constructor = "from_query")]
+pub struct Constructor<'a> {
+    pub original: &'a Attribute,
+    // The name of the variant's constructor, under #[thiserror(constructors)],
+    // in place of the variant's name in snake_case.
+    pub ident: Ident,
+}
+
+// #[error(exit_code = 65)]
+pub struct ExitCode<'a> {
+    pub original: &'a Attribute,
+    // The status the process exits with for this error. Put on an enum, it
+    // replaces the default of 1 for variants that declare none.
+    pub value: u8,
+}
+
+// #[error(kind = Io)]
+pub struct Kind<'a> {
+    pub original: &'a Attribute,
+    // The variant of the kind enum generated by #[thiserror(kind)] that the
+    // error variant maps to, in place of one named like the error variant.
+    pub ident: Ident,
+}
+
+// #[error(name = "timeout")]
+pub struct Name<'a> {
+    pub original: &'a Attribute,
+    // The name of the variant for variant_name() under
+    // #[thiserror(variant_names)] and for #[thiserror(deserialize)], in place
+    // of the variant's identifier.
+    pub value: LitStr,
+}
+
+// #[error(level = "warn")]
+pub struct Level<'a> {
+    pub original: &'a Attribute,
+    // One of the associated constants of tracing::Level.
+    pub ident: Ident,
+}
+
+pub struct Source<'a> {
+    pub original: &'a Attribute,
+    // #[source(wrap)]
+    pub wrap: bool,
+    // #[source(deref)]
+    pub deref: bool,
+    // #[source(err_as_ref)] or #[source(err_as_ref = false)], overriding
+    // #[thiserror(generics_err_as_ref)] for this field.
+    pub err_as_ref: Option<bool>,
+    // #[source(no_bound)]
+    pub no_bound: bool,
+    // #[source(with = self.inner.as_error())], an expression evaluating to
+    // the source in place of the field itself. Kept as tokens, since syn
+    // without the "full" feature cannot parse a method call.
+    pub with: Option<TokenStream>,
+}
+
+pub struct From<'a> {
+    pub original: &'a Attribute,
+    pub by_ref: Option<FromRef>,
+    // #[from(kind = NotFound | PermissionDenied)]
+    pub kinds: Vec<Ident>,
+    // #[from(try_into)]
+    pub try_into: bool,
+    // #[from(into = Inner)]
+    pub into: Option<Type>,
+    // #[from(via = Lower | Lowest)], each converted into the field's type
+    // first.
+    pub via: Vec<Type>,
+    // #[from(ctor = |source| ...)], a closure or function building the whole
+    // error out of the source. Kept as tokens, since syn without the "full"
+    // feature cannot parse a closure.
+    pub ctor: Option<TokenStream>,
+}
+
+// #[flatten] or #[flatten(Lower, Lowest)], each of the listed types converted
+// into the variant's #[from] field first.
+pub struct Flatten<'a> {
+    pub original: &'a Attribute,
+    pub types: Vec<Type>,
+}
+
+// #[from(ref)] or #[from(ref, with = path)]
+pub struct FromRef {
+    pub with: Option<Path>,
 }
 
 #[derive(Copy, Clone)]
@@ -45,6 +326,12 @@ pub struct Transparent<'a> {
     pub span: Span,
 }
 
+pub struct TryFrom<'a> {
+    pub original: &'a Attribute,
+    pub ty: Type,
+    pub with: Path,
+}
+
 #[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Debug)]
 pub enum Trait {
     Debug,
@@ -58,42 +345,149 @@ pub enum Trait {
     UpperExp,
 }
 
-pub fn get(input: &[Attribute]) -> Result<Attrs> {
+pub fn get(input: &[Attribute]) -> Result<Attrs<'_>> {
     let mut attrs = Attrs {
         config: Config::default(),
+        aggregate: None,
+        bounds: Vec::new(),
+        code: None,
+        constructor: None,
         display: None,
+        exit_code: None,
+        flatten: None,
+        kind: None,
+        level: None,
+        name: None,
+        no_bound: None,
         source: None,
+        span_trace: None,
         backtrace: None,
         from: None,
+        provide: None,
+        related: None,
+        redact: None,
+        sensitive: None,
+        serialize: None,
         transparent: None,
+        transparent_str: None,
+        try_from: None,
     };
+    let mut alternate = None;
 
     for attr in input {
         if attr.path.is_ident("error") {
-            parse_error_attribute(&mut attrs, attr)?;
+            parse_error_attribute(&mut attrs, &mut alternate, attr)?;
         } else if attr.path.is_ident("thiserror") {
             parse_thiserror_attribute(&mut attrs, attr)?;
         } else if attr.path.is_ident("source") {
-            require_empty_attribute(attr)?;
+            let source = parse_source_attribute(attr)?;
             if attrs.source.is_some() {
                 return Err(Error::new_spanned(attr, "duplicate #[source] attribute"));
             }
-            attrs.source = Some(attr);
+            attrs.source = Some(source);
         } else if attr.path.is_ident("backtrace") {
-            require_empty_attribute(attr)?;
+            let backtrace = parse_backtrace_attribute(attr)?;
             if attrs.backtrace.is_some() {
                 return Err(Error::new_spanned(attr, "duplicate #[backtrace] attribute"));
             }
-            attrs.backtrace = Some(attr);
+            attrs.backtrace = Some(backtrace);
+        } else if attr.path.is_ident("span_trace") {
+            require_empty_attribute(attr)?;
+            if attrs.span_trace.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[span_trace] attribute",
+                ));
+            }
+            attrs.span_trace = Some(attr);
+        } else if attr.path.is_ident("provide") {
+            let provide = parse_provide_attribute(attr)?;
+            if attrs.provide.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[provide] attribute"));
+            }
+            attrs.provide = Some(provide);
         } else if attr.path.is_ident("from") {
-            if !attr.tokens.is_empty() {
+            let from = match parse_from_attribute(attr)? {
+                Some(from) => from,
                 // Assume this is meant for derive_more crate or something.
-                continue;
-            }
+                None => continue,
+            };
             if attrs.from.is_some() {
                 return Err(Error::new_spanned(attr, "duplicate #[from] attribute"));
             }
-            attrs.from = Some(attr);
+            attrs.from = Some(from);
+        } else if attr.path.is_ident("aggregate") {
+            if attrs.aggregate.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[aggregate] attribute"));
+            }
+            attrs.aggregate = Some(parse_aggregate_attribute(attr)?);
+        } else if attr.path.is_ident("related") {
+            require_empty_attribute(attr)?;
+            if attrs.related.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[related] attribute"));
+            }
+            attrs.related = Some(attr);
+        } else if attr.path.is_ident("flatten") {
+            if attrs.flatten.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[flatten] attribute"));
+            }
+            attrs.flatten = Some(parse_flatten_attribute(attr)?);
+        } else if attr.path.is_ident("redact") {
+            require_empty_attribute(attr)?;
+            if attrs.redact.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[redact] attribute"));
+            }
+            attrs.redact = Some(attr);
+        } else if attr.path.is_ident("sensitive") {
+            require_empty_attribute(attr)?;
+            if attrs.sensitive.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[sensitive] attribute"));
+            }
+            attrs.sensitive = Some(attr);
+        } else if attr.path.is_ident("try_from") {
+            if attrs.try_from.is_some() {
+                return Err(Error::new_spanned(attr, "duplicate #[try_from] attribute"));
+            }
+            attrs.try_from = Some(parse_try_from_attribute(attr)?);
+        }
+    }
+
+    if let Some(aggregate) = &attrs.aggregate {
+        // An aggregate field is the source, and is converted from.
+        if let Some(from) = &attrs.from {
+            return Err(Error::new_spanned(
+                from.original,
+                "#[aggregate] already implies #[from]",
+            ));
+        }
+        attrs.from = Some(From {
+            original: aggregate.original,
+            by_ref: None,
+            kinds: Vec::new(),
+            try_into: false,
+            into: None,
+            via: Vec::new(),
+            ctor: None,
+        });
+    }
+
+    if let Some(alternate) = alternate {
+        match &mut attrs.display {
+            Some(display) if display.matching.is_none() => {
+                display.alternate = Some(Box::new(alternate));
+            }
+            Some(_) => {
+                return Err(Error::new_spanned(
+                    alternate.original,
+                    "#[error(alt = ...)] cannot be combined with #[error(match = ...)]",
+                ));
+            }
+            None => {
+                return Err(Error::new_spanned(
+                    alternate.original,
+                    "#[error(alt = ...)] requires an #[error(\"...\")] message to write without the `#` flag",
+                ));
+            }
         }
     }
 
@@ -102,29 +496,685 @@ pub fn get(input: &[Attribute]) -> Result<Attrs> {
 
 fn parse_thiserror_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Result<()> {
     syn::custom_keyword!(generics_err_as_ref);
+    syn::custom_keyword!(accessors);
+    syn::custom_keyword!(auto_transparent);
+    syn::custom_keyword!(clone);
+    syn::custom_keyword!(codes);
+    syn::custom_keyword!(constructors);
+    syn::custom_keyword!(context);
+    syn::custom_keyword!(context_setter);
+    syn::custom_keyword!(debug);
+    syn::custom_keyword!(debug_transparent);
+    syn::custom_keyword!(defmt);
+    syn::custom_keyword!(deserialize);
+    syn::custom_keyword!(discriminant_code);
+    syn::custom_keyword!(document_messages);
+    syn::custom_keyword!(downcast);
+    syn::custom_keyword!(eq);
+    syn::custom_keyword!(fields);
+    syn::custom_keyword!(hash_ignore_source);
+    syn::custom_keyword!(hide_generated);
+    syn::custom_keyword!(inner_accessors);
+    syn::custom_keyword!(kind);
+    syn::custom_keyword!(legacy_description);
+    syn::custom_keyword!(localize);
+    syn::custom_keyword!(no_error_impl);
+    syn::custom_keyword!(no_std);
+    syn::custom_keyword!(partial_eq);
+    syn::custom_keyword!(predicates);
+    syn::custom_keyword!(related);
+    syn::custom_keyword!(report);
+    syn::custom_keyword!(repr_c_codes);
+    syn::custom_keyword!(require_debug);
+    syn::custom_keyword!(result_alias);
+    syn::custom_keyword!(root_cause);
+    syn::custom_keyword!(serialize);
+    syn::custom_keyword!(setters);
+    syn::custom_keyword!(static_messages);
+    syn::custom_keyword!(ufmt);
+    syn::custom_keyword!(variant_names);
+    syn::custom_keyword!(variants);
+
+    attr.parse_args_with(|input: ParseStream| {
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(generics_err_as_ref) {
+                input.parse::<generics_err_as_ref>()?;
+                if attrs.config.generics_err_as_ref {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(generics_err_as_ref)] attribute",
+                    ));
+                }
+                attrs.config.generics_err_as_ref = true;
+            } else if lookahead.peek(accessors) {
+                let kw = input.parse::<accessors>()?;
+                if attrs.config.accessors.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(accessors)] attribute",
+                    ));
+                }
+                attrs.config.accessors = Some(kw.span);
+            } else if lookahead.peek(auto_transparent) {
+                let kw = input.parse::<auto_transparent>()?;
+                if attrs.config.auto_transparent.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(auto_transparent)] attribute",
+                    ));
+                }
+                attrs.config.auto_transparent = Some(kw.span);
+            } else if lookahead.peek(clone) {
+                let kw = input.parse::<clone>()?;
+                if attrs.config.clone.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(clone)] attribute",
+                    ));
+                }
+                input.parse::<Token![=]>()?;
+                let lit: LitStr = input.parse()?;
+                if lit.value() != "arc_sources" {
+                    return Err(Error::new_spanned(
+                        lit,
+                        "expected #[thiserror(clone = \"arc_sources\")]",
+                    ));
+                }
+                attrs.config.clone = Some(kw.span);
+            } else if lookahead.peek(Token![crate]) {
+                input.parse::<Token![crate]>()?;
+                if attrs.config.crate_path.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(crate)] attribute",
+                    ));
+                }
+                input.parse::<Token![=]>()?;
+                let lit: LitStr = input.parse()?;
+                let path: syn::Path = lit.parse().map_err(|_| {
+                    Error::new_spanned(
+                        &lit,
+                        "expected a path to thiserror, as in #[thiserror(crate = \"my_framework::thiserror\")]",
+                    )
+                })?;
+                attrs.config.crate_path = Some(path.into_token_stream());
+            } else if lookahead.peek(codes) {
+                let kw = input.parse::<codes>()?;
+                if attrs.config.codes.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(codes)] attribute",
+                    ));
+                }
+                attrs.config.codes = Some(kw.span);
+            } else if lookahead.peek(constructors) {
+                let kw = input.parse::<constructors>()?;
+                if attrs.config.constructors.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(constructors)] attribute",
+                    ));
+                }
+                attrs.config.constructors = Some(kw.span);
+            } else if lookahead.peek(context) {
+                let kw = input.parse::<context>()?;
+                if attrs.config.context.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(context)] attribute",
+                    ));
+                }
+                attrs.config.context = Some(kw.span);
+            } else if lookahead.peek(context_setter) {
+                let kw = input.parse::<context_setter>()?;
+                if attrs.config.context_setter.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(context_setter)] attribute",
+                    ));
+                }
+                attrs.config.context_setter = Some(kw.span);
+            } else if lookahead.peek(debug) {
+                let kw = input.parse::<debug>()?;
+                if attrs.config.debug.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(debug)] attribute",
+                    ));
+                }
+                attrs.config.debug = Some(kw.span);
+            } else if lookahead.peek(debug_transparent) {
+                let kw = input.parse::<debug_transparent>()?;
+                if attrs.config.debug_transparent.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(debug_transparent)] attribute",
+                    ));
+                }
+                attrs.config.debug_transparent = Some(kw.span);
+            } else if lookahead.peek(defmt) {
+                let kw = input.parse::<defmt>()?;
+                if attrs.config.defmt.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(defmt)] attribute",
+                    ));
+                }
+                attrs.config.defmt = Some(kw.span);
+            } else if lookahead.peek(deserialize) {
+                let kw = input.parse::<deserialize>()?;
+                if attrs.config.deserialize.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(deserialize)] attribute",
+                    ));
+                }
+                attrs.config.deserialize = Some(kw.span);
+            } else if lookahead.peek(discriminant_code) {
+                let kw = input.parse::<discriminant_code>()?;
+                if attrs.config.discriminant_code.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(discriminant_code)] attribute",
+                    ));
+                }
+                attrs.config.discriminant_code = Some(kw.span);
+            } else if lookahead.peek(document_messages) {
+                let kw = input.parse::<document_messages>()?;
+                if attrs.config.document_messages.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(document_messages)] attribute",
+                    ));
+                }
+                attrs.config.document_messages = Some(kw.span);
+            } else if lookahead.peek(downcast) {
+                let kw = input.parse::<downcast>()?;
+                if attrs.config.downcast.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(downcast)] attribute",
+                    ));
+                }
+                attrs.config.downcast = Some(kw.span);
+            } else if lookahead.peek(eq) {
+                let kw = input.parse::<eq>()?;
+                if attrs.config.eq.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(eq)] attribute",
+                    ));
+                }
+                attrs.config.eq = Some(kw.span);
+            } else if lookahead.peek(fields) {
+                let kw = input.parse::<fields>()?;
+                if attrs.config.fields.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(fields)] attribute",
+                    ));
+                }
+                attrs.config.fields = Some(kw.span);
+            } else if lookahead.peek(hash_ignore_source) {
+                let kw = input.parse::<hash_ignore_source>()?;
+                if attrs.config.hash_ignore_source.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(hash_ignore_source)] attribute",
+                    ));
+                }
+                attrs.config.hash_ignore_source = Some(kw.span);
+            } else if lookahead.peek(hide_generated) {
+                let kw = input.parse::<hide_generated>()?;
+                if attrs.config.hide_generated.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(hide_generated)] attribute",
+                    ));
+                }
+                attrs.config.hide_generated = Some(kw.span);
+            } else if lookahead.peek(inner_accessors) {
+                let kw = input.parse::<inner_accessors>()?;
+                if attrs.config.inner_accessors.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(inner_accessors)] attribute",
+                    ));
+                }
+                attrs.config.inner_accessors = Some(kw.span);
+            } else if lookahead.peek(kind) {
+                let kw = input.parse::<kind>()?;
+                if attrs.config.kind.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(kind)] attribute",
+                    ));
+                }
+                attrs.config.kind = Some(kw.span);
+                if input.parse::<Option<Token![=]>>()?.is_some() {
+                    let lit: LitStr = input.parse()?;
+                    attrs.config.kind_ident = Some(lit.parse()?);
+                }
+            } else if lookahead.peek(legacy_description) {
+                let kw = input.parse::<legacy_description>()?;
+                if attrs.config.legacy_description.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(legacy_description)] attribute",
+                    ));
+                }
+                attrs.config.legacy_description = Some(kw.span);
+            } else if lookahead.peek(localize) {
+                input.parse::<localize>()?;
+                if attrs.config.localize.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(localize)] attribute",
+                    ));
+                }
+                input.parse::<Token![=]>()?;
+                let path: syn::Path = input.parse().map_err(|err| {
+                    Error::new(
+                        err.span(),
+                        "expected a path to the localizer trait, as in #[thiserror(localize = crate::Localizer)]",
+                    )
+                })?;
+                attrs.config.localize = Some(path.into_token_stream());
+            } else if lookahead.peek(no_error_impl) {
+                let kw = input.parse::<no_error_impl>()?;
+                if attrs.config.no_error_impl.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(no_error_impl)] attribute",
+                    ));
+                }
+                attrs.config.no_error_impl = Some(kw.span);
+            } else if lookahead.peek(no_std) {
+                let kw = input.parse::<no_std>()?;
+                if attrs.config.no_std.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(no_std)] attribute",
+                    ));
+                }
+                attrs.config.no_std = Some(kw.span);
+            } else if lookahead.peek(partial_eq) {
+                let kw = input.parse::<partial_eq>()?;
+                if attrs.config.partial_eq.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(partial_eq)] attribute",
+                    ));
+                }
+                attrs.config.partial_eq = Some(kw.span);
+                if input.parse::<Option<Token![=]>>()?.is_some() {
+                    let lit: LitStr = input.parse()?;
+                    if lit.value() != "message" {
+                        return Err(Error::new_spanned(
+                            lit,
+                            "expected #[thiserror(partial_eq = \"message\")]",
+                        ));
+                    }
+                    attrs.config.partial_eq_message = true;
+                }
+            } else if lookahead.peek(predicates) {
+                let kw = input.parse::<predicates>()?;
+                if attrs.config.predicates.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(predicates)] attribute",
+                    ));
+                }
+                attrs.config.predicates = Some(kw.span);
+            } else if lookahead.peek(related) {
+                let kw = input.parse::<related>()?;
+                if attrs.config.related.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(related)] attribute",
+                    ));
+                }
+                attrs.config.related = Some(kw.span);
+            } else if lookahead.peek(report) {
+                let kw = input.parse::<report>()?;
+                if attrs.config.report.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(report)] attribute",
+                    ));
+                }
+                attrs.config.report = Some(kw.span);
+            } else if lookahead.peek(repr_c_codes) {
+                let kw = input.parse::<repr_c_codes>()?;
+                if attrs.config.repr_c_codes.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(repr_c_codes)] attribute",
+                    ));
+                }
+                attrs.config.repr_c_codes = Some(kw.span);
+            } else if lookahead.peek(require_debug) {
+                let kw = input.parse::<require_debug>()?;
+                if attrs.config.require_debug.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(require_debug)] attribute",
+                    ));
+                }
+                attrs.config.require_debug = Some(kw.span);
+            } else if lookahead.peek(result_alias) {
+                let kw = input.parse::<result_alias>()?;
+                if attrs.config.result_alias.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(result_alias)] attribute",
+                    ));
+                }
+                attrs.config.result_alias = Some(kw.span);
+                if input.parse::<Option<Token![=]>>()?.is_some() {
+                    let lit: LitStr = input.parse()?;
+                    attrs.config.result_alias_ident = Some(lit.parse()?);
+                }
+            } else if lookahead.peek(root_cause) {
+                let kw = input.parse::<root_cause>()?;
+                if attrs.config.root_cause.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(root_cause)] attribute",
+                    ));
+                }
+                attrs.config.root_cause = Some(kw.span);
+            } else if lookahead.peek(serialize) {
+                let kw = input.parse::<serialize>()?;
+                if attrs.config.serialize.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(serialize)] attribute",
+                    ));
+                }
+                attrs.config.serialize = Some(kw.span);
+            } else if lookahead.peek(setters) {
+                let kw = input.parse::<setters>()?;
+                if attrs.config.setters.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(setters)] attribute",
+                    ));
+                }
+                attrs.config.setters = Some(kw.span);
+            } else if lookahead.peek(static_messages) {
+                let kw = input.parse::<static_messages>()?;
+                if attrs.config.static_messages.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(static_messages)] attribute",
+                    ));
+                }
+                attrs.config.static_messages = Some(kw.span);
+            } else if lookahead.peek(ufmt) {
+                let kw = input.parse::<ufmt>()?;
+                if attrs.config.ufmt.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(ufmt)] attribute",
+                    ));
+                }
+                attrs.config.ufmt = Some(kw.span);
+            } else if lookahead.peek(variant_names) {
+                let kw = input.parse::<variant_names>()?;
+                if attrs.config.variant_names.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(variant_names)] attribute",
+                    ));
+                }
+                attrs.config.variant_names = Some(kw.span);
+            } else if lookahead.peek(variants) {
+                let kw = input.parse::<variants>()?;
+                if attrs.config.variants.is_some() {
+                    return Err(Error::new_spanned(
+                        attr,
+                        "duplicate #[thiserror(variants)] attribute",
+                    ));
+                }
+                attrs.config.variants = Some(kw.span);
+            } else {
+                return Err(lookahead.error());
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(())
+    })
+}
+
+fn parse_error_attribute<'a>(
+    attrs: &mut Attrs<'a>,
+    alternate: &mut Option<Display<'a>>,
+    attr: &'a Attribute,
+) -> Result<()> {
+    syn::custom_keyword!(alt);
+    syn::custom_keyword!(bound);
+    syn::custom_keyword!(code);
+    syn::custom_keyword!(constructor);
+    syn::custom_keyword!(display_bound);
+    syn::custom_keyword!(error_bound);
+    syn::custom_keyword!(exit_code);
+    syn::custom_keyword!(kind);
+    syn::custom_keyword!(level);
+    syn::custom_keyword!(name);
+    syn::custom_keyword!(no_bound);
+    syn::custom_keyword!(serialize);
+    syn::custom_keyword!(transparent);
+    syn::custom_keyword!(transparent_str);
 
     attr.parse_args_with(|input: ParseStream| {
-        if let Some(_kw) = input.parse::<Option<generics_err_as_ref>>()? {
-            if attrs.config.generics_err_as_ref {
+        let (display, error) = if input.peek(bound) && input.peek2(token::Paren) {
+            input.parse::<bound>()?;
+            (true, true)
+        } else if input.peek(display_bound) && input.peek2(token::Paren) {
+            input.parse::<display_bound>()?;
+            (true, false)
+        } else if input.peek(error_bound) && input.peek2(token::Paren) {
+            input.parse::<error_bound>()?;
+            (false, true)
+        } else {
+            (false, false)
+        };
+        if display || error {
+            let content;
+            parenthesized!(content in input);
+            let mut predicates = Vec::new();
+            for predicate in content.parse_terminated::<_, Token![,]>(WherePredicate::parse)? {
+                match predicate {
+                    WherePredicate::Type(predicate) => predicates.push(predicate),
+                    predicate => {
+                        return Err(Error::new_spanned(
+                            predicate,
+                            "expected a bound on a type parameter, like `T: Trait`",
+                        ));
+                    }
+                }
+            }
+            attrs.bounds.push(Bound {
+                original: attr,
+                display,
+                error,
+                predicates,
+            });
+            return Ok(());
+        }
+
+        if input.peek(alt) && input.peek2(Token![=]) {
+            input.parse::<alt>()?;
+            input.parse::<Token![=]>()?;
+            let display = Display {
+                original: attr,
+                fmt: input.parse()?,
+                args: parse_token_expr(input, false)?,
+                has_bonus_display: false,
+                implied_bounds: Set::new(),
+                leaf_bounds: Vec::new(),
+                opaque_paths: Vec::new(),
+                defmt: None,
+                ufmt: None,
+                matching: None,
+                alternate: None,
+            };
+            if alternate.is_some() {
                 return Err(Error::new_spanned(
                     attr,
-                    "duplicate #[thiserror(generics_err_as_ref)] attribute",
+                    "duplicate #[error(alt = ...)] attribute",
                 ));
             }
+            *alternate = Some(display);
+            return Ok(());
+        }
 
-            attrs.config.generics_err_as_ref = true;
+        if input.peek(code) && input.peek2(Token![=]) {
+            input.parse::<code>()?;
+            input.parse::<Token![=]>()?;
+            let expr: Expr = input.parse()?;
+            if attrs.code.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(code = ...)] attribute",
+                ));
+            }
+            attrs.code = Some(Code {
+                original: attr,
+                expr,
+            });
+            return Ok(());
+        }
 
+        if input.peek(constructor) && input.peek2(Token![=]) {
+            input.parse::<constructor>()?;
+            input.parse::<Token![=]>()?;
+            let lit: LitStr = input.parse()?;
+            let ident: Ident = lit.parse()?;
+            if attrs.constructor.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(constructor = ...)] attribute",
+                ));
+            }
+            attrs.constructor = Some(Constructor {
+                original: attr,
+                ident,
+            });
             return Ok(());
         }
 
-        Ok(())
-    })
-}
+        if input.peek(exit_code) && input.peek2(Token![=]) {
+            input.parse::<exit_code>()?;
+            input.parse::<Token![=]>()?;
+            let lit: LitInt = input.parse()?;
+            let value = lit
+                .base10_parse::<u8>()
+                .map_err(|_| Error::new_spanned(&lit, "expected an exit code from 0 to 255"))?;
+            if attrs.exit_code.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(exit_code = ...)] attribute",
+                ));
+            }
+            attrs.exit_code = Some(ExitCode {
+                original: attr,
+                value,
+            });
+            return Ok(());
+        }
 
-fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Result<()> {
-    syn::custom_keyword!(transparent);
+        if input.peek(kind) && input.peek2(Token![=]) {
+            input.parse::<kind>()?;
+            input.parse::<Token![=]>()?;
+            let ident: Ident = input.parse()?;
+            if attrs.kind.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(kind = ...)] attribute",
+                ));
+            }
+            attrs.kind = Some(Kind {
+                original: attr,
+                ident,
+            });
+            return Ok(());
+        }
+
+        if input.peek(level) && input.peek2(Token![=]) {
+            input.parse::<level>()?;
+            input.parse::<Token![=]>()?;
+            let lit: LitStr = input.parse()?;
+            let name = match lit.value().to_ascii_lowercase().as_str() {
+                "trace" => "TRACE",
+                "debug" => "DEBUG",
+                "info" => "INFO",
+                "warn" => "WARN",
+                "error" => "ERROR",
+                _ => {
+                    return Err(Error::new_spanned(
+                        lit,
+                        "expected one of \"trace\", \"debug\", \"info\", \"warn\", \"error\"",
+                    ));
+                }
+            };
+            if attrs.level.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(level = ...)] attribute",
+                ));
+            }
+            attrs.level = Some(Level {
+                original: attr,
+                ident: Ident::new(name, lit.span()),
+            });
+            return Ok(());
+        }
+
+        if input.peek(name) && input.peek2(Token![=]) {
+            input.parse::<name>()?;
+            input.parse::<Token![=]>()?;
+            let value: LitStr = input.parse()?;
+            if attrs.name.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(name = ...)] attribute",
+                ));
+            }
+            attrs.name = Some(Name {
+                original: attr,
+                value,
+            });
+            return Ok(());
+        }
+
+        if input.parse::<Option<no_bound>>()?.is_some() {
+            if attrs.no_bound.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(no_bound)] attribute",
+                ));
+            }
+            attrs.no_bound = Some(attr);
+            return Ok(());
+        }
+
+        if input.parse::<Option<serialize>>()?.is_some() {
+            if attrs.serialize.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(serialize)] attribute",
+                ));
+            }
+            attrs.serialize = Some(attr);
+            return Ok(());
+        }
 
-    attr.parse_args_with(|input: ParseStream| {
         if let Some(kw) = input.parse::<Option<transparent>>()? {
             if attrs.transparent.is_some() {
                 return Err(Error::new_spanned(
@@ -132,6 +1182,12 @@ fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Resu
                     "duplicate #[error(transparent)] attribute",
                 ));
             }
+            if !input.is_empty() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "#[error(transparent)] takes no format string or arguments",
+                ));
+            }
             attrs.transparent = Some(Transparent {
                 original: attr,
                 span: kw.span,
@@ -139,12 +1195,39 @@ fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Resu
             return Ok(());
         }
 
-        let display = Display {
-            original: attr,
-            fmt: input.parse()?,
-            args: parse_token_expr(input, false)?,
-            has_bonus_display: false,
-            implied_bounds: Set::new(),
+        if input.parse::<Option<transparent_str>>()?.is_some() {
+            if attrs.transparent_str.is_some() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "duplicate #[error(transparent_str)] attribute",
+                ));
+            }
+            if !input.is_empty() {
+                return Err(Error::new_spanned(
+                    attr,
+                    "#[error(transparent_str)] takes no format string or arguments",
+                ));
+            }
+            attrs.transparent_str = Some(attr);
+            return Ok(());
+        }
+
+        let display = if input.peek(Token![match]) && input.peek2(Token![=]) {
+            parse_display_match(attr, input)?
+        } else {
+            Display {
+                original: attr,
+                fmt: input.parse()?,
+                args: parse_token_expr(input, false)?,
+                has_bonus_display: false,
+                implied_bounds: Set::new(),
+                leaf_bounds: Vec::new(),
+                opaque_paths: Vec::new(),
+                defmt: None,
+                ufmt: None,
+                matching: None,
+                alternate: None,
+            }
         };
         if attrs.display.is_some() {
             return Err(Error::new_spanned(
@@ -157,6 +1240,360 @@ fn parse_error_attribute<'a>(attrs: &mut Attrs<'a>, attr: &'a Attribute) -> Resu
     })
 }
 
+fn parse_display_match<'a>(attr: &'a Attribute, input: ParseStream) -> Result<Display<'a>> {
+    let kw = input.parse::<Token![match]>()?;
+    input.parse::<Token![=]>()?;
+    input.parse::<Option<Token![self]>>()?;
+    input.parse::<Token![.]>()?;
+    let member: Member = input.parse()?;
+
+    let mut arms = Vec::new();
+    while !input.is_empty() {
+        input.parse::<Token![,]>()?;
+        if input.is_empty() {
+            break;
+        }
+        let mut pat = TokenStream::new();
+        while !input.peek(Token![=>]) {
+            if input.is_empty() {
+                return Err(input.error("expected `=>` after the pattern"));
+            }
+            pat.extend(iter::once(input.parse::<TokenTree>()?));
+        }
+        input.parse::<Token![=>]>()?;
+        let display = Display {
+            original: attr,
+            fmt: input.parse()?,
+            args: TokenStream::new(),
+            has_bonus_display: false,
+            implied_bounds: Set::new(),
+            leaf_bounds: Vec::new(),
+            opaque_paths: Vec::new(),
+            defmt: None,
+            ufmt: None,
+            matching: None,
+            alternate: None,
+        };
+        arms.push((pat, display));
+    }
+    if arms.is_empty() {
+        return Err(Error::new(
+            kw.span,
+            "expected at least one `pattern => \"message\"` arm",
+        ));
+    }
+
+    Ok(Display {
+        original: attr,
+        fmt: LitStr::new("", kw.span),
+        args: TokenStream::new(),
+        has_bonus_display: false,
+        implied_bounds: Set::new(),
+        leaf_bounds: Vec::new(),
+        opaque_paths: Vec::new(),
+        defmt: None,
+        ufmt: None,
+        matching: Some(DisplayMatch { member, arms }),
+        alternate: None,
+    })
+}
+
+fn parse_source_attribute(attr: &Attribute) -> Result<Source<'_>> {
+    syn::custom_keyword!(deref);
+    syn::custom_keyword!(err_as_ref);
+    syn::custom_keyword!(no_bound);
+    syn::custom_keyword!(with);
+    syn::custom_keyword!(wrap);
+
+    let mut source = Source {
+        original: attr,
+        wrap: false,
+        deref: false,
+        err_as_ref: None,
+        no_bound: false,
+        with: None,
+    };
+    if attr.tokens.is_empty() {
+        return Ok(source);
+    }
+
+    attr.parse_args_with(|input: ParseStream| {
+        loop {
+            if input.peek(wrap) && !source.wrap && !source.deref {
+                input.parse::<wrap>()?;
+                source.wrap = true;
+            } else if input.peek(deref) && !source.deref && !source.wrap {
+                input.parse::<deref>()?;
+                source.deref = true;
+            } else if input.peek(err_as_ref) && source.err_as_ref.is_none() {
+                input.parse::<err_as_ref>()?;
+                let value = if input.peek(Token![=]) {
+                    input.parse::<Token![=]>()?;
+                    input.parse::<LitBool>()?.value
+                } else {
+                    true
+                };
+                source.err_as_ref = Some(value);
+            } else if input.peek(no_bound) && !source.no_bound {
+                input.parse::<no_bound>()?;
+                source.no_bound = true;
+            } else if input.peek(with) && source.with.is_none() {
+                input.parse::<with>()?;
+                input.parse::<Token![=]>()?;
+                let mut tokens = TokenStream::new();
+                while !input.is_empty() && !input.peek(Token![,]) {
+                    tokens.extend(iter::once(input.parse::<TokenTree>()?));
+                }
+                if tokens.is_empty() {
+                    return Err(input.error("expected an expression"));
+                }
+                source.with = Some(tokens);
+            } else {
+                return Err(input.error("unexpected #[source] option"));
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+        }
+        Ok(source)
+    })
+}
+
+fn parse_provide_attribute(attr: &Attribute) -> Result<Provide<'_>> {
+    syn::custom_keyword!(value);
+
+    let mut provide = Provide {
+        original: attr,
+        value: false,
+    };
+    if attr.tokens.is_empty() {
+        return Ok(provide);
+    }
+
+    attr.parse_args_with(|input: ParseStream| {
+        input.parse::<value>()?;
+        provide.value = true;
+        Ok(provide)
+    })
+}
+
+fn parse_backtrace_attribute(attr: &Attribute) -> Result<Backtrace<'_>> {
+    syn::custom_keyword!(disabled_unless);
+    syn::custom_keyword!(force);
+    syn::custom_keyword!(from_source);
+
+    let mut backtrace = Backtrace {
+        original: attr,
+        force: false,
+        from_source: false,
+        disabled_unless: None,
+    };
+    if attr.tokens.is_empty() {
+        return Ok(backtrace);
+    }
+
+    attr.parse_args_with(|input: ParseStream| {
+        loop {
+            if input.peek(force) && !backtrace.force {
+                input.parse::<force>()?;
+                backtrace.force = true;
+            } else if input.peek(disabled_unless) && backtrace.disabled_unless.is_none() {
+                input.parse::<disabled_unless>()?;
+                input.parse::<Token![=]>()?;
+                backtrace.disabled_unless = Some(input.parse()?);
+            } else if input.peek(from_source) && !backtrace.from_source {
+                input.parse::<from_source>()?;
+                backtrace.from_source = true;
+            } else {
+                return Err(input.error("unexpected #[backtrace] option"));
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+        }
+        Ok(backtrace)
+    })
+}
+
+fn parse_aggregate_attribute(attr: &Attribute) -> Result<Aggregate<'_>> {
+    syn::custom_keyword!(max);
+    syn::custom_keyword!(separator);
+
+    let mut aggregate = Aggregate {
+        original: attr,
+        separator: None,
+        max: None,
+    };
+    if attr.tokens.is_empty() {
+        return Ok(aggregate);
+    }
+
+    attr.parse_args_with(|input: ParseStream| {
+        while !input.is_empty() {
+            let lookahead = input.lookahead1();
+            if lookahead.peek(separator) && aggregate.separator.is_none() {
+                input.parse::<separator>()?;
+                input.parse::<Token![=]>()?;
+                aggregate.separator = Some(input.parse()?);
+            } else if lookahead.peek(max) && aggregate.max.is_none() {
+                input.parse::<max>()?;
+                input.parse::<Token![=]>()?;
+                let max: LitInt = input.parse()?;
+                max.base10_parse::<usize>()?;
+                aggregate.max = Some(max);
+            } else {
+                return Err(lookahead.error());
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(aggregate)
+    })
+}
+
+fn parse_from_attribute(attr: &Attribute) -> Result<Option<From<'_>>> {
+    syn::custom_keyword!(ctor);
+    syn::custom_keyword!(into);
+    syn::custom_keyword!(kind);
+    syn::custom_keyword!(try_into);
+    syn::custom_keyword!(via);
+    syn::custom_keyword!(with);
+
+    let mut from = From {
+        original: attr,
+        by_ref: None,
+        kinds: Vec::new(),
+        try_into: false,
+        into: None,
+        via: Vec::new(),
+        ctor: None,
+    };
+    if attr.tokens.is_empty() {
+        return Ok(Some(from));
+    }
+
+    attr.parse_args_with(|input: ParseStream| {
+        if !input.peek(Token![ref])
+            && !input.peek(kind)
+            && !input.peek(try_into)
+            && !input.peek(into)
+            && !input.peek(via)
+            && !input.peek(ctor)
+        {
+            input.parse::<TokenStream>()?;
+            return Ok(None);
+        }
+        loop {
+            if input.peek(Token![ref]) && from.by_ref.is_none() {
+                input.parse::<Token![ref]>()?;
+                from.by_ref = Some(FromRef { with: None });
+            } else if input.peek(with) && from.by_ref.is_some() {
+                input.parse::<with>()?;
+                input.parse::<Token![=]>()?;
+                from.by_ref.as_mut().unwrap().with = Some(input.parse()?);
+            } else if input.peek(kind) && from.kinds.is_empty() {
+                input.parse::<kind>()?;
+                input.parse::<Token![=]>()?;
+                loop {
+                    from.kinds.push(input.parse()?);
+                    if input.parse::<Option<Token![|]>>()?.is_none() {
+                        break;
+                    }
+                }
+            } else if input.peek(try_into) && !from.try_into {
+                input.parse::<try_into>()?;
+                from.try_into = true;
+            } else if input.peek(into) && from.into.is_none() {
+                input.parse::<into>()?;
+                input.parse::<Token![=]>()?;
+                from.into = Some(input.parse()?);
+            } else if input.peek(via) && from.via.is_empty() {
+                input.parse::<via>()?;
+                input.parse::<Token![=]>()?;
+                loop {
+                    from.via.push(input.parse()?);
+                    if input.parse::<Option<Token![|]>>()?.is_none() {
+                        break;
+                    }
+                }
+            } else if input.peek(ctor) && from.ctor.is_none() {
+                input.parse::<ctor>()?;
+                input.parse::<Token![=]>()?;
+                let mut tokens = TokenStream::new();
+                while !input.is_empty() && !input.peek(Token![,]) {
+                    tokens.extend(iter::once(input.parse::<TokenTree>()?));
+                }
+                if tokens.is_empty() {
+                    return Err(input.error("expected a closure or function"));
+                }
+                from.ctor = Some(tokens);
+            } else {
+                return Err(input.error("unexpected #[from] option"));
+            }
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+        }
+        Ok(Some(from))
+    })
+}
+
+fn parse_flatten_attribute(attr: &Attribute) -> Result<Flatten<'_>> {
+    let mut flatten = Flatten {
+        original: attr,
+        types: Vec::new(),
+    };
+    if attr.tokens.is_empty() {
+        return Ok(flatten);
+    }
+
+    attr.parse_args_with(|input: ParseStream| {
+        loop {
+            flatten.types.push(input.parse()?);
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+        }
+        Ok(flatten)
+    })
+}
+
+fn parse_try_from_attribute(attr: &Attribute) -> Result<TryFrom<'_>> {
+    syn::custom_keyword!(with);
+
+    attr.parse_args_with(|input: ParseStream| {
+        let ty: Type = input.parse()?;
+        input.parse::<Token![,]>()?;
+        input.parse::<with>()?;
+        input.parse::<Token![=]>()?;
+        let with: Path = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(TryFrom {
+            original: attr,
+            ty,
+            with,
+        })
+    })
+}
+
 fn parse_token_expr(input: ParseStream, mut begin_expr: bool) -> Result<TokenStream> {
     let mut tokens = Vec::new();
     while !input.is_empty() {
@@ -189,6 +1626,8 @@ fn parse_token_expr(input: ParseStream, mut begin_expr: bool) -> Result<TokenStr
             || input.peek(Token![!])
             || input.peek(Token![^])
             || input.peek(Token![,])
+            || input.peek(Token![:])
+            || input.peek(Token![..])
             || input.peek(Token![/])
             || input.peek(Token![=])
             || input.peek(Token![>])
@@ -233,19 +1672,88 @@ fn require_empty_attribute(attr: &Attribute) -> Result<()> {
     Ok(())
 }
 
-impl ToTokens for Display<'_> {
+impl Display<'_> {
+    pub fn fmt_tokens(&self, std: &TokenStream) -> TokenStream {
+        if let Some(matching) = &self.matching {
+            let local = match &matching.member {
+                Member::Named(ident) => ident.clone(),
+                Member::Unnamed(index) => format_ident!("_{}", index, span = index.span),
+            };
+            let pats = matching.arms.iter().map(|(pat, _)| pat);
+            let displays = matching
+                .arms
+                .iter()
+                .map(|(_, display)| display.fmt_tokens(std));
+            return quote! {
+                match #local {
+                    #(#pats => #displays,)*
+                }
+            };
+        }
+        let fmt = &self.fmt;
+        let args = &self.args;
+        let write = quote! {
+            #std::write!(__formatter, #fmt #args)
+        };
+        match &self.alternate {
+            Some(alternate) => {
+                let alternate = alternate.fmt_tokens(std);
+                quote! {
+                    if __formatter.alternate() {
+                        #alternate
+                    } else {
+                        #write
+                    }
+                }
+            }
+            None => write,
+        }
+    }
+}
+
+impl DefmtMessage {
+    pub fn fmt_tokens(&self, thiserror: &TokenStream) -> TokenStream {
+        let fmt = &self.fmt;
+        let args = &self.args;
+        // Reports a field without a Format impl at the field itself rather
+        // than somewhere inside defmt::write!.
+        let checks = args.iter().map(|arg| {
+            let span = arg.clone().into_iter().last().unwrap().span();
+            quote_spanned!(span=> #thiserror::__private::require_format(&#arg);)
+        });
+        quote! {
+            {
+                #(#checks)*
+                defmt::write!(__formatter, #fmt #(, #args)*)
+            }
+        }
+    }
+}
+
+impl ToTokens for UfmtMessage {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let fmt = &self.fmt;
         let args = &self.args;
         tokens.extend(quote! {
-            write!(__formatter, #fmt #args)
+            ufmt::uwrite!(__formatter, #fmt #(, #args)*)
         });
     }
 }
 
-impl ToTokens for Trait {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
+impl UfmtTrait {
+    pub fn path(self, thiserror: &TokenStream) -> TokenStream {
+        let trait_name = match self {
+            UfmtTrait::Display => quote!(uDisplay),
+            UfmtTrait::Debug => quote!(uDebug),
+            UfmtTrait::DisplayHex => quote!(uDisplayHex),
+        };
+        quote!(#thiserror::__private::ufmt::#trait_name)
+    }
+}
+
+impl Trait {
+    pub fn path(self, std: &TokenStream) -> TokenStream {
         let trait_name = format_ident!("{}", format!("{:?}", self));
-        tokens.extend(quote!(std::fmt::#trait_name));
+        quote!(#std::fmt::#trait_name)
     }
 }
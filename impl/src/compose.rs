@@ -0,0 +1,339 @@
+use crate::expand;
+use proc_macro2::{Delimiter, Group, Punct, Spacing, TokenStream, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+use std::mem;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    braced, parenthesized, parse_quote, token, Attribute, Data, DeriveInput, Error, Ident, LitStr,
+    Result, Token, Type, Variant, Visibility,
+};
+
+mod kw {
+    syn::custom_keyword!(from);
+}
+
+// Keep in sync with the helper attributes of #[proc_macro_derive(Error)]. The
+// derive input built below uses them, but the enum handed back to the compiler
+// is not under the derive, so they must not appear on it.
+const HELPER_ATTRIBUTES: &[&str] = &[
+    "thiserror",
+    "aggregate",
+    "backtrace",
+    "error",
+    "flatten",
+    "from",
+    "provide",
+    "redact",
+    "related",
+    "sensitive",
+    "source",
+    "span_trace",
+    "try_from",
+];
+
+pub struct Compose {
+    attrs: Vec<Attribute>,
+    vis: Visibility,
+    enum_token: Token![enum],
+    ident: Ident,
+    members: Punctuated<Member, Token![,]>,
+    variants: Punctuated<Variant, Token![,]>,
+}
+
+struct Member {
+    ty: Type,
+    name: Option<Ident>,
+}
+
+impl Parse for Compose {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let attrs = input.call(Attribute::parse_outer)?;
+        let vis: Visibility = input.parse()?;
+        let enum_token: Token![enum] = input.parse()?;
+        let ident: Ident = input.parse()?;
+        input.parse::<kw::from>()?;
+
+        let content;
+        let paren_token = parenthesized!(content in input);
+        let members = content.parse_terminated(Member::parse)?;
+        if members.is_empty() {
+            return Err(Error::new(
+                paren_token.span,
+                "expected at least one error type to compose",
+            ));
+        }
+
+        let variants = if input.peek(token::Brace) {
+            let content;
+            braced!(content in input);
+            content.parse_terminated(Variant::parse)?
+        } else {
+            input.parse::<Token![;]>()?;
+            Punctuated::new()
+        };
+
+        Ok(Compose {
+            attrs,
+            vis,
+            enum_token,
+            ident,
+            members,
+            variants,
+        })
+    }
+}
+
+impl Parse for Member {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let ty: Type = input.parse()?;
+        let name = if input.peek(Token![as]) {
+            input.parse::<Token![as]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Member { ty, name })
+    }
+}
+
+pub fn expand(input: &Compose) -> Result<TokenStream> {
+    let mut names: Vec<Ident> = input.variants.iter().map(|v| v.ident.clone()).collect();
+    let mut variants = Vec::new();
+    for member in &input.members {
+        let name = match &member.name {
+            Some(name) => name.clone(),
+            None => variant_name(&member.ty)?,
+        };
+        if names.contains(&name) {
+            let msg = format!(
+                "a variant named `{}` already exists; name this one with `as`",
+                name,
+            );
+            return Err(Error::new_spanned(&member.ty, msg));
+        }
+        let ty = &member.ty;
+        variants.push(quote_spanned! {ty.span()=>
+            #[error(transparent)]
+            #name(#[from] #ty)
+        });
+        names.push(name);
+    }
+
+    let attrs = &input.attrs;
+    let vis = &input.vis;
+    let enum_token = &input.enum_token;
+    let ident = &input.ident;
+    let extra = &input.variants;
+    let item = quote! {
+        #(#attrs)*
+        #vis #enum_token #ident {
+            #(#variants,)*
+            #extra
+        }
+    };
+
+    let mut node: DeriveInput = syn::parse2(item)?;
+    let document_messages = take_document_messages(&mut node.attrs);
+    // The enum is emitted even if the derive fails, so that code naming it does
+    // not pile more errors on top.
+    let derived = expand::derive(&node).unwrap_or_else(|err| err.to_compile_error());
+    if document_messages {
+        document_messages_of(&mut node);
+    }
+    strip_helper_attributes(&mut node);
+    Ok(quote! {
+        #node
+        #derived
+    })
+}
+
+// Names the variant after the module of a type called `Error`, as in
+// `storage::Error` -> `Storage`, and otherwise after the type itself less any
+// `Error` suffix, as in `ParseIntError` -> `ParseInt`.
+fn variant_name(ty: &Type) -> Result<Ident> {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => {
+            return Err(Error::new_spanned(
+                ty,
+                "cannot name a variant after this type; name it with `as`",
+            ))
+        }
+    };
+    let segments: Vec<&Ident> = path.segments.iter().map(|segment| &segment.ident).collect();
+    let last = segments[segments.len() - 1];
+    let last_name = unraw(last);
+    let name = if last_name == "Error" {
+        match segments.len().checked_sub(2).map(|i| segments[i]) {
+            Some(module) if !is_path_keyword(module) => upper_camel_case(&unraw(module)),
+            _ => String::new(),
+        }
+    } else if last_name.ends_with("Error") && last_name.len() > "Error".len() {
+        last_name[..last_name.len() - "Error".len()].to_owned()
+    } else {
+        last_name
+    };
+    if name.is_empty() {
+        return Err(Error::new_spanned(
+            ty,
+            "cannot name a variant after this type; name it with `as`",
+        ));
+    }
+    Ok(Ident::new(&name, last.span()))
+}
+
+fn unraw(ident: &Ident) -> String {
+    let name = ident.to_string();
+    if name.starts_with("r#") {
+        name[2..].to_owned()
+    } else {
+        name
+    }
+}
+
+fn is_path_keyword(ident: &Ident) -> bool {
+    ident == "self" || ident == "super" || ident == "crate"
+}
+
+fn upper_camel_case(name: &str) -> String {
+    let mut camel = String::new();
+    for word in name.split('_') {
+        let mut chars = word.chars();
+        if let Some(first) = chars.next() {
+            camel.extend(first.to_uppercase());
+            camel.push_str(chars.as_str());
+        }
+    }
+    camel
+}
+
+// Removes `document_messages` from the #[thiserror(...)] attributes, which the
+// derive would reject since only here can the documentation of the enum be
+// changed.
+fn take_document_messages(attrs: &mut Vec<Attribute>) -> bool {
+    let mut found = false;
+    for attr in mem::replace(attrs, Vec::new()) {
+        let group = match attr.tokens.clone().into_iter().next() {
+            Some(TokenTree::Group(group))
+                if attr.path.is_ident("thiserror")
+                    && group.delimiter() == Delimiter::Parenthesis =>
+            {
+                group
+            }
+            _ => {
+                attrs.push(attr);
+                continue;
+            }
+        };
+        let mut kept = Vec::new();
+        for option in split_options(group.stream()) {
+            match option.as_slice() {
+                [TokenTree::Ident(ident)] if ident == "document_messages" => found = true,
+                _ => {
+                    if !kept.is_empty() {
+                        kept.push(TokenTree::Punct(Punct::new(',', Spacing::Alone)));
+                    }
+                    kept.extend(option);
+                }
+            }
+        }
+        if !kept.is_empty() {
+            let mut options = Group::new(Delimiter::Parenthesis, kept.into_iter().collect());
+            options.set_span(group.span());
+            attrs.push(Attribute {
+                tokens: options.into_token_stream(),
+                ..attr
+            });
+        }
+    }
+    found
+}
+
+// The comma separated options of an attribute, without the commas.
+fn split_options(tokens: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut options = vec![Vec::new()];
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == ',' => options.push(Vec::new()),
+            _ => options.last_mut().unwrap().push(token),
+        }
+    }
+    options.retain(|option| !option.is_empty());
+    options
+}
+
+// #[thiserror(document_messages)]: appends the message template of the enum and
+// of each variant to its documentation, unless the same line is already there.
+fn document_messages_of(node: &mut DeriveInput) {
+    document(&mut node.attrs);
+    if let Data::Enum(data) = &mut node.data {
+        for variant in &mut data.variants {
+            document(&mut variant.attrs);
+        }
+    }
+}
+
+fn document(attrs: &mut Vec<Attribute>) {
+    let line = match attrs.iter().find_map(message_doc) {
+        Some(line) => line,
+        None => return,
+    };
+    let mut has_docs = false;
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("doc")) {
+        has_docs = true;
+        if let Ok(syn::Meta::NameValue(meta)) = attr.parse_meta() {
+            if let syn::Lit::Str(doc) = meta.lit {
+                if doc.value().trim() == line.trim() {
+                    return;
+                }
+            }
+        }
+    }
+    if has_docs {
+        attrs.push(parse_quote!(#[doc = ""]));
+    }
+    attrs.push(parse_quote!(#[doc = #line]));
+}
+
+// The doc line for #[error("...")] or #[error(transparent)].
+fn message_doc(attr: &Attribute) -> Option<String> {
+    if !attr.path.is_ident("error") {
+        return None;
+    }
+    let group = match attr.tokens.clone().into_iter().next()? {
+        TokenTree::Group(group) => group,
+        _ => return None,
+    };
+    match group.stream().into_iter().next()? {
+        TokenTree::Literal(literal) => {
+            let template: LitStr = syn::parse2(TokenTree::Literal(literal).into()).ok()?;
+            Some(format!(" Display: \"{}\"", template.value()))
+        }
+        TokenTree::Ident(ident) if ident == "transparent" => {
+            Some(" Display: delegates to the inner error".to_owned())
+        }
+        _ => None,
+    }
+}
+
+fn strip_helper_attributes(node: &mut DeriveInput) {
+    strip(&mut node.attrs);
+    if let Data::Enum(data) = &mut node.data {
+        for variant in &mut data.variants {
+            strip(&mut variant.attrs);
+            for field in &mut variant.fields {
+                strip(&mut field.attrs);
+            }
+        }
+    }
+}
+
+fn strip(attrs: &mut Vec<Attribute>) {
+    attrs.retain(|attr| {
+        !HELPER_ATTRIBUTES
+            .iter()
+            .any(|helper| attr.path.is_ident(helper))
+    });
+}
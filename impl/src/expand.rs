@@ -1,12 +1,15 @@
-use crate::ast::{Enum, Field, Input, Struct};
-use crate::attr::Trait;
-use crate::generics::InferredBounds;
-use proc_macro2::TokenStream;
+use crate::ast::{Enum, Field, Input, Struct, Variant};
+use crate::attr::{Attrs, Bound, Config, FromRef, Trait, TryFrom, UfmtTrait};
+use crate::generics::{type_is_phantom_data, InferredBounds};
+use proc_macro2::{Span, TokenStream};
 use quote::{format_ident, quote, quote_spanned, ToTokens};
 use std::collections::BTreeSet as Set;
+use std::iter;
+use syn::ext::IdentExt;
 use syn::spanned::Spanned;
 use syn::{
-    Data, DeriveInput, GenericArgument, Member, PathArguments, Result, Token, Type, Visibility,
+    parse_quote, Data, DeriveInput, Expr, GenericArgument, GenericParam, Generics, Ident, Index,
+    Member, PathArguments, PredicateType, Result, Token, Type, Visibility, WhereClause,
 };
 
 pub fn derive(node: &DeriveInput) -> Result<TokenStream> {
@@ -14,122 +17,273 @@ pub fn derive(node: &DeriveInput) -> Result<TokenStream> {
 
     input.validate()?;
 
-    Ok(match input {
-        Input::Struct(struct_input) => impl_struct(struct_input),
-        Input::Enum(enum_input) => impl_enum(enum_input),
-    })
+    let config = match &input {
+        Input::Struct(input) => &input.attrs.config,
+        Input::Enum(input) => &input.attrs.config,
+    };
+    // Every generated path into the standard library is absolute, so that a
+    // `mod std` or `mod core` of the user's cannot get in the way. Under no_std
+    // they go through core instead, to core::error::Error and friends.
+    let std = if config.no_std.is_some() {
+        quote!(::core)
+    } else {
+        quote!(::std)
+    };
+    // Paths into thiserror resolve through whatever the user has in scope as
+    // `thiserror`, unless the error names its path with #[thiserror(crate)].
+    let thiserror = match &config.crate_path {
+        Some(crate_path) => crate_path.clone(),
+        None => quote!(thiserror),
+    };
+    let mut tokens = match &input {
+        Input::Enum(input) => {
+            let kind_enum = input.attrs.config.kind.map(|_| kind_enum(input));
+            let context_items = input
+                .attrs
+                .config
+                .context
+                .map(|_| context_items(input, &std, &thiserror));
+            quote!(#kind_enum #context_items)
+        }
+        Input::Struct(_) => TokenStream::new(),
+    };
+    if config.result_alias.is_some() {
+        tokens.extend(result_alias(node, config, &std));
+    }
+    tokens.extend(match input {
+        Input::Struct(struct_input) => impl_struct(struct_input, &std, &thiserror),
+        Input::Enum(enum_input) => impl_enum(enum_input, &std, &thiserror),
+    });
+    Ok(tokens)
 }
 
-fn impl_struct(input: Struct) -> TokenStream {
+fn impl_struct(input: Struct, std: &TokenStream, thiserror: &TokenStream) -> TokenStream {
     let ty = &input.ident;
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
     let mut error_inferred_bounds = InferredBounds::new();
 
+    let err_as_ref = input.attrs.config.generics_err_as_ref;
     let source_body = if input.attrs.transparent.is_some() {
         let only_field = &input.fields[0];
-        if only_field.contains_generic {
-            error_inferred_bounds.insert(only_field.ty, quote!(std::error::Error));
+        if only_field.infers_bounds() && !only_field.recursive {
+            let bound = if err_as_ref {
+                quote!(#std::convert::AsRef<dyn #std::error::Error + 'static>)
+            } else {
+                quote!(#std::error::Error)
+            };
+            error_inferred_bounds.insert(only_field.ty, bound);
         }
         let member = &only_field.member;
+        let as_err = if err_as_ref {
+            quote!(.as_ref())
+        } else {
+            quote!(.as_dyn_error())
+        };
+        Some(quote! {
+            #std::error::Error::source(self.#member #as_err)
+        })
+    } else if let Some(with) = input.source_field().and_then(Field::source_with) {
         Some(quote! {
-            std::error::Error::source(self.#member.as_dyn_error())
+            #with
         })
     } else if let Some(source_field) = input.source_field() {
         let source = &source_field.member;
-        if source_field.contains_generic {
+        if source_field.infers_source_bound() {
             let ty = unoptional_type(source_field.ty);
-            error_inferred_bounds.insert(ty, quote!(std::error::Error + 'static));
+            if source_field.recursive {
+                // Requiring Error of the error itself would be cyclic.
+                error_inferred_bounds.insert(ty, quote!('static));
+            } else if source_field.attrs.aggregate.is_some() {
+                let elem = source_field.collection_element().unwrap();
+                error_inferred_bounds.insert(elem, quote!(#std::error::Error + 'static));
+            } else if source_field.is_deref_source() {
+                error_inferred_bounds.insert(&ty, quote!(#std::ops::Deref));
+                let target = quote!(<#ty as #std::ops::Deref>::Target);
+                error_inferred_bounds.insert(
+                    target,
+                    quote!(#std::error::Error + #std::marker::Sized + 'static),
+                );
+            } else if source_field.is_wrapped_source() {
+                error_inferred_bounds
+                    .insert(ty, quote!(#std::fmt::Debug + #std::fmt::Display + 'static));
+            } else if source_field.is_err_as_ref(err_as_ref) {
+                error_inferred_bounds.insert(
+                    ty,
+                    quote!(#std::convert::AsRef<dyn #std::error::Error + 'static>),
+                );
+            } else {
+                error_inferred_bounds.insert(ty, quote!(#std::error::Error + 'static));
+            }
         }
         let asref = if type_is_option(source_field.ty) {
             Some(quote_spanned!(source.span()=> .as_ref()?))
         } else {
             None
         };
-        let dyn_error = quote_spanned!(source.span()=> self.#source #asref.as_dyn_error());
+        let dyn_error = if source_field.is_wrapped_source() {
+            let source_ref = match asref {
+                Some(asref) => quote!(self.#source #asref),
+                None => quote!(&self.#source),
+            };
+            quote_spanned!(source.span()=> #thiserror::__private::wrap_ref(#source_ref))
+        } else if source_field.is_deref_source() {
+            let source_ref = match asref {
+                Some(asref) => quote!(self.#source #asref),
+                None => quote!(&self.#source),
+            };
+            quote_spanned!(source.span()=> #std::ops::Deref::deref(#source_ref).as_dyn_error())
+        } else if source_field.attrs.aggregate.is_some() {
+            quote_spanned!(source.span()=> self.#source.first()?.as_dyn_error())
+        } else if source_field.is_err_as_ref(err_as_ref) {
+            quote_spanned!(source.span()=> self.#source #asref.as_ref())
+        } else {
+            quote_spanned!(source.span()=> self.#source #asref.as_dyn_error())
+        };
         Some(quote! {
-            std::option::Option::Some(#dyn_error)
+            #std::option::Option::Some(#dyn_error)
         })
     } else {
         None
     };
     let source_method = source_body.map(|body| {
+        let inline = inline_attr();
         quote! {
-            fn source(&self) -> std::option::Option<&(dyn std::error::Error + 'static)> {
-                use thiserror::__private::AsDynError;
+            #inline
+            fn source(&self) -> #std::option::Option<&(dyn #std::error::Error + 'static)> {
+                use #thiserror::__private::AsDynError;
                 #body
             }
         }
     });
 
-    let provide_method = input.backtrace_field().map(|backtrace_field| {
-        let demand = quote!(demand);
-        let backtrace = &backtrace_field.member;
-        let body = if let Some(source_field) = input.source_field() {
-            let source = &source_field.member;
-            let source_provide = if type_is_option(source_field.ty) {
-                quote_spanned! {source.span()=>
-                    if let std::option::Option::Some(source) = &self.#source {
-                        source.thiserror_provide(#demand);
+    let request = quote!(request);
+    let provide_body = if input.attrs.transparent.is_some() {
+        let member = &input.fields[0].member;
+        let provider = if err_as_ref {
+            dyn_error_as_ref(quote!(&self.#member), std)
+        } else {
+            quote!(self.#member.as_dyn_error())
+        };
+        Some(quote! {
+            use #thiserror::__private::{AsDynError, ThiserrorProvide};
+            #provider.thiserror_provide(#request);
+        })
+    } else {
+        let backtrace_provide = input.backtrace_field().map(|backtrace_field| {
+            let backtrace = &backtrace_field.member;
+            if let Some(source_field) = input.source_field() {
+                let source = &source_field.member;
+                let err_as_ref = source_field.is_err_as_ref(err_as_ref);
+                let source_provide = if let Some(with) = source_field.source_with() {
+                    quote_spanned! {source.span()=>
+                        if let #std::option::Option::Some(source) = #with {
+                            source.thiserror_provide(#request);
+                        }
                     }
+                } else if type_is_option(source_field.ty) {
+                    let provider = source_provider(err_as_ref, quote!(source), std);
+                    quote_spanned! {source.span()=>
+                        if let #std::option::Option::Some(source) = &self.#source {
+                            #provider.thiserror_provide(#request);
+                        }
+                    }
+                } else {
+                    let provider = source_provider(err_as_ref, quote!(&self.#source), std);
+                    quote_spanned! {source.span()=>
+                        #provider.thiserror_provide(#request);
+                    }
+                };
+                let self_provide = if source == backtrace {
+                    None
+                } else if type_is_option(backtrace_field.ty) {
+                    Some(quote! {
+                        if let #std::option::Option::Some(backtrace) = &self.#backtrace {
+                            #request.provide_ref::<#std::backtrace::Backtrace>(backtrace);
+                        }
+                    })
+                } else {
+                    Some(quote! {
+                        #request.provide_ref::<#std::backtrace::Backtrace>(&self.#backtrace);
+                    })
+                };
+                quote! {
+                    use #thiserror::__private::ThiserrorProvide;
+                    #source_provide
+                    #self_provide
                 }
-            } else {
-                quote_spanned! {source.span()=>
-                    self.#source.thiserror_provide(#demand);
-                }
-            };
-            let self_provide = if source == backtrace {
-                None
             } else if type_is_option(backtrace_field.ty) {
-                Some(quote! {
-                    if let std::option::Option::Some(backtrace) = &self.#backtrace {
-                        #demand.provide_ref::<std::backtrace::Backtrace>(backtrace);
+                quote! {
+                    if let #std::option::Option::Some(backtrace) = &self.#backtrace {
+                        #request.provide_ref::<#std::backtrace::Backtrace>(backtrace);
                     }
-                })
+                }
             } else {
-                Some(quote! {
-                    #demand.provide_ref::<std::backtrace::Backtrace>(&self.#backtrace);
-                })
-            };
-            quote! {
-                use thiserror::__private::ThiserrorProvide;
-                #source_provide
-                #self_provide
-            }
-        } else if type_is_option(backtrace_field.ty) {
-            quote! {
-                if let std::option::Option::Some(backtrace) = &self.#backtrace {
-                    #demand.provide_ref::<std::backtrace::Backtrace>(backtrace);
+                quote! {
+                    #request.provide_ref::<#std::backtrace::Backtrace>(&self.#backtrace);
                 }
             }
+        });
+        let fields_provide: Vec<TokenStream> = input
+            .provide_fields()
+            .map(|field| {
+                let member = &field.member;
+                provide_field(
+                    field,
+                    quote!(&self.#member),
+                    &request,
+                    &mut error_inferred_bounds,
+                    std,
+                )
+            })
+            .collect();
+        if backtrace_provide.is_none() && fields_provide.is_empty() {
+            None
         } else {
-            quote! {
-                #demand.provide_ref::<std::backtrace::Backtrace>(&self.#backtrace);
-            }
-        };
+            Some(quote! {
+                #backtrace_provide
+                #(#fields_provide)*
+            })
+        }
+    };
+    let provide_method = provide_body.map(|body| {
         quote! {
-            fn provide<'_demand>(&'_demand self, #demand: &mut std::any::Demand<'_demand>) {
-                #body
+            #thiserror::__provide_impl! {
+                fn provide<'_request>(
+                    &'_request self,
+                    #request: &mut #std::error::Request<'_request>,
+                ) {
+                    #body
+                }
             }
         }
     });
 
     let mut display_implied_bounds = Set::new();
-    let display_body = if input.attrs.transparent.is_some() {
+    let mut display_leaf_bounds = Vec::new();
+    let display_body = if input.attrs.config.static_messages.is_some() {
+        Some(quote! {
+            __formatter.write_str(self.message())
+        })
+    } else if input.attrs.transparent.is_some() {
         let only_field = &input.fields[0].member;
         display_implied_bounds.insert((0, Trait::Display));
         Some(quote! {
-            std::fmt::Display::fmt(&self.#only_field, __formatter)
+            #std::fmt::Display::fmt(&self.#only_field, __formatter)
+        })
+    } else if input.attrs.transparent_str.is_some() {
+        let only_field = &input.fields[0].member;
+        Some(quote! {
+            __formatter.write_str(#std::convert::AsRef::<str>::as_ref(&self.#only_field))
         })
     } else if let Some(display) = &input.attrs.display {
         display_implied_bounds = display.implied_bounds.clone();
+        display_leaf_bounds = display.leaf_bounds.clone();
         let use_as_display = if display.has_bonus_display {
-            Some(quote! {
-                #[allow(unused_imports)]
-                use thiserror::__private::{DisplayAsDisplay, PathAsDisplay};
-            })
+            Some(use_as_display(&input.attrs, thiserror))
         } else {
             None
         };
+        let display = display.fmt_tokens(std);
         let pat = fields_pat(&input.fields);
         Some(quote! {
             #use_as_display
@@ -137,23 +291,49 @@ fn impl_struct(input: Struct) -> TokenStream {
             let Self #pat = self;
             #display
         })
+    } else if let Some(aggregate_field) = input.aggregate_field() {
+        let member = &aggregate_field.member;
+        Some(aggregate_display(
+            aggregate_field,
+            quote!(self.#member),
+            std,
+            thiserror,
+        ))
     } else {
         None
     };
     let display_impl = display_body.map(|body| {
         let mut display_inferred_bounds = InferredBounds::new();
+        if input.attrs.display.is_none() {
+            insert_aggregate_bound(
+                &mut display_inferred_bounds,
+                input.aggregate_field(),
+                Trait::Display.path(std),
+            );
+        }
+        if input.attrs.transparent_str.is_some() {
+            insert_str_bound(&mut display_inferred_bounds, &input.fields[0], std);
+        }
         for (field, bound) in display_implied_bounds {
             let field = &input.fields[field];
-            if field.contains_generic {
-                display_inferred_bounds.insert(field.ty, bound);
+            if field.infers_bounds() && !field.recursive {
+                display_inferred_bounds.insert(field.ty, bound.path(std));
+            }
+        }
+        for (field, leaf, bound) in display_leaf_bounds {
+            if input.fields[field].infers_bounds() {
+                display_inferred_bounds.insert(leaf, bound.path(std));
             }
         }
+        display_inferred_bounds.replace(&explicit_bounds(&[&input.attrs], |bound| bound.display));
         let display_where_clause = display_inferred_bounds.augment_where_clause(input.generics);
+        let inline = inline_attr();
         quote! {
             #[allow(unused_qualifications)]
-            impl #impl_generics std::fmt::Display for #ty #ty_generics #display_where_clause {
+            impl #impl_generics #std::fmt::Display for #ty #ty_generics #display_where_clause {
+                #inline
                 #[allow(clippy::used_underscore_binding)]
-                fn fmt(&self, __formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                fn fmt(&self, __formatter: &mut #std::fmt::Formatter) -> #std::fmt::Result {
                     #body
                 }
             }
@@ -162,39 +342,515 @@ fn impl_struct(input: Struct) -> TokenStream {
 
     let from_impl = input.from_field().map(|from_field| {
         let backtrace_field = input.distinct_backtrace_field();
+        let span_trace_field = input.span_trace_field();
         let from = unoptional_type(from_field.ty);
-        let body = from_initializer(from_field, backtrace_field);
+        let body = from_initializer(
+            from_field,
+            backtrace_field,
+            span_trace_field,
+            input.phantom_fields(),
+            std,
+            thiserror,
+        );
+        let from_attr = from_field.attrs.from.as_ref().unwrap();
+        if let Some(into) = &from_attr.into {
+            return from_into_impl(input.original, into, quote!(#ty #body), std);
+        }
+        let body = match &from_attr.ctor {
+            Some(ctor) => quote!((#ctor)(source)),
+            None => quote!(#ty #body),
+        };
+        let inline = inline_attr();
         quote! {
             #[allow(unused_qualifications)]
-            impl #impl_generics std::convert::From<#from> for #ty #ty_generics #where_clause {
-                #[allow(deprecated)]
+            impl #impl_generics #std::convert::From<#from> for #ty #ty_generics #where_clause {
+                #inline
+                #[allow(deprecated, clippy::redundant_closure_call)]
                 fn from(source: #from) -> Self {
+                    #body
+                }
+            }
+        }
+    });
+
+    let from_via_impls = input
+        .from_field()
+        .map(|from_field| from_via_impls(input.original, &input.attrs, from_field, &[], std));
+
+    let from_ref_impl = input.from_field().and_then(|from_field| {
+        let by_ref = from_field.attrs.from.as_ref()?.by_ref.as_ref()?;
+        let backtrace_field = input.distinct_backtrace_field();
+        let span_trace_field = input.span_trace_field();
+        let from = unoptional_type(from_field.ty);
+        let body = from_initializer(
+            from_field,
+            backtrace_field,
+            span_trace_field,
+            input.phantom_fields(),
+            std,
+            thiserror,
+        );
+        let to_owned = from_ref_to_owned(by_ref, std);
+        let (impl_generics, from_ref_where_clause) =
+            from_ref_generics(from_field, by_ref, input.generics, std);
+        let (impl_generics, _, _) = impl_generics.split_for_impl();
+        let inline = inline_attr();
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::convert::From<&'__source #from>
+                for #ty #ty_generics #from_ref_where_clause
+            {
+                #inline
+                #[allow(deprecated)]
+                fn from(source: &'__source #from) -> Self {
+                    let source = #to_owned(source);
                     #ty #body
                 }
             }
+        })
+    });
+
+    let try_from_impl = input.attrs.try_from.as_ref().map(|try_from| {
+        let body = try_from_body(quote!(#ty), try_from, &input.fields, std);
+        let source = &try_from.ty;
+        let inline = inline_attr();
+        quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::convert::TryFrom<#source> for #ty #ty_generics #where_clause {
+                type Error = #source;
+                #inline
+                #[allow(deprecated)]
+                fn try_from(source: #source) -> #std::result::Result<Self, Self::Error> {
+                    #body
+                }
+            }
         }
     });
 
-    let error_trait = spanned_error_trait(input.original);
+    let description_method = input.attrs.config.legacy_description.map(|_| {
+        let name = input.ident.unraw().to_string();
+        description_method(quote!(#name))
+    });
+
+    let error_trait = spanned_error_trait(input.original, std);
     if input.generics.type_params().next().is_some() {
         let self_token = <Token![Self]>::default();
-        error_inferred_bounds.insert(self_token, Trait::Debug);
-        error_inferred_bounds.insert(self_token, Trait::Display);
+        error_inferred_bounds.insert(self_token, Trait::Debug.path(std));
+        error_inferred_bounds.insert(self_token, Trait::Display.path(std));
     }
+    error_inferred_bounds.replace(&explicit_bounds(&[&input.attrs], |bound| bound.error));
     let error_where_clause = error_inferred_bounds.augment_where_clause(input.generics);
 
-    quote! {
-        #[allow(unused_qualifications)]
-        impl #impl_generics #error_trait for #ty #ty_generics #error_where_clause {
-            #source_method
-            #provide_method
+    let mut methods = Vec::new();
+    if input.attrs.config.setters.is_some() {
+        let source_field = input.source_field().unwrap();
+        let member = &source_field.member;
+        let source_ty = unoptional_type(source_field.ty);
+        let set_source = quote! {
+            self.#member = #std::option::Option::Some(#std::convert::Into::into(source));
+        };
+        methods.push(setter_methods(input.original, source_ty, set_source, std));
+    }
+    if input.attrs.config.context_setter.is_some() {
+        let context_field = input.context_field().unwrap();
+        methods.push(context_setter_method(input.original, context_field, std));
+    }
+    if input.attrs.config.inner_accessors.is_some() {
+        methods.push(inner_accessor_methods(input.original, &input.fields[0]));
+    }
+    if input.attrs.config.variants.is_some() {
+        methods.push(variants_const(input.original, &[&input.ident]));
+    }
+    if input.attrs.config.variant_names.is_some() {
+        let name = input.ident.unraw().to_string();
+        methods.push(variant_name_items(input.original, quote!(#name), &[name]));
+    }
+    if input.attrs.config.static_messages.is_some() {
+        let message = input
+            .attrs
+            .display
+            .as_ref()
+            .unwrap()
+            .static_message()
+            .unwrap();
+        methods.push(message_method(input.original, quote!(#message)));
+    }
+    if input.attrs.config.downcast.is_some() {
+        methods.push(downcast_method(input.original, std));
+    }
+    if input.attrs.config.root_cause.is_some() {
+        methods.push(root_cause_method(input.original, std));
+    }
+    if input.attrs.config.report.is_some() {
+        methods.push(report_method(
+            input.original,
+            quote!(#std::option::Option::None),
+            quote!(#std::option::Option::None),
+            std,
+            thiserror,
+        ));
+    }
+    if let Some(exit_code) = &input.attrs.exit_code {
+        let value = exit_code.value;
+        methods.push(exit_code_method(input.original, quote!(#value)));
+    }
+    if input.attrs.config.fields.is_some() {
+        let mut inferred_bounds = InferredBounds::new();
+        let fields = context_fields(
+            &input.fields,
+            input.source_field(),
+            input.backtrace_field(),
+            &mut inferred_bounds,
+            std,
+        );
+        let pairs = fields
+            .iter()
+            .map(|(name, ident)| quote!((#name, (&FieldValue(&self.#ident)).field_string())));
+        methods.push(fields_method(
+            input.original,
+            &inferred_bounds,
+            quote!(#std::vec![#(#pairs),*]),
+            std,
+            thiserror,
+        ));
+    }
+    let related_fields: Vec<&Field> = input
+        .fields
+        .iter()
+        .filter(|field| field.attrs.related.is_some())
+        .collect();
+    if !related_fields.is_empty() {
+        let lists = related_fields.iter().map(|field| {
+            let member = &field.member;
+            quote!(#std::option::Option::Some(&self.#member))
+        });
+        methods.push(related_method(
+            input.original,
+            &related_fields,
+            lists,
+            std,
+            thiserror,
+        ));
+    }
+    if let Some(span_trace_field) = input.span_trace_field() {
+        let member = &span_trace_field.member;
+        let span_trace_ty = unoptional_type(span_trace_field.ty);
+        methods.push(if type_is_option(span_trace_field.ty) {
+            span_trace_method(
+                input.original,
+                quote!(#std::option::Option<&#span_trace_ty>),
+                quote!(self.#member.as_ref()),
+                thiserror,
+            )
+        } else {
+            span_trace_method(
+                input.original,
+                quote!(&#span_trace_ty),
+                quote!(&self.#member),
+                thiserror,
+            )
+        });
+    }
+
+    let exit_code_impl = input
+        .attrs
+        .exit_code
+        .as_ref()
+        .map(|_| exit_code_impl(input.original, &input.attrs, std, thiserror));
+    let tracing_impl = input.attrs.level.as_ref().map(|level| {
+        let ident = &level.ident;
+        tracing_impl(
+            input.original,
+            &input.attrs,
+            quote!(#thiserror::__private::tracing::Level::#ident),
+            std,
+            thiserror,
+        )
+    });
+    let defmt_impl = if input.attrs.config.defmt.is_some() {
+        let mut defmt_inferred_bounds = InferredBounds::new();
+        let body = if input.attrs.transparent.is_some() {
+            let only_field = &input.fields[0];
+            if only_field.contains_generic && !only_field.recursive {
+                defmt_inferred_bounds
+                    .insert(only_field.ty, quote!(#thiserror::__private::defmt::Format));
+            }
+            let member = &only_field.member;
+            quote!(defmt::Format::format(&self.#member, __formatter))
+        } else {
+            let defmt = input
+                .attrs
+                .display
+                .as_ref()
+                .unwrap()
+                .defmt
+                .as_ref()
+                .unwrap();
+            for &field in &defmt.fields {
+                let field = &input.fields[field];
+                if field.contains_generic && !field.recursive {
+                    defmt_inferred_bounds
+                        .insert(field.ty, quote!(#thiserror::__private::defmt::Format));
+                }
+            }
+            let defmt = defmt.fmt_tokens(thiserror);
+            let pat = fields_pat(&input.fields);
+            quote! {
+                #[allow(unused_variables, deprecated)]
+                let Self #pat = self;
+                #defmt
+            }
+        };
+        Some(defmt_impl(
+            input.original,
+            &defmt_inferred_bounds,
+            body,
+            thiserror,
+        ))
+    } else {
+        None
+    };
+
+    let serialize_impl = if input.attrs.config.serialize.is_some() {
+        let mut serialize_inferred_bounds = InferredBounds::new();
+        let entries = serialize_entries(
+            &input.attrs,
+            &input.attrs,
+            &input.fields,
+            quote!(SerializeStruct),
+            &mut serialize_inferred_bounds,
+            std,
+            thiserror,
+        );
+        let name = input.ident.unraw().to_string();
+        let len = entries.len();
+        let pat = fields_pat(&input.fields);
+        let body = quote! {
+            #[allow(unused_variables, deprecated)]
+            let Self #pat = self;
+            let mut __state = #thiserror::__private::serde::Serializer::serialize_struct(
+                __serializer,
+                #name,
+                #len,
+            )?;
+            #(#entries)*
+            #thiserror::__private::serde::ser::SerializeStruct::end(__state)
+        };
+        Some(serialize_impl(
+            input.original,
+            &serialize_inferred_bounds,
+            body,
+            std,
+            thiserror,
+        ))
+    } else {
+        None
+    };
+
+    let ufmt_impl = if input.attrs.config.ufmt.is_some() {
+        let mut ufmt_inferred_bounds = InferredBounds::new();
+        let body = if input.attrs.transparent.is_some() {
+            let only_field = &input.fields[0];
+            if only_field.contains_generic && !only_field.recursive {
+                ufmt_inferred_bounds.insert(only_field.ty, UfmtTrait::Display.path(thiserror));
+            }
+            let member = &only_field.member;
+            quote!(ufmt::uDisplay::fmt(&self.#member, __formatter))
+        } else {
+            let ufmt = input.attrs.display.as_ref().unwrap().ufmt.as_ref().unwrap();
+            for &(field, bound) in &ufmt.bounds {
+                let field = &input.fields[field];
+                if field.contains_generic && !field.recursive {
+                    ufmt_inferred_bounds.insert(field.ty, bound.path(thiserror));
+                }
+            }
+            let pat = fields_pat(&input.fields);
+            quote! {
+                #[allow(unused_variables, deprecated)]
+                let Self #pat = self;
+                #ufmt
+            }
+        };
+        Some(ufmt_impl(
+            input.original,
+            &ufmt_inferred_bounds,
+            body,
+            std,
+            thiserror,
+        ))
+    } else {
+        None
+    };
+
+    let debug_impl = if input.attrs.config.debug_transparent.is_some() {
+        let only_field = &input.fields[0];
+        let mut debug_inferred_bounds = InferredBounds::new();
+        if only_field.contains_generic {
+            debug_inferred_bounds.insert(only_field.ty, quote!(#std::fmt::Debug));
+        }
+        let debug_where_clause = debug_inferred_bounds.augment_where_clause(input.generics);
+        let member = &only_field.member;
+        let inline = inline_attr();
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::fmt::Debug for #ty #ty_generics #debug_where_clause {
+                #inline
+                fn fmt(&self, __formatter: &mut #std::fmt::Formatter) -> #std::fmt::Result {
+                    #std::fmt::Debug::fmt(&self.#member, __formatter)
+                }
+            }
+        })
+    } else if input.attrs.config.debug.is_some() {
+        let mut debug_inferred_bounds = InferredBounds::new();
+        let pat = fields_pat(&input.fields);
+        let name = ty.unraw().to_string();
+        let debug = debug_fields(
+            &name,
+            &input.fields,
+            &mut debug_inferred_bounds,
+            std,
+            thiserror,
+        );
+        let debug_where_clause = debug_inferred_bounds.augment_where_clause(input.generics);
+        let inline = inline_attr();
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::fmt::Debug for #ty #ty_generics #debug_where_clause {
+                #inline
+                fn fmt(&self, __formatter: &mut #std::fmt::Formatter) -> #std::fmt::Result {
+                    #[allow(unused_variables, deprecated, clippy::used_underscore_binding)]
+                    let Self #pat = self;
+                    #debug
+                }
+            }
+        })
+    } else {
+        None
+    };
+
+    let hash_impl = if input.attrs.config.hash_ignore_source.is_some() {
+        let mut hash_inferred_bounds = InferredBounds::new();
+        let fields = input.data_fields();
+        for field in &fields {
+            if field.contains_generic && !field.recursive {
+                hash_inferred_bounds.insert(field.ty, quote!(#std::hash::Hash));
+            }
+        }
+        let members = fields.iter().map(|field| &field.member);
+        let body = quote! {
+            #(#std::hash::Hash::hash(&self.#members, __state);)*
+        };
+        Some(hash_impl(
+            &input.ident,
+            input.generics,
+            &hash_inferred_bounds,
+            body,
+            std,
+        ))
+    } else {
+        None
+    };
+
+    let clone_impl = if input.attrs.config.clone.is_some() {
+        let mut clone_inferred_bounds = InferredBounds::new();
+        for field in &input.fields {
+            if field.contains_generic && !field.recursive {
+                clone_inferred_bounds.insert(field.ty, quote!(#std::clone::Clone));
+            }
+        }
+        let members = input.fields.iter().map(|field| &field.member);
+        let body = quote! {
+            #ty {
+                #(#members: #std::clone::Clone::clone(&self.#members),)*
+            }
+        };
+        Some(clone_impl(
+            &input.ident,
+            input.generics,
+            &clone_inferred_bounds,
+            body,
+            std,
+        ))
+    } else {
+        None
+    };
+
+    let partial_eq_impl = if input.attrs.config.partial_eq.is_some() {
+        let mut eq_inferred_bounds = InferredBounds::new();
+        let mut comparisons = Vec::new();
+        for field in input.data_fields() {
+            if field.contains_generic && !field.recursive {
+                eq_inferred_bounds.insert(field.ty, quote!(#std::cmp::PartialEq));
+            }
+            let member = &field.member;
+            comparisons.push(quote!(self.#member == other.#member));
         }
+        if input.attrs.config.partial_eq_message {
+            if let Some(field) = input.message_field() {
+                let member = &field.member;
+                comparisons.push(message_eq(
+                    field,
+                    &mut eq_inferred_bounds,
+                    quote!(&self.#member),
+                    quote!(&other.#member),
+                    std,
+                ));
+            }
+        }
+        let body = if comparisons.is_empty() {
+            quote!(true)
+        } else {
+            quote!(#(#comparisons)&&*)
+        };
+        Some(partial_eq_impl(
+            &input.ident,
+            input.generics,
+            &eq_inferred_bounds,
+            body,
+            std,
+        ))
+    } else {
+        None
+    };
+
+    if input.attrs.config.require_debug.is_some() {
+        methods.push(require_debug_method(input.ident.span(), thiserror));
+    }
+    let error_impl = if input.attrs.config.no_error_impl.is_none() {
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #error_trait for #ty #ty_generics #error_where_clause {
+                #source_method
+                #provide_method
+                #description_method
+            }
+        })
+    } else {
+        None
+    };
+    let inherent_impl = inherent_impl(input.original, &input.attrs, methods);
+
+    quote! {
+        #error_impl
+        #inherent_impl
+        #exit_code_impl
+        #tracing_impl
         #display_impl
+        #debug_impl
+        #defmt_impl
+        #ufmt_impl
+        #serialize_impl
+        #clone_impl
+        #partial_eq_impl
+        #hash_impl
         #from_impl
+        #from_via_impls
+        #from_ref_impl
+        #try_from_impl
     }
 }
 
-fn impl_enum(input: Enum) -> TokenStream {
+fn impl_enum(input: Enum, std: &TokenStream, thiserror: &TokenStream) -> TokenStream {
     let ty = &input.ident;
 
     let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
@@ -204,9 +860,9 @@ fn impl_enum(input: Enum) -> TokenStream {
     let attrs = &input.attrs;
 
     let generic_type_bound = if attrs.config.generics_err_as_ref {
-        quote!(AsRef<std::error::Error + 'static>)
+        quote!(#std::convert::AsRef<dyn #std::error::Error + 'static>)
     } else {
-        quote!(std::error::Error + 'static)
+        quote!(#std::error::Error + 'static)
     };
 
     let as_err = if attrs.config.generics_err_as_ref {
@@ -221,21 +877,36 @@ fn impl_enum(input: Enum) -> TokenStream {
             if variant.attrs.transparent.is_some() {
                 let only_field = &variant.fields[0];
 
-                if only_field.contains_generic {
+                if only_field.infers_bounds() && !only_field.recursive {
                     error_inferred_bounds.insert(only_field.ty, generic_type_bound.clone());
                 }
 
                 let member = &only_field.member;
-                let source = quote!(std::error::Error::source(transparent#as_err));
+                let source = quote!(#std::error::Error::source(transparent#as_err));
                 quote! {
                     #ty::#ident {#member: transparent} => #source,
                 }
             } else if let Some(source_field) = variant.source_field() {
                 let source = &source_field.member;
-                if source_field.contains_generic {
+                if source_field.infers_source_bound() {
                     let ty = unoptional_type(source_field.ty);
 
-                    error_inferred_bounds.insert(ty, generic_type_bound.clone());
+                    if source_field.recursive {
+                        error_inferred_bounds.insert(ty, quote!('static));
+                    } else if source_field.attrs.aggregate.is_some() {
+                        let elem = source_field.collection_element().unwrap();
+                        error_inferred_bounds.insert(elem, quote!(#std::error::Error + 'static));
+                    } else if source_field.is_deref_source() {
+                        error_inferred_bounds.insert(&ty, quote!(#std::ops::Deref));
+                        let target = quote!(<#ty as #std::ops::Deref>::Target);
+                        error_inferred_bounds.insert(target, quote!(#std::error::Error + #std::marker::Sized + 'static));
+                    } else if source_field.is_wrapped_source() {
+                        error_inferred_bounds.insert(ty, quote!(#std::fmt::Debug + #std::fmt::Display + 'static));
+                    } else if source_field.is_err_as_ref(attrs.config.generics_err_as_ref) {
+                        error_inferred_bounds.insert(ty, quote!(#std::convert::AsRef<dyn #std::error::Error + 'static>));
+                    } else {
+                        error_inferred_bounds.insert(ty, quote!(#std::error::Error + 'static));
+                    }
                 }
                 let asref = if type_is_option(source_field.ty) {
                     Some(quote_spanned!(source.span()=> .as_ref()?))
@@ -243,19 +914,31 @@ fn impl_enum(input: Enum) -> TokenStream {
                     None
                 };
                 let varsource = quote!(source);
-                let dyn_error = quote_spanned!(source.span()=> #varsource #asref#as_err);
+                let dyn_error = if source_field.is_wrapped_source() {
+                    quote_spanned!(source.span()=> #thiserror::__private::wrap_ref(#varsource #asref))
+                } else if source_field.is_deref_source() {
+                    quote_spanned!(source.span()=> #std::ops::Deref::deref(#varsource #asref).as_dyn_error())
+                } else if source_field.attrs.aggregate.is_some() {
+                    quote_spanned!(source.span()=> #varsource.first()?.as_dyn_error())
+                } else if source_field.is_err_as_ref(attrs.config.generics_err_as_ref) {
+                    quote_spanned!(source.span()=> #varsource #asref.as_ref())
+                } else {
+                    quote_spanned!(source.span()=> #varsource #asref.as_dyn_error())
+                };
                 quote! {
-                    #ty::#ident {#source: #varsource, ..} => std::option::Option::Some(#dyn_error),
+                    #ty::#ident {#source: #varsource, ..} => #std::option::Option::Some(#dyn_error),
                 }
             } else {
                 quote! {
-                    #ty::#ident {..} => std::option::Option::None,
+                    #ty::#ident {..} => #std::option::Option::None,
                 }
             }
         });
+        let inline = inline_attr();
         Some(quote! {
-            fn source(&self) -> std::option::Option<&(dyn std::error::Error + 'static)> {
-                use thiserror::__private::AsDynError;
+            #inline
+            fn source(&self) -> #std::option::Option<&(dyn #std::error::Error + 'static)> {
+                use #thiserror::__private::AsDynError;
                 #[allow(deprecated)]
                 match self {
                     #(#arms)*
@@ -266,103 +949,157 @@ fn impl_enum(input: Enum) -> TokenStream {
         None
     };
 
-    let provide_method = if input.has_backtrace() {
-        let demand = quote!(demand);
+    let provide_method = if input.has_provide() {
+        let request = quote!(request);
         let arms = input.variants.iter().map(|variant| {
             let ident = &variant.ident;
-            match (variant.backtrace_field(), variant.source_field()) {
+            if variant.attrs.transparent.is_some() {
+                let member = &variant.fields[0].member;
+                let use_as_dyn_error = if attrs.config.generics_err_as_ref {
+                    None
+                } else {
+                    Some(quote!(
+                        use #thiserror::__private::AsDynError;
+                    ))
+                };
+                return quote! {
+                    #ty::#ident {#member: transparent} => {
+                        #use_as_dyn_error
+                        use #thiserror::__private::ThiserrorProvide;
+                        transparent #as_err .thiserror_provide(#request);
+                    }
+                };
+            }
+            // Fields bound by the match arm, and the name each one is bound to.
+            let mut bindings: Vec<(&Member, Ident)> = Vec::new();
+            let backtrace_provide = match (variant.backtrace_field(), variant.source_field()) {
+                // A #[backtrace(from_source)] field defers to the source, which
+                // holds the backtrace the From impl did not capture again.
                 (Some(backtrace_field), Some(source_field))
-                    if backtrace_field.attrs.backtrace.is_none() =>
+                    if backtrace_field
+                        .attrs
+                        .backtrace
+                        .as_ref()
+                        .map_or(true, |backtrace| backtrace.from_source) =>
                 {
-                    let backtrace = &backtrace_field.member;
                     let source = &source_field.member;
                     let varsource = quote!(source);
+                    let err_as_ref = source_field.is_err_as_ref(attrs.config.generics_err_as_ref);
                     let source_provide = if type_is_option(source_field.ty) {
+                        let provider = source_provider(err_as_ref, quote!(source), std);
                         quote_spanned! {source.span()=>
-                            if let std::option::Option::Some(source) = #varsource {
-                                source.thiserror_provide(#demand);
+                            if let #std::option::Option::Some(source) = #varsource {
+                                #provider.thiserror_provide(#request);
                             }
                         }
                     } else {
+                        let provider = source_provider(err_as_ref, varsource.clone(), std);
                         quote_spanned! {source.span()=>
-                            #varsource.thiserror_provide(#demand);
+                            #provider.thiserror_provide(#request);
                         }
                     };
                     let self_provide = if type_is_option(backtrace_field.ty) {
                         quote! {
-                            if let std::option::Option::Some(backtrace) = backtrace {
-                                #demand.provide_ref::<std::backtrace::Backtrace>(backtrace);
+                            if let #std::option::Option::Some(backtrace) = backtrace {
+                                #request.provide_ref::<#std::backtrace::Backtrace>(backtrace);
                             }
                         }
                     } else {
                         quote! {
-                            #demand.provide_ref::<std::backtrace::Backtrace>(backtrace);
+                            #request.provide_ref::<#std::backtrace::Backtrace>(backtrace);
                         }
                     };
-                    quote! {
-                        #ty::#ident {
-                            #backtrace: backtrace,
-                            #source: #varsource,
-                            ..
-                        } => {
-                            use thiserror::__private::ThiserrorProvide;
-                            #source_provide
-                            #self_provide
-                        }
-                    }
+                    bindings.push((&backtrace_field.member, format_ident!("backtrace")));
+                    bindings.push((source, format_ident!("source")));
+                    Some(quote! {
+                        use #thiserror::__private::ThiserrorProvide;
+                        #source_provide
+                        #self_provide
+                    })
                 }
                 (Some(backtrace_field), Some(source_field))
                     if backtrace_field.member == source_field.member =>
                 {
                     let backtrace = &backtrace_field.member;
                     let varsource = quote!(source);
+                    let err_as_ref = source_field.is_err_as_ref(attrs.config.generics_err_as_ref);
                     let source_provide = if type_is_option(source_field.ty) {
+                        let provider = source_provider(err_as_ref, quote!(source), std);
                         quote_spanned! {backtrace.span()=>
-                            if let std::option::Option::Some(source) = #varsource {
-                                source.thiserror_provide(#demand);
+                            if let #std::option::Option::Some(source) = #varsource {
+                                #provider.thiserror_provide(#request);
                             }
                         }
                     } else {
+                        let provider = source_provider(err_as_ref, varsource.clone(), std);
                         quote_spanned! {backtrace.span()=>
-                            #varsource.thiserror_provide(#demand);
+                            #provider.thiserror_provide(#request);
                         }
                     };
-                    quote! {
-                        #ty::#ident {#backtrace: #varsource, ..} => {
-                            use thiserror::__private::ThiserrorProvide;
-                            #source_provide
-                        }
-                    }
+                    bindings.push((backtrace, format_ident!("source")));
+                    Some(quote! {
+                        use #thiserror::__private::ThiserrorProvide;
+                        #source_provide
+                    })
                 }
                 (Some(backtrace_field), _) => {
-                    let backtrace = &backtrace_field.member;
-                    let body = if type_is_option(backtrace_field.ty) {
+                    bindings.push((&backtrace_field.member, format_ident!("backtrace")));
+                    Some(if type_is_option(backtrace_field.ty) {
                         quote! {
-                            if let std::option::Option::Some(backtrace) = backtrace {
-                                #demand.provide_ref::<std::backtrace::Backtrace>(backtrace);
+                            if let #std::option::Option::Some(backtrace) = backtrace {
+                                #request.provide_ref::<#std::backtrace::Backtrace>(backtrace);
                             }
                         }
                     } else {
                         quote! {
-                            #demand.provide_ref::<std::backtrace::Backtrace>(backtrace);
+                            #request.provide_ref::<#std::backtrace::Backtrace>(backtrace);
                         }
-                    };
-                    quote! {
-                        #ty::#ident {#backtrace: backtrace, ..} => {
-                            #body
+                    })
+                }
+                (None, _) => None,
+            };
+            let fields_provide: Vec<TokenStream> = variant
+                .provide_fields()
+                .enumerate()
+                .map(|(i, field)| {
+                    let binding = match bindings.iter().find(|(member, _)| **member == field.member)
+                    {
+                        Some((_, binding)) => binding.clone(),
+                        None => {
+                            let binding = format_ident!("provided{}", i);
+                            bindings.push((&field.member, binding.clone()));
+                            binding
                         }
-                    }
+                    };
+                    provide_field(
+                        field,
+                        quote!(#binding),
+                        &request,
+                        &mut error_inferred_bounds,
+                        std,
+                    )
+                })
+                .collect();
+            let pat = bindings
+                .iter()
+                .map(|(member, binding)| quote!(#member: #binding));
+            quote! {
+                #ty::#ident {#(#pat,)* ..} => {
+                    #backtrace_provide
+                    #(#fields_provide)*
                 }
-                (None, _) => quote! {
-                    #ty::#ident {..} => {}
-                },
             }
         });
         Some(quote! {
-            fn provide<'_demand>(&'_demand self, #demand: &mut std::any::Demand<'_demand>) {
-                #[allow(deprecated)]
-                match self {
-                    #(#arms)*
+            #thiserror::__provide_impl! {
+                fn provide<'_request>(
+                    &'_request self,
+                    #request: &mut #std::error::Request<'_request>,
+                ) {
+                    #[allow(deprecated)]
+                    match self {
+                        #(#arms)*
+                    }
                 }
             }
         })
@@ -370,7 +1107,25 @@ fn impl_enum(input: Enum) -> TokenStream {
         None
     };
 
-    let display_impl = if input.has_display() {
+    let variant_attrs = iter::once(&input.attrs)
+        .chain(input.variants.iter().map(|variant| &variant.attrs))
+        .collect::<Vec<_>>();
+    let display_bounds = explicit_bounds(&variant_attrs, |bound| bound.display);
+    let display_impl = if input.attrs.config.static_messages.is_some() {
+        let mut display_inferred_bounds = InferredBounds::new();
+        display_inferred_bounds.replace(&display_bounds);
+        let display_where_clause = display_inferred_bounds.augment_where_clause(input.generics);
+        let inline = inline_attr();
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::fmt::Display for #ty #ty_generics #display_where_clause {
+                #inline
+                fn fmt(&self, __formatter: &mut #std::fmt::Formatter) -> #std::fmt::Result {
+                    __formatter.write_str(self.message())
+                }
+            }
+        })
+    } else if input.has_display() {
         let mut display_inferred_bounds = InferredBounds::new();
         let use_as_display = if input.variants.iter().any(|v| {
             v.attrs
@@ -378,10 +1133,7 @@ fn impl_enum(input: Enum) -> TokenStream {
                 .as_ref()
                 .map_or(false, |display| display.has_bonus_display)
         }) {
-            Some(quote! {
-                #[allow(unused_imports)]
-                use thiserror::__private::{DisplayAsDisplay, PathAsDisplay};
-            })
+            Some(use_as_display(&input.attrs, thiserror))
         } else {
             None
         };
@@ -392,10 +1144,34 @@ fn impl_enum(input: Enum) -> TokenStream {
         };
         let arms = input.variants.iter().map(|variant| {
             let mut display_implied_bounds = Set::new();
+            let mut display_leaf_bounds = Vec::new();
             let display = match &variant.attrs.display {
                 Some(display) => {
                     display_implied_bounds = display.implied_bounds.clone();
-                    display.to_token_stream()
+                    display_leaf_bounds = display.leaf_bounds.clone();
+                    display.fmt_tokens(std)
+                }
+                None if variant.attrs.transparent_str.is_some() => {
+                    let only_field = &variant.fields[0];
+                    insert_str_bound(&mut display_inferred_bounds, only_field, std);
+                    let var = match &only_field.member {
+                        Member::Named(ident) => ident.clone(),
+                        Member::Unnamed(index) => format_ident!("_{}", index),
+                    };
+                    quote!(__formatter.write_str(#std::convert::AsRef::<str>::as_ref(#var)))
+                }
+                None if variant.aggregate_field().is_some() => {
+                    let aggregate_field = variant.aggregate_field().unwrap();
+                    insert_aggregate_bound(
+                        &mut display_inferred_bounds,
+                        Some(aggregate_field),
+                        Trait::Display.path(std),
+                    );
+                    let var = match &aggregate_field.member {
+                        Member::Named(ident) => ident.clone(),
+                        Member::Unnamed(index) => format_ident!("_{}", index),
+                    };
+                    aggregate_display(aggregate_field, quote!(#var), std, thiserror)
                 }
                 None => {
                     let only_field = match &variant.fields[0].member {
@@ -403,27 +1179,35 @@ fn impl_enum(input: Enum) -> TokenStream {
                         Member::Unnamed(index) => format_ident!("_{}", index),
                     };
                     display_implied_bounds.insert((0, Trait::Display));
-                    quote!(std::fmt::Display::fmt(#only_field, __formatter))
+                    quote!(#std::fmt::Display::fmt(#only_field, __formatter))
                 }
             };
             for (field, bound) in display_implied_bounds {
                 let field = &variant.fields[field];
-                if field.contains_generic {
-                    display_inferred_bounds.insert(field.ty, bound);
+                if field.infers_bounds() && !field.recursive {
+                    display_inferred_bounds.insert(field.ty, bound.path(std));
                 }
             }
-            let ident = &variant.ident;
+            for (field, leaf, bound) in display_leaf_bounds {
+                if variant.fields[field].infers_bounds() {
+                    display_inferred_bounds.insert(leaf, bound.path(std));
+                }
+            }
+            let ident = &variant.ident;
             let pat = fields_pat(&variant.fields);
             quote! {
                 #ty::#ident #pat => #display
             }
         });
         let arms = arms.collect::<Vec<_>>();
+        display_inferred_bounds.replace(&display_bounds);
         let display_where_clause = display_inferred_bounds.augment_where_clause(input.generics);
+        let inline = inline_attr();
         Some(quote! {
             #[allow(unused_qualifications)]
-            impl #impl_generics std::fmt::Display for #ty #ty_generics #display_where_clause {
-                fn fmt(&self, __formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+            impl #impl_generics #std::fmt::Display for #ty #ty_generics #display_where_clause {
+                #inline
+                fn fmt(&self, __formatter: &mut #std::fmt::Formatter) -> #std::fmt::Result {
                     #use_as_display
                     #[allow(unused_variables, deprecated, clippy::used_underscore_binding)]
                     match #void_deref self {
@@ -438,97 +1222,3049 @@ fn impl_enum(input: Enum) -> TokenStream {
 
     let from_impls = input.variants.iter().filter_map(|variant| {
         let from_field = variant.from_field()?;
+        if !from_field.attrs.from.as_ref()?.kinds.is_empty() {
+            // Handled by the catch-all variant's impl below.
+            return None;
+        }
+        let backtrace_field = variant.distinct_backtrace_field();
+        let span_trace_field = variant.span_trace_field();
+        let from = unoptional_type(from_field.ty);
+        let body = from_initializer(
+            from_field,
+            backtrace_field,
+            span_trace_field,
+            variant.phantom_fields(),
+            std,
+            thiserror,
+        );
+        let ident = &variant.ident;
+        let mut body = quote!(#ty::#ident #body);
+        let from_attr = from_field.attrs.from.as_ref()?;
+        if let Some(into) = &from_attr.into {
+            return Some(from_into_impl(input.original, into, body, std));
+        }
+        if let Some(ctor) = &from_attr.ctor {
+            body = quote!((#ctor)(source));
+        }
+        let kind_arms = kind_dispatch_arms(&input, from_field.ty, std, thiserror);
+        if !kind_arms.is_empty() {
+            body = quote! {
+                match source.kind() {
+                    #(#kind_arms)*
+                    _ => #body,
+                }
+            };
+        }
+        let inline = inline_attr();
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::convert::From<#from> for #ty #ty_generics #where_clause {
+                #inline
+                #[allow(deprecated, clippy::redundant_closure_call)]
+                fn from(source: #from) -> Self {
+                    #body
+                }
+            }
+        })
+    });
+
+    let from_via_impls = input.variants.iter().filter_map(|variant| {
+        let from_field = variant.from_field()?;
+        let flattened = variant
+            .attrs
+            .flatten
+            .as_ref()
+            .map_or(&[][..], |flatten| &flatten.types);
+        Some(from_via_impls(
+            input.original,
+            &input.attrs,
+            from_field,
+            flattened,
+            std,
+        ))
+    });
+
+    let from_ref_impls = input.variants.iter().filter_map(|variant| {
+        let from_field = variant.from_field()?;
+        let by_ref = from_field.attrs.from.as_ref()?.by_ref.as_ref()?;
         let backtrace_field = variant.distinct_backtrace_field();
+        let span_trace_field = variant.span_trace_field();
+        let body = from_initializer(
+            from_field,
+            backtrace_field,
+            span_trace_field,
+            variant.phantom_fields(),
+            std,
+            thiserror,
+        );
         let variant = &variant.ident;
         let from = unoptional_type(from_field.ty);
-        let body = from_initializer(from_field, backtrace_field);
+        let to_owned = from_ref_to_owned(by_ref, std);
+        let (impl_generics, from_ref_where_clause) =
+            from_ref_generics(from_field, by_ref, input.generics, std);
+        let (impl_generics, _, _) = impl_generics.split_for_impl();
+        let inline = inline_attr();
         Some(quote! {
             #[allow(unused_qualifications)]
-            impl #impl_generics std::convert::From<#from> for #ty #ty_generics #where_clause {
+            impl #impl_generics #std::convert::From<&'__source #from>
+                for #ty #ty_generics #from_ref_where_clause
+            {
+                #inline
                 #[allow(deprecated)]
-                fn from(source: #from) -> Self {
+                fn from(source: &'__source #from) -> Self {
+                    let source = #to_owned(source);
                     #ty::#variant #body
                 }
             }
         })
     });
 
-    let error_trait = spanned_error_trait(input.original);
+    let try_into_impls = input.variants.iter().filter_map(|variant| {
+        let from_field = variant.from_field()?;
+        if !from_field.attrs.from.as_ref()?.try_into {
+            return None;
+        }
+        let ident = &variant.ident;
+        let member = &from_field.member;
+        let from = unoptional_type(from_field.ty);
+        let pat = if type_is_option(from_field.ty) {
+            quote!(#ty::#ident { #member: #std::option::Option::Some(source), .. })
+        } else {
+            quote!(#ty::#ident { #member: source, .. })
+        };
+        let inline = inline_attr();
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::convert::TryFrom<#ty #ty_generics> for #from #where_clause {
+                type Error = #ty #ty_generics;
+                #inline
+                #[allow(deprecated, unreachable_patterns)]
+                fn try_from(error: #ty #ty_generics) -> #std::result::Result<Self, Self::Error> {
+                    match error {
+                        #pat => #std::result::Result::Ok(source),
+                        error => #std::result::Result::Err(error),
+                    }
+                }
+            }
+        })
+    });
+
+    let try_from_impls = input.variants.iter().filter_map(|variant| {
+        let try_from = variant.attrs.try_from.as_ref()?;
+        let ident = &variant.ident;
+        let body = try_from_body(quote!(#ty::#ident), try_from, &variant.fields, std);
+        let source = &try_from.ty;
+        let inline = inline_attr();
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::convert::TryFrom<#source> for #ty #ty_generics #where_clause {
+                type Error = #source;
+                #inline
+                #[allow(deprecated)]
+                fn try_from(source: #source) -> #std::result::Result<Self, Self::Error> {
+                    #body
+                }
+            }
+        })
+    });
+
+    let description_method = input.attrs.config.legacy_description.map(|_| {
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let name = ident.unraw().to_string();
+            quote!(#ty::#ident {..} => #name,)
+        });
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        description_method(quote! {
+            match #void_deref self {
+                #(#arms)*
+            }
+        })
+    });
+
+    let error_trait = spanned_error_trait(input.original, std);
     if input.generics.type_params().next().is_some() {
         let self_token = <Token![Self]>::default();
-        error_inferred_bounds.insert(self_token, Trait::Debug);
-        error_inferred_bounds.insert(self_token, Trait::Display);
+        error_inferred_bounds.insert(self_token, Trait::Debug.path(std));
+        error_inferred_bounds.insert(self_token, Trait::Display.path(std));
     }
+    error_inferred_bounds.replace(&explicit_bounds(&variant_attrs, |bound| bound.error));
     let error_where_clause = error_inferred_bounds.augment_where_clause(input.generics);
 
-    quote! {
-        #[allow(unused_qualifications)]
-        impl #impl_generics #error_trait for #ty #ty_generics #error_where_clause {
-            #source_method
-            #provide_method
+    let mut methods = Vec::new();
+    if input.attrs.config.constructors.is_some() {
+        for variant in &input.variants {
+            methods.push(constructor_method(input.original, variant, std));
         }
-        #display_impl
-        #(#from_impls)*
     }
-}
-
-fn fields_pat(fields: &[Field]) -> TokenStream {
-    let mut members = fields.iter().map(|field| &field.member).peekable();
-    match members.peek() {
-        Some(Member::Named(_)) => quote!({ #(#members),* }),
-        Some(Member::Unnamed(_)) => {
-            let vars = members.map(|member| match member {
-                Member::Unnamed(member) => format_ident!("_{}", member),
-                Member::Named(_) => unreachable!(),
-            });
-            quote!((#(#vars),*))
+    if input.attrs.config.predicates.is_some() {
+        for variant in &input.variants {
+            methods.push(predicate_method(input.original, variant));
         }
-        None => quote!({}),
     }
-}
-
-fn from_initializer(from_field: &Field, backtrace_field: Option<&Field>) -> TokenStream {
-    let from_member = &from_field.member;
-    let some_source = if type_is_option(from_field.ty) {
-        quote!(std::option::Option::Some(source))
-    } else {
-        quote!(source)
-    };
-    let backtrace = backtrace_field.map(|backtrace_field| {
-        let backtrace_member = &backtrace_field.member;
-        if type_is_option(backtrace_field.ty) {
-            quote! {
-                #backtrace_member: std::option::Option::Some(std::backtrace::Backtrace::capture()),
+    if input.attrs.config.accessors.is_some() {
+        for variant in &input.variants {
+            if !variant.fields.is_empty() {
+                methods.push(accessor_methods(input.original, variant, std));
+            }
+        }
+    }
+    if input.attrs.config.inner_accessors.is_some() {
+        for variant in &input.variants {
+            if variant.attrs.transparent.is_some() {
+                methods.push(variant_inner_accessor_methods(input.original, variant, std));
+            }
+        }
+    }
+    if input.attrs.config.setters.is_some() {
+        let mut source_ty = None;
+        let arms = input.variants.iter().filter_map(|variant| {
+            if variant.attrs.transparent.is_some() {
+                return None;
+            }
+            let source_field = variant.source_field()?;
+            source_ty = Some(unoptional_type(source_field.ty));
+            let ident = &variant.ident;
+            let member = &source_field.member;
+            Some(quote! {
+                #ty::#ident {#member: slot, ..} => {
+                    *slot = #std::option::Option::Some(#std::convert::Into::into(source));
+                }
+            })
+        });
+        let arms: Vec<TokenStream> = arms.collect();
+        let set_source = quote! {
+            #[allow(unreachable_patterns)]
+            match self {
+                #(#arms)*
+                _ => {}
+            }
+        };
+        methods.push(setter_methods(
+            input.original,
+            source_ty.unwrap(),
+            set_source,
+            std,
+        ));
+    }
+    if input.attrs.config.variants.is_some() {
+        let idents: Vec<&Ident> = input
+            .variants
+            .iter()
+            .map(|variant| &variant.ident)
+            .collect();
+        methods.push(variants_const(input.original, &idents));
+    }
+    if input.attrs.config.variant_names.is_some() {
+        let names: Vec<String> = input.variants.iter().map(Variant::name).collect();
+        let arms = input.variants.iter().zip(&names).map(|(variant, name)| {
+            let ident = &variant.ident;
+            quote!(#ty::#ident {..} => #name,)
+        });
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let body = quote! {
+            match #void_deref self {
+                #(#arms)*
+            }
+        };
+        methods.push(variant_name_items(input.original, body, &names));
+    }
+    if input.attrs.config.static_messages.is_some() {
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let message = variant
+                .attrs
+                .display
+                .as_ref()
+                .unwrap()
+                .static_message()
+                .unwrap();
+            quote!(#ty::#ident {..} => #message)
+        });
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let body = quote! {
+            match #void_deref self {
+                #(#arms,)*
+            }
+        };
+        methods.push(message_method(input.original, body));
+    }
+    if input.attrs.config.discriminant_code.is_some() {
+        let fieldless = input.is_fieldless();
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let code = if let Some(code) = &variant.attrs.code {
+                let expr = &code.expr;
+                quote!(#expr)
+            } else if fieldless {
+                // Casting a freshly built variant rather than `*self` works
+                // without the enum being Copy.
+                quote!(#ty::#ident)
+            } else {
+                let (_, expr) = variant.original.discriminant.as_ref().unwrap();
+                quote!(#expr)
+            };
+            quote!(#ty::#ident {..} => (#code) as i64)
+        });
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let body = quote! {
+            match #void_deref self {
+                #(#arms,)*
+            }
+        };
+        methods.push(code_method(input.original, body));
+    }
+    if input.attrs.config.codes.is_some() {
+        let codes: Vec<(&Ident, &Expr)> = input
+            .variants
+            .iter()
+            .map(|variant| (&variant.ident, &variant.attrs.code.as_ref().unwrap().expr))
+            .collect();
+        let arms = codes
+            .iter()
+            .map(|(ident, code)| quote!(#ty::#ident {..} => #code));
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let body = quote! {
+            match #void_deref self {
+                #(#arms,)*
+            }
+        };
+        methods.push(codes_items(input.original, body, &codes));
+    }
+    if input.attrs.config.repr_c_codes.is_some() {
+        let codes: Vec<(&Ident, &Expr)> = input
+            .variants
+            .iter()
+            .map(|variant| (&variant.ident, &variant.attrs.code.as_ref().unwrap().expr))
+            .collect();
+        let arms = codes
+            .iter()
+            .map(|(ident, code)| quote!(#ty::#ident {..} => #code));
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let to_body = quote! {
+            match #void_deref self {
+                #(#arms,)*
+            }
+        };
+        // Only an enum without fields can be rebuilt from its code alone.
+        let from_body = if input.is_fieldless() {
+            let arms = codes
+                .iter()
+                .map(|(ident, code)| quote!(#code => #std::option::Option::Some(#ty::#ident)));
+            Some(quote! {
+                match code {
+                    #(#arms,)*
+                    _ => #std::option::Option::None,
+                }
+            })
+        } else {
+            None
+        };
+        methods.push(repr_c_codes_methods(
+            input.original,
+            to_body,
+            from_body,
+            std,
+        ));
+    }
+    if input.attrs.config.kind.is_some() {
+        let kind = input.kind_ident();
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let kind_variant = variant.kind_variant();
+            quote!(#ty::#ident {..} => #kind::#kind_variant)
+        });
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let body = quote! {
+            match #void_deref self {
+                #(#arms,)*
+            }
+        };
+        methods.push(kind_method(input.original, &kind, body));
+    }
+    if input.attrs.config.downcast.is_some() {
+        methods.push(downcast_method(input.original, std));
+    }
+    if input.attrs.config.root_cause.is_some() {
+        methods.push(root_cause_method(input.original, std));
+    }
+    if input.attrs.config.report.is_some() {
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let name = ident.unraw().to_string();
+            quote!(#ty::#ident {..} => #name,)
+        });
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let variant = quote! {
+            #std::option::Option::Some(match #void_deref self {
+                #(#arms)*
+            })
+        };
+        let code = if input.attrs.config.codes.is_some() {
+            quote!(#std::option::Option::Some(self.code()))
+        } else if input.attrs.config.discriminant_code.is_some() {
+            quote!(#thiserror::__private::report_code(self.code()))
+        } else {
+            quote!(#std::option::Option::None)
+        };
+        methods.push(report_method(input.original, variant, code, std, thiserror));
+    }
+    let has_exit_code = input.attrs.exit_code.is_some()
+        || input
+            .variants
+            .iter()
+            .any(|variant| variant.attrs.exit_code.is_some());
+    if has_exit_code {
+        let default = input
+            .attrs
+            .exit_code
+            .as_ref()
+            .map_or(1, |exit_code| exit_code.value);
+        let mut delegates = false;
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            if let Some(exit_code) = &variant.attrs.exit_code {
+                let value = exit_code.value;
+                quote!(#ty::#ident {..} => #value,)
+            } else if variant.attrs.transparent.is_some() {
+                delegates = true;
+                let member = &variant.fields[0].member;
+                quote! {
+                    #ty::#ident { #member: __inner } => (&ExitCodeValue(__inner))
+                        .inner_exit_code()
+                        .unwrap_or(#default),
+                }
+            } else {
+                quote!(#ty::#ident {..} => #default,)
+            }
+        });
+        let arms = arms.collect::<Vec<_>>();
+        let use_exit_code_value = if delegates {
+            Some(quote! {
+                use #thiserror::__private::{ExitCodeDefault, ExitCodeDelegate, ExitCodeValue};
+            })
+        } else {
+            None
+        };
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let body = quote! {
+            #use_exit_code_value
+            #[allow(deprecated)]
+            match #void_deref self {
+                #(#arms)*
             }
+        };
+        methods.push(exit_code_method(input.original, body));
+    }
+    if input.attrs.config.fields.is_some() {
+        let mut inferred_bounds = InferredBounds::new();
+        let arms: Vec<TokenStream> = input
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                let fields = context_fields(
+                    &variant.fields,
+                    variant.source_field(),
+                    variant.backtrace_field(),
+                    &mut inferred_bounds,
+                    std,
+                );
+                let vars = fields.iter().map(|(_name, ident)| ident);
+                let pairs = fields
+                    .iter()
+                    .map(|(name, ident)| quote!((#name, (&FieldValue(#ident)).field_string())));
+                quote! {
+                    #ty::#ident {#(#vars,)* ..} => #std::vec![#(#pairs),*],
+                }
+            })
+            .collect();
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        methods.push(fields_method(
+            input.original,
+            &inferred_bounds,
+            quote! {
+                #[allow(deprecated)]
+                match #void_deref self {
+                    #(#arms)*
+                }
+            },
+            std,
+            thiserror,
+        ));
+    }
+    if let Some(localizer) = &input.attrs.config.localize {
+        let mut inferred_bounds = InferredBounds::new();
+        let arms: Vec<TokenStream> = input
+            .variants
+            .iter()
+            .map(|variant| {
+                let ident = &variant.ident;
+                let args = localize_args(
+                    &variant.fields,
+                    variant.source_field(),
+                    variant.backtrace_field(),
+                    &mut inferred_bounds,
+                    std,
+                );
+                let bindings = args.iter().map(|(_name, member, binding)| match member {
+                    Member::Named(_) => quote!(#binding),
+                    Member::Unnamed(_) => quote!(#member: #binding),
+                });
+                let pairs = args.iter().map(|(name, _member, binding)| {
+                    quote!((#name, (&FieldValue(#binding)).field_string()))
+                });
+                quote! {
+                    #ty::#ident {#(#bindings,)* ..} => #std::vec![#(#pairs),*],
+                }
+            })
+            .collect();
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
         } else {
+            None
+        };
+        methods.push(localized_method(
+            input.original,
+            localizer,
+            &inferred_bounds,
+            quote! {
+                match #void_deref self {
+                    #(#arms)*
+                }
+            },
+            std,
+            thiserror,
+        ));
+    }
+    let related_fields: Vec<(&Variant, &Field)> = input
+        .variants
+        .iter()
+        .flat_map(|variant| variant.fields.iter().map(move |field| (variant, field)))
+        .filter(|(_variant, field)| field.attrs.related.is_some())
+        .collect();
+    if !related_fields.is_empty() {
+        let lists = related_fields.iter().map(|(variant, field)| {
+            let ident = &variant.ident;
+            let member = &field.member;
             quote! {
-                #backtrace_member: std::convert::From::from(std::backtrace::Backtrace::capture()),
+                match self {
+                    #ty::#ident {#member: related, ..} => #std::option::Option::Some(related),
+                    _ => #std::option::Option::None,
+                }
             }
-        }
-    });
-    quote!({
-        #from_member: #some_source,
-        #backtrace
-    })
-}
-
-fn type_is_option(ty: &Type) -> bool {
-    type_parameter_of_option(ty).is_some()
-}
-
-fn unoptional_type(ty: &Type) -> TokenStream {
-    let unoptional = type_parameter_of_option(ty).unwrap_or(ty);
-    quote!(#unoptional)
+        });
+        let fields: Vec<&Field> = related_fields
+            .iter()
+            .map(|(_variant, field)| *field)
+            .collect();
+        methods.push(related_method(
+            input.original,
+            &fields,
+            lists,
+            std,
+            thiserror,
+        ));
+    }
+    if let Some(span_trace_field) = input
+        .variants
+        .iter()
+        .find_map(|variant| variant.span_trace_field())
+    {
+        let span_trace_ty = unoptional_type(span_trace_field.ty);
+        // Only an error that has a span trace in every variant can return it
+        // without an Option.
+        let always = input.variants.iter().all(|variant| {
+            variant
+                .span_trace_field()
+                .map_or(false, |field| !type_is_option(field.ty))
+        });
+        let arms = input.variants.iter().filter_map(|variant| {
+            let field = variant.span_trace_field()?;
+            let ident = &variant.ident;
+            let member = &field.member;
+            let span_trace = if always {
+                quote!(span_trace)
+            } else if type_is_option(field.ty) {
+                quote!(span_trace.as_ref())
+            } else {
+                quote!(#std::option::Option::Some(span_trace))
+            };
+            Some(quote! {
+                #ty::#ident {#member: span_trace, ..} => #span_trace,
+            })
+        });
+        let (ret, fallback) = if always {
+            (quote!(&#span_trace_ty), None)
+        } else {
+            (
+                quote!(#std::option::Option<&#span_trace_ty>),
+                Some(quote!(_ => #std::option::Option::None,)),
+            )
+        };
+        methods.push(span_trace_method(
+            input.original,
+            ret,
+            quote! {
+                #[allow(deprecated)]
+                match self {
+                    #(#arms)*
+                    #fallback
+                }
+            },
+            thiserror,
+        ));
+    }
+
+    let exit_code_impl = if has_exit_code {
+        Some(exit_code_impl(input.original, &input.attrs, std, thiserror))
+    } else {
+        None
+    };
+    let tracing_impl = if input.attrs.level.is_some()
+        || input
+            .variants
+            .iter()
+            .any(|variant| variant.attrs.level.is_some())
+    {
+        let arms = input.variants.iter().map(|variant| {
+            let level = variant
+                .attrs
+                .level
+                .as_ref()
+                .or(input.attrs.level.as_ref())
+                .map_or_else(|| format_ident!("ERROR"), |level| level.ident.clone());
+            let ident = &variant.ident;
+            quote! {
+                #ty::#ident {..} => #thiserror::__private::tracing::Level::#level,
+            }
+        });
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        Some(tracing_impl(
+            input.original,
+            &input.attrs,
+            quote! {
+                match #void_deref self {
+                    #(#arms)*
+                }
+            },
+            std,
+            thiserror,
+        ))
+    } else {
+        None
+    };
+    let defmt_impl = if input.attrs.config.defmt.is_some() {
+        let mut defmt_inferred_bounds = InferredBounds::new();
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let pat = fields_pat(&variant.fields);
+            if let Some(display) = &variant.attrs.display {
+                let defmt = display.defmt.as_ref().unwrap();
+                for &field in &defmt.fields {
+                    let field = &variant.fields[field];
+                    if field.contains_generic && !field.recursive {
+                        defmt_inferred_bounds
+                            .insert(field.ty, quote!(#thiserror::__private::defmt::Format));
+                    }
+                }
+                let defmt = defmt.fmt_tokens(thiserror);
+                return quote! {
+                    #ty::#ident #pat => #defmt
+                };
+            }
+            let only_field = &variant.fields[0];
+            if only_field.contains_generic && !only_field.recursive {
+                defmt_inferred_bounds
+                    .insert(only_field.ty, quote!(#thiserror::__private::defmt::Format));
+            }
+            let var = match &only_field.member {
+                Member::Named(ident) => ident.clone(),
+                Member::Unnamed(index) => format_ident!("_{}", index),
+            };
+            quote! {
+                #ty::#ident #pat => defmt::Format::format(#var, __formatter)
+            }
+        });
+        let arms = arms.collect::<Vec<_>>();
+        let body = quote! {
+            #[allow(unused_variables, deprecated, clippy::used_underscore_binding)]
+            match #void_deref self {
+                #(#arms,)*
+            }
+        };
+        Some(defmt_impl(
+            input.original,
+            &defmt_inferred_bounds,
+            body,
+            thiserror,
+        ))
+    } else {
+        None
+    };
+
+    let serialize_impl = if input.attrs.config.serialize.is_some() {
+        let mut serialize_inferred_bounds = InferredBounds::new();
+        let name = input.ident.unraw().to_string();
+        let arms = input.variants.iter().enumerate().map(|(i, variant)| {
+            let entries = serialize_entries(
+                &input.attrs,
+                &variant.attrs,
+                &variant.fields,
+                quote!(SerializeStructVariant),
+                &mut serialize_inferred_bounds,
+                std,
+                thiserror,
+            );
+            let ident = &variant.ident;
+            let variant_name = ident.unraw().to_string();
+            let index = i as u32;
+            let len = entries.len();
+            let pat = fields_pat(&variant.fields);
+            quote! {
+                #[allow(unused_variables, deprecated)]
+                #ty::#ident #pat => {
+                    let mut __state =
+                        #thiserror::__private::serde::Serializer::serialize_struct_variant(
+                            __serializer,
+                            #name,
+                            #index,
+                            #variant_name,
+                            #len,
+                        )?;
+                    #(#entries)*
+                    #thiserror::__private::serde::ser::SerializeStructVariant::end(__state)
+                }
+            }
+        });
+        let arms = arms.collect::<Vec<_>>();
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let body = quote! {
+            match #void_deref self {
+                #(#arms)*
+            }
+        };
+        Some(serialize_impl(
+            input.original,
+            &serialize_inferred_bounds,
+            body,
+            std,
+            thiserror,
+        ))
+    } else {
+        None
+    };
+
+    let deserialize_impl = if input.attrs.config.deserialize.is_some() {
+        let names: Vec<String> = input.variants.iter().map(Variant::name).collect();
+        let arms = input.variants.iter().enumerate().map(|(i, variant)| {
+            let ident = &variant.ident;
+            quote!(#i => #ty::#ident {},)
+        });
+        let body = quote! {
+            match __index {
+                #(#arms)*
+                _ => #std::unreachable!(),
+            }
+        };
+        Some(deserialize_impl(
+            input.original,
+            &names,
+            body,
+            std,
+            thiserror,
+        ))
+    } else {
+        None
+    };
+
+    let ufmt_impl = if input.attrs.config.ufmt.is_some() {
+        let mut ufmt_inferred_bounds = InferredBounds::new();
+        let void_deref = if input.variants.is_empty() {
+            Some(quote!(*))
+        } else {
+            None
+        };
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let pat = fields_pat(&variant.fields);
+            if let Some(display) = &variant.attrs.display {
+                let ufmt = display.ufmt.as_ref().unwrap();
+                for &(field, bound) in &ufmt.bounds {
+                    let field = &variant.fields[field];
+                    if field.contains_generic && !field.recursive {
+                        ufmt_inferred_bounds.insert(field.ty, bound.path(thiserror));
+                    }
+                }
+                return quote! {
+                    #ty::#ident #pat => #ufmt
+                };
+            }
+            let only_field = &variant.fields[0];
+            if only_field.contains_generic && !only_field.recursive {
+                ufmt_inferred_bounds.insert(only_field.ty, UfmtTrait::Display.path(thiserror));
+            }
+            let var = match &only_field.member {
+                Member::Named(ident) => ident.clone(),
+                Member::Unnamed(index) => format_ident!("_{}", index),
+            };
+            quote! {
+                #ty::#ident #pat => ufmt::uDisplay::fmt(#var, __formatter)
+            }
+        });
+        let arms = arms.collect::<Vec<_>>();
+        let body = quote! {
+            #[allow(unused_variables, deprecated, clippy::used_underscore_binding)]
+            match #void_deref self {
+                #(#arms,)*
+            }
+        };
+        Some(ufmt_impl(
+            input.original,
+            &ufmt_inferred_bounds,
+            body,
+            std,
+            thiserror,
+        ))
+    } else {
+        None
+    };
+
+    let eq_impl = if input.attrs.config.eq.is_some() {
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::cmp::PartialEq for #ty #ty_generics #where_clause {
+                fn eq(&self, other: &Self) -> bool {
+                    #std::mem::discriminant(self) == #std::mem::discriminant(other)
+                }
+            }
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::cmp::Eq for #ty #ty_generics #where_clause {}
+        })
+    } else {
+        None
+    };
+
+    let debug_transparent = input.attrs.config.debug_transparent.is_some();
+    let debug_impl = if debug_transparent || input.attrs.config.debug.is_some() {
+        let mut debug_inferred_bounds = InferredBounds::new();
+        let arms = input.variants.iter().map(|variant| {
+            let ident = &variant.ident;
+            let pat = fields_pat(&variant.fields);
+            if debug_transparent && variant.attrs.transparent.is_some() {
+                let only_field = &variant.fields[0];
+                if only_field.contains_generic && !only_field.recursive {
+                    debug_inferred_bounds.insert(only_field.ty, quote!(#std::fmt::Debug));
+                }
+                let only_field = match &only_field.member {
+                    Member::Named(ident) => ident.clone(),
+                    Member::Unnamed(index) => format_ident!("_{}", index),
+                };
+                return quote! {
+                    #ty::#ident #pat => #std::fmt::Debug::fmt(#only_field, __formatter)
+                };
+            }
+            let name = ident.unraw().to_string();
+            let debug = debug_fields(
+                &name,
+                &variant.fields,
+                &mut debug_inferred_bounds,
+                std,
+                thiserror,
+            );
+            quote! {
+                #ty::#ident #pat => #debug
+            }
+        });
+        let arms = arms.collect::<Vec<_>>();
+        let body = if arms.is_empty() {
+            quote!(match *self {})
+        } else {
+            quote! {
+                #[allow(unused_variables, deprecated, clippy::used_underscore_binding)]
+                match self {
+                    #(#arms,)*
+                }
+            }
+        };
+        let debug_where_clause = debug_inferred_bounds.augment_where_clause(input.generics);
+        let inline = inline_attr();
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::fmt::Debug for #ty #ty_generics #debug_where_clause {
+                #inline
+                fn fmt(&self, __formatter: &mut #std::fmt::Formatter) -> #std::fmt::Result {
+                    #body
+                }
+            }
+        })
+    } else {
+        None
+    };
+    let context_impls = input
+        .attrs
+        .config
+        .context
+        .map(|_| context_impls(&input, std, thiserror));
+    let hash_impl = if input.attrs.config.hash_ignore_source.is_some() {
+        let mut hash_inferred_bounds = InferredBounds::new();
+        let body = if input.variants.is_empty() {
+            quote!(match *self {})
+        } else {
+            let arms = input.variants.iter().map(|variant| {
+                let fields = variant.data_fields();
+                for field in &fields {
+                    if field.contains_generic && !field.recursive {
+                        hash_inferred_bounds.insert(field.ty, quote!(#std::hash::Hash));
+                    }
+                }
+                let ident = &variant.ident;
+                let pat = fields.iter().map(|field| match &field.member {
+                    Member::Named(ident) => quote!(#ident),
+                    Member::Unnamed(index) => {
+                        let var = format_ident!("_{}", index);
+                        quote!(#index: #var)
+                    }
+                });
+                let vars = fields.iter().map(|field| match &field.member {
+                    Member::Named(ident) => ident.clone(),
+                    Member::Unnamed(index) => format_ident!("_{}", index),
+                });
+                quote! {
+                    #ty::#ident { #(#pat,)* .. } => {
+                        #(#std::hash::Hash::hash(#vars, __state);)*
+                    }
+                }
+            });
+            let arms = arms.collect::<Vec<_>>();
+            quote! {
+                #std::hash::Hash::hash(&#std::mem::discriminant(self), __state);
+                #[allow(deprecated)]
+                match self {
+                    #(#arms)*
+                }
+            }
+        };
+        Some(hash_impl(
+            &input.ident,
+            input.generics,
+            &hash_inferred_bounds,
+            body,
+            std,
+        ))
+    } else {
+        None
+    };
+
+    let clone_impl = if input.attrs.config.clone.is_some() {
+        let mut clone_inferred_bounds = InferredBounds::new();
+        let body = if input.variants.is_empty() {
+            quote!(match *self {})
+        } else {
+            let arms = input.variants.iter().map(|variant| {
+                for field in &variant.fields {
+                    if field.contains_generic && !field.recursive {
+                        clone_inferred_bounds.insert(field.ty, quote!(#std::clone::Clone));
+                    }
+                }
+                let ident = &variant.ident;
+                let members = variant
+                    .fields
+                    .iter()
+                    .map(|field| &field.member)
+                    .collect::<Vec<_>>();
+                let vars = members
+                    .iter()
+                    .map(|member| match member {
+                        Member::Named(ident) => ident.clone(),
+                        Member::Unnamed(index) => format_ident!("_{}", index),
+                    })
+                    .collect::<Vec<_>>();
+                let pats = members.iter().zip(&vars).map(|(member, var)| match member {
+                    Member::Named(_) => quote!(#var),
+                    Member::Unnamed(index) => quote!(#index: #var),
+                });
+                quote! {
+                    #ty::#ident { #(#pats),* } => #ty::#ident {
+                        #(#members: #std::clone::Clone::clone(#vars),)*
+                    }
+                }
+            });
+            let arms = arms.collect::<Vec<_>>();
+            quote! {
+                #[allow(deprecated)]
+                match self {
+                    #(#arms,)*
+                }
+            }
+        };
+        Some(clone_impl(
+            &input.ident,
+            input.generics,
+            &clone_inferred_bounds,
+            body,
+            std,
+        ))
+    } else {
+        None
+    };
+
+    let partial_eq_impl = if input.attrs.config.partial_eq.is_some() {
+        let mut eq_inferred_bounds = InferredBounds::new();
+        let body = if input.variants.is_empty() {
+            quote!(match *self {})
+        } else {
+            let arms = input.variants.iter().map(|variant| {
+                let mut fields = variant.data_fields();
+                for field in &fields {
+                    if field.contains_generic && !field.recursive {
+                        eq_inferred_bounds.insert(field.ty, quote!(#std::cmp::PartialEq));
+                    }
+                }
+                let mut comparisons = fields
+                    .iter()
+                    .map(|field| {
+                        let (this, other) = partial_eq_vars(field);
+                        quote!(#this == #other)
+                    })
+                    .collect::<Vec<_>>();
+                if input.attrs.config.partial_eq_message {
+                    if let Some(field) = variant.message_field() {
+                        let (this, other) = partial_eq_vars(field);
+                        comparisons.push(message_eq(
+                            field,
+                            &mut eq_inferred_bounds,
+                            quote!(#this),
+                            quote!(#other),
+                            std,
+                        ));
+                        fields.push(field);
+                    }
+                }
+                let this_pat = fields.iter().map(|field| match &field.member {
+                    Member::Named(ident) => quote!(#ident),
+                    Member::Unnamed(index) => {
+                        let (this, _) = partial_eq_vars(field);
+                        quote!(#index: #this)
+                    }
+                });
+                let other_pat = fields.iter().map(|field| {
+                    let (_, other) = partial_eq_vars(field);
+                    let member = &field.member;
+                    quote!(#member: #other)
+                });
+                let ident = &variant.ident;
+                let body = if comparisons.is_empty() {
+                    quote!(true)
+                } else {
+                    quote!(#(#comparisons)&&*)
+                };
+                quote! {
+                    (
+                        #ty::#ident { #(#this_pat,)* .. },
+                        #ty::#ident { #(#other_pat,)* .. },
+                    ) => #body,
+                }
+            });
+            let arms = arms.collect::<Vec<_>>();
+            quote! {
+                #[allow(deprecated, unreachable_patterns)]
+                match (self, other) {
+                    #(#arms)*
+                    _ => false,
+                }
+            }
+        };
+        Some(partial_eq_impl(
+            &input.ident,
+            input.generics,
+            &eq_inferred_bounds,
+            body,
+            std,
+        ))
+    } else {
+        None
+    };
+
+    if input.attrs.config.require_debug.is_some() {
+        methods.push(require_debug_method(input.ident.span(), thiserror));
+    }
+    let error_impl = if input.attrs.config.no_error_impl.is_none() {
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #error_trait for #ty #ty_generics #error_where_clause {
+                #source_method
+                #provide_method
+                #description_method
+            }
+        })
+    } else {
+        None
+    };
+    let inherent_impl = inherent_impl(input.original, &input.attrs, methods);
+
+    quote! {
+        #error_impl
+        #inherent_impl
+        #exit_code_impl
+        #tracing_impl
+        #display_impl
+        #debug_impl
+        #defmt_impl
+        #ufmt_impl
+        #serialize_impl
+        #deserialize_impl
+        #eq_impl
+        #clone_impl
+        #partial_eq_impl
+        #hash_impl
+        #context_impls
+        #(#from_impls)*
+        #(#from_via_impls)*
+        #(#from_ref_impls)*
+        #(#try_into_impls)*
+        #(#try_from_impls)*
+    }
+}
+
+// #[thiserror(clone = "arc_sources")]
+fn clone_impl(
+    ty: &Ident,
+    generics: &Generics,
+    inferred_bounds: &InferredBounds,
+    body: TokenStream,
+    std: &TokenStream,
+) -> TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let where_clause = inferred_bounds.augment_where_clause(generics);
+    quote! {
+        #[allow(unused_qualifications)]
+        impl #impl_generics #std::clone::Clone for #ty #ty_generics #where_clause {
+            fn clone(&self) -> Self {
+                #body
+            }
+        }
+    }
+}
+
+// #[thiserror(partial_eq)]
+fn partial_eq_impl(
+    ty: &Ident,
+    generics: &Generics,
+    inferred_bounds: &InferredBounds,
+    body: TokenStream,
+    std: &TokenStream,
+) -> TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let where_clause = inferred_bounds.augment_where_clause(generics);
+    quote! {
+        #[allow(unused_qualifications)]
+        impl #impl_generics #std::cmp::PartialEq for #ty #ty_generics #where_clause {
+            fn eq(&self, other: &Self) -> bool {
+                #body
+            }
+        }
+    }
+}
+
+// The bindings of a field of either side in the match of a generated
+// PartialEq impl for an enum.
+fn partial_eq_vars(field: &Field) -> (Ident, Ident) {
+    let this = match &field.member {
+        Member::Named(ident) => ident.clone(),
+        Member::Unnamed(index) => format_ident!("_{}", index),
+    };
+    let other = format_ident!("__other{}", this);
+    (this, other)
+}
+
+// Compares two sources by their Display output, under
+// #[thiserror(partial_eq = "message")].
+fn message_eq(
+    field: &Field,
+    inferred_bounds: &mut InferredBounds,
+    this: TokenStream,
+    other: TokenStream,
+    std: &TokenStream,
+) -> TokenStream {
+    if field.contains_generic && !field.recursive {
+        inferred_bounds.insert(unoptional_type(field.ty), quote!(#std::fmt::Display));
+    }
+    if type_is_option(field.ty) {
+        quote! {
+            #std::option::Option::map(
+                #std::option::Option::as_ref(#this),
+                #std::string::ToString::to_string,
+            ) == #std::option::Option::map(
+                #std::option::Option::as_ref(#other),
+                #std::string::ToString::to_string,
+            )
+        }
+    } else {
+        quote! {
+            #std::string::ToString::to_string(#this) == #std::string::ToString::to_string(#other)
+        }
+    }
+}
+
+// #[thiserror(hash_ignore_source)]
+fn hash_impl(
+    ty: &Ident,
+    generics: &Generics,
+    inferred_bounds: &InferredBounds,
+    body: TokenStream,
+    std: &TokenStream,
+) -> TokenStream {
+    let (impl_generics, ty_generics, _) = generics.split_for_impl();
+    let where_clause = inferred_bounds.augment_where_clause(generics);
+    quote! {
+        #[allow(unused_qualifications)]
+        impl #impl_generics #std::hash::Hash for #ty #ty_generics #where_clause {
+            fn hash<__H: #std::hash::Hasher>(&self, __state: &mut __H) {
+                #body
+            }
+        }
+    }
+}
+
+// The body of a Debug impl in the shape #[derive(Debug)] gives it, over the
+// fields bound by fields_pat, with *** written in place of each #[redact] field.
+fn debug_fields(
+    name: &str,
+    fields: &[Field],
+    inferred_bounds: &mut InferredBounds,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let values = fields.iter().map(|field| {
+        if field.attrs.redact.is_some() {
+            return quote!(&#thiserror::__private::RedactedDebug);
+        }
+        if field.contains_generic && !field.recursive {
+            inferred_bounds.insert(field.ty, quote!(#std::fmt::Debug));
+        }
+        match &field.member {
+            Member::Named(ident) => quote!(#ident),
+            Member::Unnamed(index) => format_ident!("_{}", index).into_token_stream(),
+        }
+    });
+    match fields.first().map(|field| &field.member) {
+        Some(Member::Named(_)) => {
+            let names = fields.iter().map(|field| match &field.member {
+                Member::Named(ident) => ident.unraw().to_string(),
+                Member::Unnamed(_) => unreachable!(),
+            });
+            quote!(__formatter.debug_struct(#name)#(.field(#names, #values))*.finish())
+        }
+        Some(Member::Unnamed(_)) => {
+            quote!(__formatter.debug_tuple(#name)#(.field(#values))*.finish())
+        }
+        None => quote!(__formatter.write_str(#name)),
+    }
+}
+
+fn fields_pat(fields: &[Field]) -> TokenStream {
+    let mut members = fields.iter().map(|field| &field.member).peekable();
+    match members.peek() {
+        Some(Member::Named(_)) => quote!({ #(#members),* }),
+        Some(Member::Unnamed(_)) => {
+            let vars = members.map(|member| match member {
+                Member::Unnamed(member) => format_ident!("_{}", member),
+                Member::Named(_) => unreachable!(),
+            });
+            quote!((#(#vars),*))
+        }
+        None => quote!({}),
+    }
+}
+
+fn from_initializer<'a, 'b: 'a>(
+    from_field: &Field,
+    backtrace_field: Option<&Field>,
+    span_trace_field: Option<&Field>,
+    phantom_fields: impl Iterator<Item = &'a Field<'b>>,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let from_member = &from_field.member;
+    let some_source = if type_is_option(from_field.ty) {
+        quote!(#std::option::Option::Some(source))
+    } else {
+        quote!(source)
+    };
+    // The source is borrowed to look for its backtrace before it is moved into
+    // its field, so that initializer goes first.
+    let from_source = backtrace_field.filter(|backtrace_field| {
+        backtrace_field
+            .attrs
+            .backtrace
+            .as_ref()
+            .map_or(false, |backtrace| backtrace.from_source)
+    });
+    let reused_backtrace =
+        from_source.map(|field| reused_backtrace_initializer(field, std, thiserror));
+    let backtrace_field = backtrace_field.filter(|_| from_source.is_none());
+    let filled = filled_initializers(backtrace_field, span_trace_field, phantom_fields, std);
+    quote!({
+        #reused_backtrace
+        #from_member: #some_source,
+        #filled
+    })
+}
+
+// #[backtrace(from_source)]: a disabled backtrace, or None, in place of a new
+// capture if the source already provides one. provide() hands out the source's
+// backtrace ahead of the field's.
+fn reused_backtrace_initializer(
+    backtrace_field: &Field,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let backtrace_member = &backtrace_field.member;
+    let capture = backtrace_capture(backtrace_field, std);
+    let (reused, captured) = if type_is_option(backtrace_field.ty) {
+        (
+            quote!(#std::option::Option::None),
+            quote!(#std::option::Option::Some(#std::convert::From::from(#capture))),
+        )
+    } else {
+        (
+            quote!(#std::convert::From::from(
+                #std::backtrace::Backtrace::disabled()
+            )),
+            quote!(#std::convert::From::from(#capture)),
+        )
+    };
+    quote! {
+        #backtrace_member: if #thiserror::__private::provides_backtrace(
+            #thiserror::__private::AsDynError::as_dyn_error(&source),
+        ) {
+            #reused
+        } else {
+            #captured
+        },
+    }
+}
+
+// Initializers of the fields that a generated From impl or constructor fills
+// in by itself: the backtrace and span trace, captured where the error is
+// created, and any PhantomData markers.
+fn filled_initializers<'a, 'b: 'a>(
+    backtrace_field: Option<&Field>,
+    span_trace_field: Option<&Field>,
+    phantom_fields: impl Iterator<Item = &'a Field<'b>>,
+    std: &TokenStream,
+) -> TokenStream {
+    let backtrace = backtrace_field.map(|backtrace_field| {
+        let backtrace_member = &backtrace_field.member;
+        let capture = backtrace_capture(backtrace_field, std);
+        if type_is_option(backtrace_field.ty) {
+            quote! {
+                #backtrace_member: #std::option::Option::Some(#std::convert::From::from(#capture)),
+            }
+        } else {
+            quote! {
+                #backtrace_member: #std::convert::From::from(#capture),
+            }
+        }
+    });
+    let span_trace = span_trace_field.map(|span_trace_field| {
+        let span_trace_member = &span_trace_field.member;
+        let span_trace_ty = unoptional_type(span_trace_field.ty);
+        let capture = quote!(<#span_trace_ty>::capture());
+        if type_is_option(span_trace_field.ty) {
+            quote! {
+                #span_trace_member: #std::option::Option::Some(#capture),
+            }
+        } else {
+            quote! {
+                #span_trace_member: #capture,
+            }
+        }
+    });
+    let phantom = phantom_fields.map(|phantom_field| {
+        let phantom_member = &phantom_field.member;
+        quote! {
+            #phantom_member: #std::marker::PhantomData,
+        }
+    });
+    quote! {
+        #backtrace
+        #span_trace
+        #(#phantom)*
+    }
+}
+
+// Expression producing the Backtrace stored by a generated From impl.
+fn backtrace_capture(backtrace_field: &Field, std: &TokenStream) -> TokenStream {
+    let backtrace = backtrace_field.attrs.backtrace.as_ref();
+    let capture = if backtrace.map_or(false, |backtrace| backtrace.force) {
+        quote!(#std::backtrace::Backtrace::force_capture())
+    } else {
+        quote!(#std::backtrace::Backtrace::capture())
+    };
+    match backtrace.and_then(|backtrace| backtrace.disabled_unless.as_ref()) {
+        Some(feature) => quote!({
+            #[cfg(feature = #feature)]
+            let backtrace = #capture;
+            #[cfg(not(feature = #feature))]
+            let backtrace = #std::backtrace::Backtrace::disabled();
+            backtrace
+        }),
+        None => capture,
+    }
+}
+
+fn use_as_display(attrs: &Attrs, thiserror: &TokenStream) -> TokenStream {
+    if attrs.config.no_std.is_some() {
+        // Path and OsStr are only exported by thiserror's std feature.
+        quote! {
+            #[allow(unused_imports)]
+            use #thiserror::__private::{DisplayAsDisplay, JoinAsDisplay};
+        }
+    } else {
+        quote! {
+            #[allow(unused_imports)]
+            use #thiserror::__private::{
+                DisplayAsDisplay, JoinAsDisplay, OsStrAsDisplay, PathAsDisplay,
+            };
+        }
+    }
+}
+
+// Match arms routing an io::Error into the variants declared with
+// #[from(kind = ...)] for the given source type.
+fn kind_dispatch_arms(
+    input: &Enum,
+    from_ty: &Type,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> Vec<TokenStream> {
+    let repr = from_ty.to_token_stream().to_string();
+    let mut arms = Vec::new();
+    for variant in &input.variants {
+        let from_field = match variant.from_field() {
+            Some(from_field) => from_field,
+            None => continue,
+        };
+        let kinds = &from_field.attrs.from.as_ref().unwrap().kinds;
+        if kinds.is_empty() || from_field.ty.to_token_stream().to_string() != repr {
+            continue;
+        }
+        let backtrace_field = variant.distinct_backtrace_field();
+        let span_trace_field = variant.span_trace_field();
+        let body = from_initializer(
+            from_field,
+            backtrace_field,
+            span_trace_field,
+            variant.phantom_fields(),
+            std,
+            thiserror,
+        );
+        let ty = &input.ident;
+        let ident = &variant.ident;
+        arms.push(quote! {
+            #(#std::io::ErrorKind::#kinds)|* => #ty::#ident #body,
+        });
+    }
+    arms
+}
+
+fn from_ref_to_owned(by_ref: &FromRef, std: &TokenStream) -> TokenStream {
+    match &by_ref.with {
+        Some(with) => quote!(#with),
+        None => quote!(#std::clone::Clone::clone),
+    }
+}
+
+// Generics for `impl From<&'__source E>`, requiring `E: Clone` if the field is
+// generic and no `with` function was given.
+// #[from(into = Inner)]: From<T> for every T: Into<Inner>.
+// #[from(via = Lower)] or #[flatten(Lower)], converting into the #[from]
+// field's type first and then through the From impl generated for it.
+fn from_via_impls(
+    input: &DeriveInput,
+    attrs: &Attrs,
+    from_field: &Field,
+    flattened: &[Type],
+    std: &TokenStream,
+) -> TokenStream {
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let from = unoptional_type(from_field.ty);
+    let vias = &from_field.attrs.from.as_ref().unwrap().via;
+    // Under #[thiserror(clone = "arc_sources")], the error inside an Arc
+    // converts by way of the Arc.
+    let arc_source = if attrs.config.clone.is_some() {
+        from_field.arc_source_type()
+    } else {
+        None
+    };
+    let impls = vias.iter().chain(arc_source).chain(flattened).map(|via| {
+        let mut inferred_bounds = InferredBounds::new();
+        if from_field.contains_generic {
+            inferred_bounds.insert(&from, quote!(#std::convert::From<#via>));
+        }
+        let where_clause = inferred_bounds.augment_where_clause(&input.generics);
+        let inline = inline_attr();
+        quote! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::convert::From<#via> for #ty #ty_generics #where_clause {
+                #inline
+                fn from(source: #via) -> Self {
+                    let source: #from = #std::convert::From::from(source);
+                    #std::convert::From::from(source)
+                }
+            }
+        }
+    });
+    quote!(#(#impls)*)
+}
+
+fn from_into_impl(
+    input: &DeriveInput,
+    into: &Type,
+    body: TokenStream,
+    std: &TokenStream,
+) -> TokenStream {
+    let ty = &input.ident;
+    let (_, ty_generics, _) = input.generics.split_for_impl();
+    let mut inferred_bounds = InferredBounds::new();
+    inferred_bounds.insert(quote!(__Source), quote!(#std::convert::Into<#into>));
+    let where_clause = inferred_bounds.augment_where_clause(&input.generics);
+    let mut generics = input.generics.clone();
+    generics.params.push(parse_quote!(__Source));
+    let (impl_generics, _, _) = generics.split_for_impl();
+    let inline = inline_attr();
+    quote! {
+        #[allow(unused_qualifications)]
+        impl #impl_generics #std::convert::From<__Source> for #ty #ty_generics #where_clause {
+            #inline
+            #[allow(deprecated)]
+            fn from(source: __Source) -> Self {
+                let source: #into = #std::convert::Into::into(source);
+                #body
+            }
+        }
+    }
+}
+
+fn from_ref_generics(
+    from_field: &Field,
+    by_ref: &FromRef,
+    generics: &Generics,
+    std: &TokenStream,
+) -> (Generics, WhereClause) {
+    let mut inferred_bounds = InferredBounds::new();
+    if from_field.contains_generic && !from_field.recursive && by_ref.with.is_none() {
+        let from = unoptional_type(from_field.ty);
+        inferred_bounds.insert(from, quote!(#std::clone::Clone));
+    }
+    let where_clause = inferred_bounds.augment_where_clause(generics);
+    let mut generics = generics.clone();
+    generics.params.insert(0, parse_quote!('__source));
+    (generics, where_clause)
+}
+
+// The `with` function returns the fields of the variant: nothing for a unit
+// variant, the bare value for a single field, otherwise a tuple of all fields
+// in declaration order.
+fn try_from_body(
+    path: TokenStream,
+    try_from: &TryFrom,
+    fields: &[Field],
+    std: &TokenStream,
+) -> TokenStream {
+    let with = &try_from.with;
+    let vars = fields.iter().map(|field| match &field.member {
+        Member::Named(ident) => ident.clone(),
+        Member::Unnamed(index) => format_ident!("_{}", index),
+    });
+    let vars = vars.collect::<Vec<_>>();
+    let pat = match vars.as_slice() {
+        [var] => quote!(#var),
+        vars => quote!((#(#vars),*)),
+    };
+    let inits = fields
+        .iter()
+        .zip(&vars)
+        .map(|(field, var)| match &field.member {
+            Member::Named(_) => quote!(#var),
+            Member::Unnamed(index) => quote!(#index: #var),
+        });
+    quote! {
+        let #pat = #with(source)?;
+        #std::result::Result::Ok(#path { #(#inits),* })
+    }
+}
+
+// #[thiserror(constructors)]
+fn constructor_method(input: &DeriveInput, variant: &Variant, std: &TokenStream) -> TokenStream {
+    let vis = &input.vis;
+    let ty = &input.ident;
+    let ident = &variant.ident;
+    let constructor = variant.constructor_ident().unwrap();
+    let source_field = variant.source_field();
+    let backtrace_field = variant.backtrace_field().filter(|backtrace_field| {
+        source_field.map_or(true, |source_field| {
+            source_field.member != backtrace_field.member
+        })
+    });
+    let span_trace_field = variant.span_trace_field();
+    let is_same = |field: &Field, other: Option<&Field>| {
+        other.map_or(false, |other| other.member == field.member)
+    };
+    let mut params = Vec::new();
+    let mut inits = Vec::new();
+    for field in &variant.fields {
+        if is_same(field, backtrace_field)
+            || is_same(field, span_trace_field)
+            || type_is_phantom_data(field.ty)
+        {
+            continue;
+        }
+        let member = &field.member;
+        let var = match member {
+            Member::Named(ident) => ident.clone(),
+            Member::Unnamed(index) => format_ident!("_{}", index),
+        };
+        let field_ty = field.ty;
+        if type_is_into_param(field_ty) {
+            params.push(quote!(#var: impl #std::convert::Into<#field_ty>));
+            inits.push(quote!(#member: #std::convert::Into::into(#var),));
+        } else {
+            params.push(quote!(#var: #field_ty));
+            inits.push(match member {
+                Member::Named(_) => quote!(#var,),
+                Member::Unnamed(_) => quote!(#member: #var,),
+            });
+        }
+    }
+    let filled = filled_initializers(
+        backtrace_field,
+        span_trace_field,
+        variant.phantom_fields(),
+        std,
+    );
+    let doc = format!("Creates the `{}` variant of this error.", ident.unraw());
+    quote! {
+        #[doc = #doc]
+        #vis fn #constructor(#(#params),*) -> Self {
+            #ty::#ident {
+                #(#inits)*
+                #filled
+            }
+        }
+    }
+}
+
+// #[thiserror(predicates)]
+fn predicate_method(input: &DeriveInput, variant: &Variant) -> TokenStream {
+    let vis = &input.vis;
+    let ty = &input.ident;
+    let ident = &variant.ident;
+    let predicate = variant.predicate_ident();
+    let doc = format!(
+        "Returns true if this is the `{}` variant of this error.",
+        ident.unraw(),
+    );
+    quote! {
+        #[doc = #doc]
+        #[must_use]
+        #vis fn #predicate(&self) -> bool {
+            #[allow(unreachable_patterns)]
+            match self {
+                #ty::#ident {..} => true,
+                _ => false,
+            }
+        }
+    }
+}
+
+// #[thiserror(accessors)] on a variant with fields. A single field is returned
+// bare, several as a tuple in declaration order.
+fn accessor_methods(input: &DeriveInput, variant: &Variant, std: &TokenStream) -> TokenStream {
+    let vis = &input.vis;
+    let ty = &input.ident;
+    let ident = &variant.ident;
+    let (as_ident, into_ident) = variant.accessor_idents();
+    let pat = fields_pat(&variant.fields);
+    let vars: Vec<Ident> = variant
+        .fields
+        .iter()
+        .map(|field| match &field.member {
+            Member::Named(ident) => ident.clone(),
+            Member::Unnamed(index) => format_ident!("_{}", index),
+        })
+        .collect();
+    let types: Vec<&Type> = variant.fields.iter().map(|field| field.ty).collect();
+    let (ref_ty, owned_ty, value) = if variant.fields.len() == 1 {
+        let ty = types[0];
+        let var = &vars[0];
+        (quote!(&#ty), quote!(#ty), quote!(#var))
+    } else {
+        (
+            quote!((#(&#types),*)),
+            quote!((#(#types),*)),
+            quote!((#(#vars),*)),
+        )
+    };
+    let as_doc = format!(
+        "Returns the fields of this error if it is the `{}` variant.",
+        ident.unraw(),
+    );
+    let into_doc = format!(
+        "Converts this error into its fields if it is the `{}` variant.",
+        ident.unraw(),
+    );
+    quote! {
+        #[doc = #as_doc]
+        #vis fn #as_ident(&self) -> #std::option::Option<#ref_ty> {
+            #[allow(unreachable_patterns)]
+            match self {
+                #ty::#ident #pat => #std::option::Option::Some(#value),
+                _ => #std::option::Option::None,
+            }
+        }
+
+        #[doc = #into_doc]
+        #vis fn #into_ident(self) -> #std::option::Option<#owned_ty> {
+            #[allow(unreachable_patterns)]
+            match self {
+                #ty::#ident #pat => #std::option::Option::Some(#value),
+                _ => #std::option::Option::None,
+            }
+        }
+    }
+}
+
+// #[thiserror(inner_accessors)] on a transparent struct.
+fn inner_accessor_methods(input: &DeriveInput, only_field: &Field) -> TokenStream {
+    let vis = &input.vis;
+    let member = &only_field.member;
+    let inner = only_field.ty;
+    quote! {
+        /// Consumes this error, returning the error it wraps.
+        #vis fn into_inner(self) -> #inner {
+            self.#member
+        }
+
+        /// Returns a reference to the error this error wraps.
+        #vis fn get_ref(&self) -> &#inner {
+            &self.#member
+        }
+
+        /// Returns a mutable reference to the error this error wraps.
+        #vis fn get_mut(&mut self) -> &mut #inner {
+            &mut self.#member
+        }
+    }
+}
+
+// #[thiserror(inner_accessors)] on a transparent variant, returning None for
+// any other variant.
+fn variant_inner_accessor_methods(
+    input: &DeriveInput,
+    variant: &Variant,
+    std: &TokenStream,
+) -> TokenStream {
+    let vis = &input.vis;
+    let ty = &input.ident;
+    let ident = &variant.ident;
+    let (as_ident, as_mut_ident, into_ident) = variant.inner_accessor_idents();
+    let pat = fields_pat(&variant.fields);
+    let var = match &variant.fields[0].member {
+        Member::Named(ident) => ident.clone(),
+        Member::Unnamed(index) => format_ident!("_{}", index),
+    };
+    let inner = variant.fields[0].ty;
+    let as_doc = format!(
+        "Returns the error wrapped by this error if it is the `{}` variant.",
+        ident.unraw(),
+    );
+    let as_mut_doc = format!(
+        "Returns a mutable reference to the error wrapped by this error if it is the `{}` variant.",
+        ident.unraw(),
+    );
+    let into_doc = format!(
+        "Converts this error into the error it wraps if it is the `{}` variant.",
+        ident.unraw(),
+    );
+    quote! {
+        #[doc = #as_doc]
+        #vis fn #as_ident(&self) -> #std::option::Option<&#inner> {
+            #[allow(unreachable_patterns)]
+            match self {
+                #ty::#ident #pat => #std::option::Option::Some(#var),
+                _ => #std::option::Option::None,
+            }
+        }
+
+        #[doc = #as_mut_doc]
+        #vis fn #as_mut_ident(&mut self) -> #std::option::Option<&mut #inner> {
+            #[allow(unreachable_patterns)]
+            match self {
+                #ty::#ident #pat => #std::option::Option::Some(#var),
+                _ => #std::option::Option::None,
+            }
+        }
+
+        #[doc = #into_doc]
+        #vis fn #into_ident(self) -> #std::option::Option<#inner> {
+            #[allow(unreachable_patterns)]
+            match self {
+                #ty::#ident #pat => #std::option::Option::Some(#var),
+                _ => #std::option::Option::None,
+            }
+        }
+    }
+}
+
+// Owned string-like field types, which a generated constructor accepts as
+// `impl Into<_>` so that callers can pass a `&str` or `&Path`.
+fn type_is_into_param(ty: &Type) -> bool {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return false,
+    };
+    let last = path.segments.last().unwrap();
+    (last.ident == "String" || last.ident == "PathBuf" || last.ident == "OsString")
+        && last.arguments.is_empty()
+}
+
+// #[thiserror(setters)] on an error whose source field is an Option.
+fn setter_methods(
+    input: &DeriveInput,
+    source_ty: TokenStream,
+    set_source: TokenStream,
+    std: &TokenStream,
+) -> TokenStream {
+    let vis = &input.vis;
+    quote! {
+        /// Fills in the source of this error, consuming and returning it.
+        #vis fn with_source(mut self, source: impl #std::convert::Into<#source_ty>) -> Self {
+            self.set_source(source);
+            self
+        }
+
+        /// Fills in the source of this error.
+        #vis fn set_source(&mut self, source: impl #std::convert::Into<#source_ty>) {
+            #set_source
+        }
+    }
+}
+
+// #[thiserror(context_setter)] on a struct with a `context: String` or
+// `context: Option<String>` field.
+fn context_setter_method(
+    input: &DeriveInput,
+    context_field: &Field,
+    std: &TokenStream,
+) -> TokenStream {
+    let vis = &input.vis;
+    let member = &context_field.member;
+    let optional = type_is_option(context_field.ty);
+    let string_ty = unoptional_type(context_field.ty);
+    let append = if optional {
+        quote! {
+            match &mut self.#member {
+                #std::option::Option::Some(existing) => {
+                    existing.push_str(": ");
+                    existing.push_str(&context);
+                }
+                #std::option::Option::None => self.#member = #std::option::Option::Some(context),
+            }
+        }
+    } else {
+        quote! {
+            if self.#member.is_empty() {
+                self.#member = context;
+            } else {
+                self.#member.push_str(": ");
+                self.#member.push_str(&context);
+            }
+        }
+    };
+    quote! {
+        /// Appends to the context of this error, separated from any context
+        /// already there by `": "`, consuming and returning it.
+        #vis fn with_context(mut self, context: impl #std::convert::Into<#string_ty>) -> Self {
+            let context: #string_ty = #std::convert::Into::into(context);
+            #append
+            self
+        }
+    }
+}
+
+// #[thiserror(static_messages)]
+fn message_method(input: &DeriveInput, body: TokenStream) -> TokenStream {
+    let vis = &input.vis;
+    quote! {
+        /// Returns the message of this error, which is also what its Display
+        /// impl writes.
+        #vis const fn message(&self) -> &'static str {
+            #body
+        }
+    }
+}
+
+// #[thiserror(discriminant_code)]
+fn code_method(input: &DeriveInput, body: TokenStream) -> TokenStream {
+    let vis = &input.vis;
+    quote! {
+        /// Returns the code of this error, which is the discriminant of its
+        /// variant unless overridden by #[error(code = ...)].
+        #vis fn code(&self) -> i64 {
+            #body
+        }
+    }
+}
+
+// #[thiserror(kind)]
+fn kind_method(input: &DeriveInput, kind: &Ident, body: TokenStream) -> TokenStream {
+    let vis = &input.vis;
+    quote! {
+        /// Returns the kind of this error, which identifies its variant
+        /// without its fields.
+        #vis fn kind(&self) -> #kind {
+            #body
+        }
+    }
+}
+
+// The fieldless enum generated by #[thiserror(kind)], with a variant per
+// variant of the error, except where #[error(kind = ...)] maps several of them
+// to the same kind.
+fn kind_enum(input: &Enum) -> TokenStream {
+    let vis = &input.original.vis;
+    let kind = input.kind_ident();
+    let mut kinds: Vec<(&Ident, Vec<&Ident>)> = Vec::new();
+    for variant in &input.variants {
+        let kind_variant = variant.kind_variant();
+        match kinds.iter_mut().find(|(ident, _)| *ident == kind_variant) {
+            Some((_, variants)) => variants.push(&variant.ident),
+            None => kinds.push((kind_variant, vec![&variant.ident])),
+        }
+    }
+    let doc = format!(
+        "The kind of a `{}`, telling its variants apart without their fields.",
+        input.ident.unraw(),
+    );
+    let variants = kinds.iter().map(|(kind_variant, variants)| {
+        let names: Vec<String> = variants
+            .iter()
+            .map(|variant| format!("`{}`", variant.unraw()))
+            .collect();
+        let doc = match names.split_last() {
+            Some((last, [])) => format!("The {} variant.", last),
+            Some((last, rest)) => format!("The {} and {} variants.", rest.join(", "), last),
+            None => unreachable!(),
+        };
+        quote! {
+            #[doc = #doc]
+            #kind_variant,
+        }
+    });
+    quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        #vis enum #kind {
+            #(#variants)*
+        }
+    }
+}
+
+// #[thiserror(result_alias)]: `type Result<T> = std::result::Result<T, Error>`,
+// with the parameters of the error type after `T`. A type alias ignores bounds
+// other than defaults, so only those are carried over.
+fn result_alias(node: &DeriveInput, config: &Config, std: &TokenStream) -> TokenStream {
+    let vis = &node.vis;
+    let ty = &node.ident;
+    let alias = match &config.result_alias_ident {
+        Some(ident) => ident.clone(),
+        None => Ident::new("Result", ty.span()),
+    };
+    let taken = node.generics.type_params().any(|param| param.ident == "T");
+    let ok = if taken {
+        format_ident!("__T")
+    } else {
+        format_ident!("T")
+    };
+    let lifetimes = node
+        .generics
+        .lifetimes()
+        .map(|param| param.lifetime.clone());
+    let params = node.generics.params.iter().filter_map(|param| match param {
+        GenericParam::Lifetime(_) => None,
+        GenericParam::Type(param) => {
+            let ident = &param.ident;
+            let default = param.default.as_ref().map(|default| quote!(= #default));
+            Some(quote!(#ident #default))
+        }
+        GenericParam::Const(param) => {
+            let ident = &param.ident;
+            let ty = &param.ty;
+            let default = param.default.as_ref().map(|default| quote!(= #default));
+            Some(quote!(const #ident: #ty #default))
+        }
+    });
+    let (_, ty_generics, _) = node.generics.split_for_impl();
+    let doc = format!("A `Result` whose error is a `{}`.", ty.unraw());
+    quote! {
+        #[doc = #doc]
+        #vis type #alias<#(#lifetimes,)* #ok #(, #params)*> =
+            #std::result::Result<#ok, #ty #ty_generics>;
+    }
+}
+
+// The context selectors and extension trait generated by #[thiserror(context)].
+fn context_items(input: &Enum, std: &TokenStream, thiserror: &TokenStream) -> TokenStream {
+    let vis = &input.original.vis;
+    let ty = &input.ident;
+    let context_trait = input.context_trait_ident();
+    let sealed = format_ident!("__{}Sealed", context_trait);
+    let selectors = input.variants.iter().filter_map(|variant| {
+        let selector = variant.context_selector_ident()?;
+        let fields = context_selector_fields(variant);
+        let params = (0..fields.len()).map(|i| format_ident!("__T{}", i));
+        let def = match fields.first().map(|field| &field.member) {
+            Some(Member::Named(_)) => {
+                let fields = fields.iter().zip(params.clone()).map(|(field, param)| {
+                    let ident = match &field.member {
+                        Member::Named(ident) => ident,
+                        Member::Unnamed(_) => unreachable!(),
+                    };
+                    quote!(#vis #ident: #param)
+                });
+                quote!(<#(#params),*> { #(#fields,)* })
+            }
+            Some(Member::Unnamed(_)) => {
+                let fields = params.clone().map(|param| quote!(#vis #param));
+                quote!(<#(#params),*>(#(#fields),*);)
+            }
+            None => quote!(;),
+        };
+        let doc = format!(
+            "Context for the `{}` variant of `{}`, attached to its source error with `{}::context`.",
+            variant.ident.unraw(),
+            ty.unraw(),
+            context_trait,
+        );
+        Some(quote! {
+            #[doc = #doc]
+            #vis struct #selector #def
+        })
+    });
+    let doc = format!(
+        "Attaches the fields of a `{}` variant to the error of a `Result`, through the context selector generated for the variant.",
+        ty.unraw(),
+    );
+    quote! {
+        #(#selectors)*
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        mod #sealed {
+            pub trait Sealed {}
+        }
+
+        #[doc = #doc]
+        #vis trait #context_trait<__T, __S>: #sealed::Sealed {
+            /// Wraps the error of this result in the variant that `context`
+            /// selects, along with the fields it carries.
+            fn context<__C>(self, context: __C) -> #std::result::Result<__T, #ty>
+            where
+                __C: #thiserror::ContextSelector<#ty, Source = __S>;
+        }
+    }
+}
+
+// The impls behind the items of context_items, which go with the other impls.
+fn context_impls(input: &Enum, std: &TokenStream, thiserror: &TokenStream) -> TokenStream {
+    let ty = &input.ident;
+    let context_trait = input.context_trait_ident();
+    let sealed = format_ident!("__{}Sealed", context_trait);
+    let selector_impls = input.variants.iter().filter_map(|variant| {
+        let selector = variant.context_selector_ident()?;
+        let ident = &variant.ident;
+        let source_field = variant.source_field().unwrap();
+        let fields = context_selector_fields(variant);
+        let params: Vec<Ident> = (0..fields.len())
+            .map(|i| format_ident!("__T{}", i))
+            .collect();
+        let bounds = fields.iter().zip(&params).map(|(field, param)| {
+            let field_ty = field.ty;
+            quote!(#param: #std::convert::Into<#field_ty>)
+        });
+        let inits = fields.iter().enumerate().map(|(i, field)| {
+            let member = &field.member;
+            let selector_member = match member {
+                Member::Named(ident) => Member::Named(ident.clone()),
+                Member::Unnamed(_) => Member::Unnamed(Index::from(i)),
+            };
+            quote!(#member: #std::convert::Into::into(self.#selector_member),)
+        });
+        let source_member = &source_field.member;
+        let source_ty = unoptional_type(source_field.ty);
+        let some_source = if type_is_option(source_field.ty) {
+            quote!(#std::option::Option::Some(source))
+        } else {
+            quote!(source)
+        };
+        let filled = filled_initializers(
+            variant.distinct_backtrace_field(),
+            variant.span_trace_field(),
+            variant.phantom_fields(),
+            std,
+        );
+        Some(quote! {
+            #[allow(unused_qualifications)]
+            impl<#(#bounds),*> #thiserror::ContextSelector<#ty> for #selector<#(#params),*> {
+                type Source = #source_ty;
+
+                #[allow(deprecated)]
+                fn into_error(self, source: Self::Source) -> #ty {
+                    #ty::#ident {
+                        #source_member: #some_source,
+                        #(#inits)*
+                        #filled
+                    }
+                }
+            }
+        })
+    });
+    quote! {
+        #(#selector_impls)*
+
+        impl<__T, __S> #sealed::Sealed for #std::result::Result<__T, __S> {}
+
+        #[allow(unused_qualifications)]
+        impl<__T, __S> #context_trait<__T, __S> for #std::result::Result<__T, __S> {
+            fn context<__C>(self, context: __C) -> #std::result::Result<__T, #ty>
+            where
+                __C: #thiserror::ContextSelector<#ty, Source = __S>,
+            {
+                self.map_err(|source| #thiserror::ContextSelector::into_error(context, source))
+            }
+        }
+    }
+}
+
+// The fields of a variant that its context selector carries: all but the
+// source, and the backtrace, span trace and PhantomData markers filled in when
+// the variant is created.
+fn context_selector_fields<'a, 'b>(variant: &'a Variant<'b>) -> Vec<&'a Field<'b>> {
+    let source_field = variant.source_field();
+    let backtrace_field = variant.distinct_backtrace_field();
+    let span_trace_field = variant.span_trace_field();
+    let is_same = |field: &Field, other: Option<&Field>| {
+        other.map_or(false, |other| other.member == field.member)
+    };
+    variant
+        .fields
+        .iter()
+        .filter(|field| {
+            !is_same(field, source_field)
+                && !is_same(field, backtrace_field)
+                && !is_same(field, span_trace_field)
+                && !type_is_phantom_data(field.ty)
+        })
+        .collect()
+}
+
+// #[thiserror(codes)]
+fn codes_items(input: &DeriveInput, body: TokenStream, codes: &[(&Ident, &Expr)]) -> TokenStream {
+    let vis = &input.vis;
+    let entries = codes.iter().map(|(ident, code)| {
+        let name = ident.unraw().to_string();
+        quote!((#code, #name))
+    });
+    quote! {
+        /// Returns the code of this error, as given by #[error(code = ...)]
+        /// on its variant.
+        #vis const fn code(&self) -> u32 {
+            #body
+        }
+
+        /// The code of every variant of this error paired with the name of
+        /// the variant, in declaration order.
+        #vis const ALL_CODES: &'static [(u32, &'static str)] = &[#(#entries),*];
+    }
+}
+
+// #[thiserror(repr_c_codes)]
+fn repr_c_codes_methods(
+    input: &DeriveInput,
+    to_body: TokenStream,
+    from_body: Option<TokenStream>,
+    std: &TokenStream,
+) -> TokenStream {
+    let vis = &input.vis;
+    let from_method = from_body.map(|from_body| {
+        quote! {
+            /// Returns the error whose code is `code`, as given by
+            /// #[error(code = ...)] on its variant, or None for an unknown
+            /// code.
+            #vis const fn from_ffi_code(code: i32) -> #std::option::Option<Self> {
+                #from_body
+            }
+        }
+    });
+    quote! {
+        /// Returns the code of this error to hand across an FFI boundary, as
+        /// given by #[error(code = ...)] on its variant.
+        #vis const fn to_ffi_code(&self) -> i32 {
+            #to_body
+        }
+
+        #from_method
+    }
+}
+
+// #[thiserror(variant_names)]
+fn variant_name_items(input: &DeriveInput, body: TokenStream, names: &[String]) -> TokenStream {
+    let vis = &input.vis;
+    quote! {
+        /// Returns the name of the variant of this error, as given by
+        /// #[error(name = "...")] or else the variant's identifier.
+        #vis fn variant_name(&self) -> &'static str {
+            #body
+        }
+
+        /// The names returned by `variant_name()`, in declaration order.
+        #vis const VARIANT_NAMES: &'static [&'static str] = &[#(#names),*];
+    }
+}
+
+// #[thiserror(variants)]
+fn variants_const(input: &DeriveInput, idents: &[&Ident]) -> TokenStream {
+    let vis = &input.vis;
+    let names = idents.iter().map(|ident| ident.unraw().to_string());
+    quote! {
+        /// The names of the variants of this error, in declaration order.
+        #vis const VARIANTS: &'static [&'static str] = &[#(#names),*];
+    }
+}
+
+// The named fields reported by fields(), leaving out the source, backtrace and
+// other errors attached to the error.
+fn context_fields<'a>(
+    fields: &'a [Field],
+    source_field: Option<&Field>,
+    backtrace_field: Option<&Field>,
+    inferred_bounds: &mut InferredBounds,
+    std: &TokenStream,
+) -> Vec<(String, &'a Ident)> {
+    let mut context = Vec::new();
+    for field in fields {
+        let ident = match &field.member {
+            Member::Named(ident) => ident,
+            Member::Unnamed(_) => continue,
+        };
+        let is_same =
+            |other: Option<&Field>| other.map_or(false, |other| other.member == field.member);
+        if is_same(source_field)
+            || is_same(backtrace_field)
+            || field.attrs.span_trace.is_some()
+            || field.attrs.related.is_some()
+        {
+            continue;
+        }
+        if field.contains_generic {
+            inferred_bounds.insert(field.ty, quote!(#std::fmt::Debug));
+        }
+        context.push((ident.unraw().to_string(), ident));
+    }
+    context
+}
+
+fn fields_method(
+    input: &DeriveInput,
+    inferred_bounds: &InferredBounds,
+    body: TokenStream,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let vis = &input.vis;
+    let mut where_clause = Some(inferred_bounds.augment_where_clause(&Generics::default()));
+    if where_clause.as_ref().unwrap().predicates.is_empty() {
+        where_clause = None;
+    }
+    quote! {
+        /// Returns the name and formatted value of each named field of this
+        /// error other than its source and backtrace, using Display if the
+        /// field implements it and Debug otherwise.
+        #vis fn fields(&self) -> #std::vec::Vec<(&'static str, #std::string::String)>
+        #where_clause
+        {
+            #[allow(unused_imports)]
+            use #thiserror::__private::{FieldDebug, FieldDisplay, FieldValue};
+            #body
+        }
+    }
+}
+
+// The arguments passed to the localizer by localized(): the same fields as
+// fields() reports, plus the fields of tuple variants under their index so that
+// they match the `{0}` of a message.
+fn localize_args<'a>(
+    fields: &'a [Field],
+    source_field: Option<&Field>,
+    backtrace_field: Option<&Field>,
+    inferred_bounds: &mut InferredBounds,
+    std: &TokenStream,
+) -> Vec<(String, &'a Member, Ident)> {
+    let mut args = Vec::new();
+    for field in fields {
+        let is_same =
+            |other: Option<&Field>| other.map_or(false, |other| other.member == field.member);
+        if is_same(source_field)
+            || is_same(backtrace_field)
+            || field.attrs.span_trace.is_some()
+            || field.attrs.related.is_some()
+        {
+            continue;
+        }
+        if field.contains_generic {
+            inferred_bounds.insert(field.ty, quote!(#std::fmt::Debug));
+        }
+        let (name, binding) = match &field.member {
+            Member::Named(ident) => (ident.unraw().to_string(), ident.clone()),
+            Member::Unnamed(index) => (index.index.to_string(), format_ident!("_{}", index.index)),
+        };
+        args.push((name, &field.member, binding));
+    }
+    args
+}
+
+// #[thiserror(localize = path::to::Localizer)]
+fn localized_method(
+    input: &DeriveInput,
+    localizer: &TokenStream,
+    inferred_bounds: &InferredBounds,
+    body: TokenStream,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let vis = &input.vis;
+    let mut where_clause = Some(inferred_bounds.augment_where_clause(&Generics::default()));
+    if where_clause.as_ref().unwrap().predicates.is_empty() {
+        where_clause = None;
+    }
+    quote! {
+        /// Returns the message of this error as written by the given
+        /// localizer, which is passed the code of the error along with the
+        /// name and formatted value of each of its fields other than its
+        /// source and backtrace.
+        #[allow(unreachable_code)]
+        #vis fn localized(&self, localizer: &dyn #localizer) -> #std::string::String #where_clause {
+            #[allow(unused_imports)]
+            use #thiserror::__private::{FieldDebug, FieldDisplay, FieldValue};
+            #[allow(deprecated)]
+            let args: #std::vec::Vec<(&'static str, #std::string::String)> = #body;
+            localizer.message(self.code(), &args)
+        }
+    }
+}
+
+// #[thiserror(downcast)]
+fn downcast_method(input: &DeriveInput, std: &TokenStream) -> TokenStream {
+    let vis = &input.vis;
+    quote! {
+        /// Returns the first error of type `__Source` in the chain of sources
+        /// of this error, not including this error itself.
+        #vis fn downcast_source<__Source>(&self) -> #std::option::Option<&__Source>
+        where
+            Self: #std::error::Error,
+            __Source: #std::error::Error + 'static,
+        {
+            let mut next = #std::error::Error::source(self);
+            while let #std::option::Option::Some(source) = next {
+                if let #std::option::Option::Some(source) = source.downcast_ref::<__Source>() {
+                    return #std::option::Option::Some(source);
+                }
+                next = source.source();
+            }
+            #std::option::Option::None
+        }
+    }
+}
+
+// #[error(exit_code = ...)]
+fn exit_code_method(input: &DeriveInput, body: TokenStream) -> TokenStream {
+    let vis = &input.vis;
+    quote! {
+        /// Returns the status a process should exit with when it fails with
+        /// this error.
+        #vis fn exit_code(&self) -> u8 {
+            #body
+        }
+    }
+}
+
+// impl ErrorExitCode alongside the exit_code() method, plus the conversion to
+// std::process::ExitCode and report_and_exit(), which need the exit_code
+// feature of thiserror.
+fn exit_code_impl(
+    input: &DeriveInput,
+    attrs: &Attrs,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let ty = &input.ident;
+    let vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let doc_hidden = doc_hidden(attrs);
+    quote! {
+        #[allow(unused_qualifications)]
+        impl #impl_generics #thiserror::ErrorExitCode for #ty #ty_generics #where_clause {
+            fn exit_code(&self) -> u8 {
+                Self::exit_code(self)
+            }
+        }
+        #thiserror::__exit_code_impl! {
+            #doc_hidden
+            #[allow(dead_code, unused_qualifications)]
+            impl #impl_generics #ty #ty_generics #where_clause {
+                /// Prints this error and its chain of sources to stderr, and
+                /// returns the exit code to end the process with.
+                #vis fn report_and_exit(&self) -> #std::process::ExitCode
+                where
+                    Self: #std::error::Error,
+                {
+                    #std::eprintln!("Error: {}", self);
+                    let mut source = #std::error::Error::source(self);
+                    while let #std::option::Option::Some(cause) = source {
+                        #std::eprintln!("Caused by: {}", cause);
+                        source = cause.source();
+                    }
+                    <#std::process::ExitCode as #std::convert::From<u8>>::from(self.exit_code())
+                }
+            }
+            #[allow(unused_qualifications)]
+            impl #impl_generics #std::convert::From<#ty #ty_generics>
+                for #std::process::ExitCode #where_clause
+            {
+                fn from(error: #ty #ty_generics) -> Self {
+                    <#std::process::ExitCode as #std::convert::From<u8>>::from(error.exit_code())
+                }
+            }
+        }
+    }
+}
+
+// #[thiserror(root_cause)]
+fn root_cause_method(input: &DeriveInput, std: &TokenStream) -> TokenStream {
+    let vis = &input.vis;
+    quote! {
+        /// Returns the last error in the chain of sources of this error, or
+        /// this error itself if it has no source.
+        #vis fn root_cause(&self) -> &(dyn #std::error::Error + 'static)
+        where
+            Self: #std::error::Error + 'static,
+        {
+            let mut cause: &(dyn #std::error::Error + 'static) = self;
+            while let #std::option::Option::Some(source) = cause.source() {
+                cause = source;
+            }
+            cause
+        }
+    }
+}
+
+// #[thiserror(report)]
+fn report_method(
+    input: &DeriveInput,
+    variant: TokenStream,
+    code: TokenStream,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let vis = &input.vis;
+    let type_name = input.ident.unraw().to_string();
+    quote! {
+        /// Returns an owned, structured view of this error: the name of its
+        /// type and variant, its message and code, and the messages of its
+        /// chain of sources.
+        #[allow(unreachable_code)] // for an enum without variants
+        #vis fn to_report(&self) -> #thiserror::Report
+        where
+            Self: #std::error::Error,
+        {
+            #thiserror::Report::__new(#type_name, #variant, #code, self)
+        }
+    }
+}
+
+// Fields marked #[related]. Each list expression evaluates to an Option of a
+// reference to one of the fields.
+fn related_method(
+    input: &DeriveInput,
+    fields: &[&Field],
+    lists: impl Iterator<Item = TokenStream>,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let vis = &input.vis;
+    let mut inferred_bounds = InferredBounds::new();
+    for field in fields {
+        if field.contains_generic {
+            let elem = field.collection_element().unwrap();
+            inferred_bounds.insert(elem, quote!(#std::error::Error + 'static));
+        }
+    }
+    let mut where_clause = Some(inferred_bounds.augment_where_clause(&Generics::default()));
+    if where_clause.as_ref().unwrap().predicates.is_empty() {
+        where_clause = None;
+    }
+    let chain = lists.map(|list| {
+        quote! {
+            .chain(
+                (#list)
+                    .into_iter()
+                    .flat_map(|related| related.iter())
+                    .map(|related| related.as_dyn_error()),
+            )
+        }
+    });
+    quote! {
+        /// Returns the errors related to this one, other than its source.
+        #[allow(unreachable_patterns)]
+        #vis fn related(
+            &self,
+        ) -> impl #std::iter::Iterator<Item = &(dyn #std::error::Error + 'static)>
+        #where_clause
+        {
+            use #std::iter::{IntoIterator, Iterator};
+            use #thiserror::__private::AsDynError;
+            #std::iter::empty() #(#chain)*
+        }
+    }
+}
+
+// Error::description() for #[thiserror(legacy_description)], returning the
+// name of the struct or of the variant.
+fn description_method(body: TokenStream) -> TokenStream {
+    quote! {
+        #[allow(deprecated)]
+        fn description(&self) -> &str {
+            #body
+        }
+    }
+}
+
+// #[error(level = "...")], expanding to nothing unless the tracing feature of
+// thiserror is enabled.
+fn span_trace_method(
+    input: &DeriveInput,
+    ret: TokenStream,
+    body: TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let vis = &input.vis;
+    quote! {
+        #thiserror::__span_trace_impl! {
+            /// Returns the span trace captured when this error was created.
+            #vis fn span_trace(&self) -> #ret {
+                #body
+            }
+        }
+    }
+}
+
+// impl defmt::Format for #[thiserror(defmt)], expanding to a compile error
+// unless the defmt feature of thiserror is enabled.
+fn defmt_impl(
+    input: &DeriveInput,
+    inferred_bounds: &InferredBounds,
+    body: TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let where_clause = inferred_bounds.augment_where_clause(&input.generics);
+    quote! {
+        #thiserror::__defmt_impl! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #thiserror::__private::defmt::Format
+                for #ty #ty_generics #where_clause
+            {
+                fn format(&self, __formatter: #thiserror::__private::defmt::Formatter) {
+                    use #thiserror::__private::defmt;
+                    #body
+                }
+            }
+        }
+    }
+}
+
+// impl serde::Serialize for #[thiserror(serialize)], expanding to a compile
+// error unless the serde feature of thiserror is enabled.
+fn serialize_impl(
+    input: &DeriveInput,
+    inferred_bounds: &InferredBounds,
+    body: TokenStream,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let where_clause = inferred_bounds.augment_where_clause(&input.generics);
+    quote! {
+        #thiserror::__serde_impl! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #thiserror::__private::serde::Serialize
+                for #ty #ty_generics #where_clause
+            {
+                fn serialize<__S>(
+                    &self,
+                    __serializer: __S,
+                ) -> #std::result::Result<__S::Ok, __S::Error>
+                where
+                    __S: #thiserror::__private::serde::Serializer,
+                {
+                    #body
+                }
+            }
+        }
+    }
+}
+
+// impl serde::Deserialize for #[thiserror(deserialize)], reading the name of a
+// variant. The body builds the variant from its index in `names`.
+fn deserialize_impl(
+    input: &DeriveInput,
+    names: &[String],
+    body: TokenStream,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let ty = &input.ident;
+    quote! {
+        #thiserror::__serde_impl! {
+            #[allow(unused_qualifications)]
+            impl<'de> #thiserror::__private::serde::Deserialize<'de> for #ty {
+                #[allow(unreachable_code)] // for an enum without variants
+                fn deserialize<__D>(__deserializer: __D) -> #std::result::Result<Self, __D::Error>
+                where
+                    __D: #thiserror::__private::serde::Deserializer<'de>,
+                {
+                    const NAMES: &[&str] = &[#(#names),*];
+                    let __index =
+                        #thiserror::__private::deserialize_variant(__deserializer, NAMES)?;
+                    #std::result::Result::Ok(#body)
+                }
+            }
+        }
+    }
+}
+
+// The `message`, `code` and `chain` entries of a struct or variant serialized
+// by #[thiserror(serialize)], followed by its #[error(serialize)] fields. The
+// fields are expected to be bound by fields_pat.
+fn serialize_entries(
+    container: &Attrs,
+    attrs: &Attrs,
+    fields: &[Field],
+    state: TokenStream,
+    inferred_bounds: &mut InferredBounds,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> Vec<TokenStream> {
+    let state = quote!(#thiserror::__private::serde::ser::#state);
+    let var = |field: &Field| match &field.member {
+        Member::Named(ident) => ident.clone(),
+        Member::Unnamed(index) => format_ident!("_{}", index),
+    };
+    let mut entries = Vec::new();
+
+    inferred_bounds.insert(quote!(Self), quote!(#std::error::Error));
+    let redact = container.config.static_messages.is_none()
+        && fields.iter().any(|field| field.attrs.sensitive.is_some());
+    let message = match &attrs.display {
+        Some(display) if redact => {
+            for &(field, bound) in &display.implied_bounds {
+                let field = &fields[field];
+                if field.attrs.sensitive.is_none() && field.infers_bounds() && !field.recursive {
+                    inferred_bounds.insert(field.ty, bound.path(std));
+                }
+            }
+            for &(field, leaf, bound) in &display.leaf_bounds {
+                let field = &fields[field];
+                if field.attrs.sensitive.is_none() && field.infers_bounds() {
+                    inferred_bounds.insert(leaf, bound.path(std));
+                }
+            }
+            let use_as_display = if display.has_bonus_display {
+                Some(use_as_display(container, thiserror))
+            } else {
+                None
+            };
+            let redacted = fields
+                .iter()
+                .filter(|field| field.attrs.sensitive.is_some())
+                .map(var);
+            let display = display.fmt_tokens(std);
+            quote! {
+                #use_as_display
+                #(let #redacted = &#thiserror::__private::Redacted;)*
+                #display
+            }
+        }
+        _ => quote!(#std::fmt::Display::fmt(self, __formatter)),
+    };
+    entries.push(quote! {
+        #state::serialize_field(
+            &mut __state,
+            "message",
+            &#thiserror::__private::message(|__formatter: &mut #std::fmt::Formatter| {
+                #message
+            }),
+        )?;
+    });
+
+    if container.config.codes.is_some() || container.config.discriminant_code.is_some() {
+        entries.push(quote! {
+            #state::serialize_field(&mut __state, "code", &self.code())?;
+        });
+    }
+
+    entries.push(quote! {
+        #state::serialize_field(
+            &mut __state,
+            "chain",
+            &#thiserror::__private::Chain(#std::error::Error::source(self)),
+        )?;
+    });
+
+    for field in fields {
+        if field.attrs.serialize.is_none() {
+            continue;
+        }
+        if field.contains_generic && !field.recursive {
+            inferred_bounds.insert(field.ty, quote!(#thiserror::__private::serde::Serialize));
+        }
+        let key = match &field.member {
+            Member::Named(ident) => ident.unraw().to_string(),
+            Member::Unnamed(index) => index.index.to_string(),
+        };
+        let var = var(field);
+        entries.push(quote! {
+            #state::serialize_field(&mut __state, #key, #var)?;
+        });
+    }
+
+    entries
+}
+
+// impl ufmt::uDisplay for #[thiserror(ufmt)], expanding to a compile error
+// unless the ufmt feature of thiserror is enabled.
+fn ufmt_impl(
+    input: &DeriveInput,
+    inferred_bounds: &InferredBounds,
+    body: TokenStream,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, _) = input.generics.split_for_impl();
+    let where_clause = inferred_bounds.augment_where_clause(&input.generics);
+    quote! {
+        #thiserror::__ufmt_impl! {
+            #[allow(unused_qualifications)]
+            impl #impl_generics #thiserror::__private::ufmt::uDisplay
+                for #ty #ty_generics #where_clause
+            {
+                fn fmt<__W>(
+                    &self,
+                    __formatter: &mut #thiserror::__private::ufmt::Formatter<__W>,
+                ) -> #std::result::Result<(), __W::Error>
+                where
+                    __W: #thiserror::__private::ufmt::uWrite + ?#std::marker::Sized,
+                {
+                    // ufmt::uwrite! names core, which a #![no_implicit_prelude]
+                    // module has no other way to find.
+                    extern crate core;
+                    use #thiserror::__private::ufmt;
+                    #body
+                }
+            }
+        }
+    }
+}
+
+fn tracing_impl(
+    input: &DeriveInput,
+    attrs: &Attrs,
+    level: TokenStream,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let ty = &input.ident;
+    let vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let doc_hidden = doc_hidden(attrs);
+    quote! {
+        #thiserror::__tracing_impl! {
+            #doc_hidden
+            #[allow(dead_code, unused_qualifications)]
+            impl #impl_generics #ty #ty_generics #where_clause {
+                /// Returns the severity at which this error is logged.
+                #vis fn tracing_level(&self) -> #thiserror::__private::tracing::Level {
+                    #level
+                }
+
+                /// Logs the message of this error as a tracing event at its level.
+                #vis fn emit(&self)
+                where
+                    Self: #std::fmt::Display,
+                {
+                    use #thiserror::__private::tracing::{event, Level};
+                    match self.tracing_level() {
+                        Level::TRACE => event!(Level::TRACE, "{}", self),
+                        Level::DEBUG => event!(Level::DEBUG, "{}", self),
+                        Level::INFO => event!(Level::INFO, "{}", self),
+                        Level::WARN => event!(Level::WARN, "{}", self),
+                        _ => event!(Level::ERROR, "{}", self),
+                    }
+                }
+            }
+        }
+    }
+}
+
+// #[thiserror(require_debug)]: names the missing #[derive(Debug)] in a note on
+// the error, next to the unsatisfied supertrait of Error.
+fn require_debug_method(span: Span, thiserror: &TokenStream) -> TokenStream {
+    let require_debug = quote_spanned!(span=> #thiserror::__private::require_debug::<Self>);
+    quote! {
+        #[doc(hidden)]
+        fn __thiserror_require_debug() {
+            #require_debug();
+        }
+    }
+}
+
+fn inherent_impl(
+    input: &DeriveInput,
+    attrs: &Attrs,
+    methods: Vec<TokenStream>,
+) -> Option<TokenStream> {
+    if methods.is_empty() {
+        return None;
+    }
+    let ty = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let doc_hidden = doc_hidden(attrs);
+    Some(quote! {
+        #doc_hidden
+        #[allow(dead_code, unused_qualifications)]
+        impl #impl_generics #ty #ty_generics #where_clause {
+            #(#methods)*
+        }
+    })
+}
+
+// #[thiserror(hide_generated)] keeps the generated inherent methods callable
+// but out of the documentation of the error type.
+fn doc_hidden(attrs: &Attrs) -> Option<TokenStream> {
+    attrs
+        .config
+        .hide_generated
+        .map(|span| quote_spanned!(span=> #[doc(hidden)]))
+}
+
+// Predicates of the #[error(bound(...))] attributes that apply to one impl,
+// selected by whether each attribute targets Display or Error.
+fn explicit_bounds<'a>(attrs: &[&'a Attrs], applies: fn(&Bound) -> bool) -> Vec<&'a PredicateType> {
+    attrs
+        .iter()
+        .flat_map(|attrs| &attrs.bounds)
+        .filter(|bound| applies(bound))
+        .flat_map(|bound| &bound.predicates)
+        .collect()
+}
+
+// Display of an #[aggregate] field, given a place expression for it.
+fn aggregate_display(
+    aggregate_field: &Field,
+    field: TokenStream,
+    std: &TokenStream,
+    thiserror: &TokenStream,
+) -> TokenStream {
+    let aggregate = aggregate_field.attrs.aggregate.as_ref().unwrap();
+    let separator = match &aggregate.separator {
+        Some(separator) => quote!(#std::option::Option::Some(#separator)),
+        None => quote!(#std::option::Option::None),
+    };
+    let max = match &aggregate.max {
+        Some(max) => quote!(#std::option::Option::Some(#max)),
+        None => quote!(#std::option::Option::None),
+    };
+    quote! {
+        #std::fmt::Display::fmt(
+            &#thiserror::__private::Aggregate::new(&#field[..], #separator, #max),
+            __formatter,
+        )
+    }
+}
+
+// The only field of an #[error(transparent_str)] error is written out as is.
+fn insert_str_bound(inferred_bounds: &mut InferredBounds, only_field: &Field, std: &TokenStream) {
+    if only_field.contains_generic && !only_field.recursive {
+        inferred_bounds.insert(only_field.ty, quote!(#std::convert::AsRef<str>));
+    }
+}
+
+fn insert_aggregate_bound(
+    inferred_bounds: &mut InferredBounds,
+    aggregate_field: Option<&Field>,
+    bound: impl ToTokens,
+) {
+    if let Some(aggregate_field) = aggregate_field {
+        if aggregate_field.infers_bounds() {
+            inferred_bounds.insert(aggregate_field.collection_element().unwrap(), bound);
+        }
+    }
+}
+
+fn inline_attr() -> Option<TokenStream> {
+    if cfg!(feature = "inline") {
+        Some(quote!(#[inline]))
+    } else {
+        None
+    }
+}
+
+// The error that provide() is forwarded to for a source, given a reference to
+// it. An err_as_ref source is reached through its AsRef<dyn Error> impl.
+fn source_provider(err_as_ref: bool, source: TokenStream, std: &TokenStream) -> TokenStream {
+    if err_as_ref {
+        dyn_error_as_ref(source, std)
+    } else {
+        source
+    }
+}
+
+fn dyn_error_as_ref(source: TokenStream, std: &TokenStream) -> TokenStream {
+    quote!(#std::convert::AsRef::<dyn #std::error::Error + 'static>::as_ref(#source))
+}
+
+// Supplies a #[provide] field to the request under its own type, or under the
+// inner type if the field is an Option. By reference, or by copy with
+// #[provide(value)].
+fn provide_field(
+    field: &Field,
+    value: TokenStream,
+    request: &TokenStream,
+    bounds: &mut InferredBounds,
+    std: &TokenStream,
+) -> TokenStream {
+    let ty = unoptional_type(field.ty);
+    if field.infers_bounds() {
+        bounds.insert(&ty, quote!('static));
+    }
+    let by_value = field.attrs.provide.map_or(false, |provide| provide.value);
+    let provide = |provided: TokenStream| {
+        if by_value {
+            quote!(#request.provide_value::<#ty>(*#provided))
+        } else {
+            quote!(#request.provide_ref::<#ty>(#provided))
+        }
+    };
+    if type_is_option(field.ty) {
+        let provide = provide(quote!(provided));
+        quote_spanned! {field.member.span()=>
+            if let #std::option::Option::Some(provided) = #value {
+                #provide;
+            }
+        }
+    } else {
+        let provide = provide(value);
+        quote_spanned! {field.member.span()=>
+            #provide;
+        }
+    }
+}
+
+pub(crate) fn type_is_option(ty: &Type) -> bool {
+    type_parameter_of_option(ty).is_some()
+}
+
+fn unoptional_type(ty: &Type) -> TokenStream {
+    let unoptional = type_parameter_of_option(ty).unwrap_or(ty);
+    quote!(#unoptional)
+}
+
+pub(crate) fn type_parameter_of_option(ty: &Type) -> Option<&Type> {
+    type_parameter_of(ty, "Option")
+}
+
+pub(crate) fn type_parameter_of_arc(ty: &Type) -> Option<&Type> {
+    type_parameter_of(ty, "Arc")
 }
 
-fn type_parameter_of_option(ty: &Type) -> Option<&Type> {
+fn type_parameter_of<'a>(ty: &'a Type, name: &str) -> Option<&'a Type> {
     let path = match ty {
         Type::Path(ty) => &ty.path,
         _ => return None,
     };
 
     let last = path.segments.last().unwrap();
-    if last.ident != "Option" {
+    if last.ident != name {
         return None;
     }
 
@@ -547,7 +4283,7 @@ fn type_parameter_of_option(ty: &Type) -> Option<&Type> {
     }
 }
 
-fn spanned_error_trait(input: &DeriveInput) -> TokenStream {
+fn spanned_error_trait(input: &DeriveInput, std: &TokenStream) -> TokenStream {
     let vis_span = match &input.vis {
         Visibility::Public(vis) => Some(vis.pub_token.span()),
         Visibility::Crate(vis) => Some(vis.crate_token.span()),
@@ -561,7 +4297,7 @@ fn spanned_error_trait(input: &DeriveInput) -> TokenStream {
     };
     let first_span = vis_span.unwrap_or(data_span);
     let last_span = input.ident.span();
-    let path = quote_spanned!(first_span=> std::error::);
+    let path = quote_spanned!(first_span=> #std::error::);
     let error = quote_spanned!(last_span=> Error);
     quote!(#path #error)
 }
@@ -1,15 +1,27 @@
 use crate::ast::Field;
-use crate::attr::{Display, Trait};
-use proc_macro2::TokenTree;
+use crate::attr::{DefmtMessage, Display, Trait, UfmtMessage, UfmtTrait};
+use crate::generics::{leaf_type, ParamsInScope};
+use proc_macro2::{Span, TokenStream, TokenTree};
 use quote::{format_ident, quote_spanned};
 use std::collections::{BTreeSet as Set, HashMap as Map};
 use syn::ext::IdentExt;
 use syn::parse::{ParseStream, Parser};
-use syn::{Ident, Index, LitStr, Member, Result, Token};
+use syn::spanned::Spanned;
+use syn::{Error, Ident, Index, LitStr, Member, Result, Token};
 
-impl Display<'_> {
+impl<'a> Display<'a> {
     // Transform `"error {var}"` to `"error {}", var`.
-    pub fn expand_shorthand(&mut self, fields: &[Field]) {
+    pub fn expand_shorthand(&mut self, fields: &[Field<'a>], scope: &ParamsInScope) {
+        if let Some(matching) = &mut self.matching {
+            for (_pat, display) in &mut matching.arms {
+                display.expand_shorthand(fields, scope);
+                self.has_bonus_display |= display.has_bonus_display;
+                self.implied_bounds.extend(display.implied_bounds.clone());
+                self.leaf_bounds.extend(display.leaf_bounds.clone());
+                self.opaque_paths.extend(display.opaque_paths.clone());
+            }
+            return;
+        }
         let raw_args = self.args.clone();
         let mut named_args = explicit_named_args.parse2(raw_args).unwrap();
         let mut member_index = Map::new();
@@ -24,6 +36,8 @@ impl Display<'_> {
         let mut args = self.args.clone();
         let mut has_bonus_display = false;
         let mut implied_bounds = Set::new();
+        let mut leaf_bounds = Vec::new();
+        let mut opaque_paths = Vec::new();
 
         let mut has_trailing_comma = false;
         if let Some(TokenTree::Punct(punct)) = args.clone().into_iter().last() {
@@ -40,6 +54,20 @@ impl Display<'_> {
                 read = &read[1..];
                 continue;
             }
+            if let Some(method) = take_self_method(&mut read, span) {
+                // Transform `"{self.describe()}"` to `"{self__describe}", self__describe = Self::describe(self)`.
+                let formatvar = format_ident!("self__{}", method.unraw());
+                out += &formatvar.to_string();
+                if !named_args.insert(formatvar.clone()) {
+                    continue;
+                }
+                if !has_trailing_comma {
+                    args.extend(quote_spanned!(span=> ,));
+                }
+                args.extend(quote_spanned!(span=> #formatvar = Self::#method(self)));
+                has_trailing_comma = false;
+                continue;
+            }
             let next = match read.chars().next() {
                 Some(next) => next,
                 None => return,
@@ -64,27 +92,53 @@ impl Display<'_> {
                 }
                 _ => continue,
             };
-            if let Some(&field) = member_index.get(&member) {
+            let path = take_path(&mut read, span);
+            let call = match path.last() {
+                Some(Member::Named(_)) => read.starts_with("()"),
+                _ => false,
+            };
+            if call {
+                read = &read["()".len()..];
+            }
+            let join = read.starts_with(":join}");
+            if join {
+                read = &read[":join".len()..];
+            }
+            if let (Some(&field), false, false) = (member_index.get(&member), call, join) {
                 let end_spec = match read.find('}') {
                     Some(end_spec) => end_spec,
                     None => return,
                 };
-                let bound = match read[..end_spec].chars().next_back() {
-                    Some('?') => Trait::Debug,
-                    Some('o') => Trait::Octal,
-                    Some('x') => Trait::LowerHex,
-                    Some('X') => Trait::UpperHex,
-                    Some('p') => Trait::Pointer,
-                    Some('b') => Trait::Binary,
-                    Some('e') => Trait::LowerExp,
-                    Some('E') => Trait::UpperExp,
-                    Some(_) | None => Trait::Display,
-                };
-                implied_bounds.insert((field, bound));
+                let bound = spec_bound(&read[..end_spec]);
+                if path.is_empty() {
+                    implied_bounds.insert((field, bound));
+                } else {
+                    // Bound the type reached by `{inner.0.code}` where it can
+                    // be seen, which is through tuples.
+                    match leaf_type(fields[field].ty, &path) {
+                        Some(leaf) => {
+                            if scope.intersects(leaf) {
+                                leaf_bounds.push((field, leaf, bound));
+                            }
+                        }
+                        None => {
+                            if fields[field].contains_generic {
+                                opaque_paths.push((field, path_string(&member, &path)));
+                            }
+                        }
+                    }
+                }
             }
-            let local = match &member {
-                Member::Unnamed(index) => format_ident!("_{}", index),
-                Member::Named(ident) => ident.clone(),
+            let local = match (&member, member_index.get(&member)) {
+                (Member::Unnamed(index), _) => format_ident!("_{}", index),
+                // The ident of the field itself, which is what the field is
+                // bound by. When a macro pastes in the field's name, it can be
+                // from another hygiene context than the message.
+                (Member::Named(_), Some(&field)) => match &fields[field].member {
+                    Member::Named(ident) => ident.clone(),
+                    Member::Unnamed(_) => unreachable!(),
+                },
+                (Member::Named(ident), None) => ident.clone(),
             };
             let mut formatvar = local.clone();
             if formatvar.to_string().starts_with("r#") {
@@ -95,6 +149,29 @@ impl Display<'_> {
                 // older compilers. https://github.com/rust-lang/rust/pull/66847
                 formatvar = format_ident!("field_{}", formatvar);
             }
+            let mut local = if path.is_empty() {
+                quote_spanned!(span=> #local)
+            } else {
+                // Transform `"{inner.code}"` to `"{inner__code}", inner__code = &inner.code`.
+                for segment in &path {
+                    formatvar = match segment {
+                        Member::Named(ident) => format_ident!("{}__{}", formatvar, ident.unraw()),
+                        Member::Unnamed(index) => format_ident!("{}__{}", formatvar, index),
+                    };
+                }
+                if call {
+                    // Transform `"{errors.len()}"` to `"{errors__len}", errors__len = &errors.len()`.
+                    quote_spanned!(span=> (&#local #(.#path)*()))
+                } else {
+                    quote_spanned!(span=> (&#local #(.#path)*))
+                }
+            };
+            if join {
+                // Transform `"{names:join}"` to `"{names__join}", names__join = names.iter().join_display()`.
+                has_bonus_display = true;
+                formatvar = format_ident!("{}__join", formatvar);
+                local = quote_spanned!(span=> #local.iter().join_display());
+            }
             out += &formatvar.to_string();
             if !named_args.insert(formatvar.clone()) {
                 // Already specified in the format argument list.
@@ -104,7 +181,7 @@ impl Display<'_> {
                 args.extend(quote_spanned!(span=> ,));
             }
             args.extend(quote_spanned!(span=> #formatvar = #local));
-            if read.starts_with('}') && member_index.contains_key(&member) {
+            if read.starts_with('}') && member_index.contains_key(&member) && !join {
                 has_bonus_display = true;
                 args.extend(quote_spanned!(span=> .as_display()));
             }
@@ -116,6 +193,264 @@ impl Display<'_> {
         self.args = args;
         self.has_bonus_display = has_bonus_display;
         self.implied_bounds = implied_bounds;
+        self.leaf_bounds = leaf_bounds;
+        self.opaque_paths = opaque_paths;
+
+        if let Some(alternate) = &mut self.alternate {
+            alternate.expand_shorthand(fields, scope);
+            self.has_bonus_display |= alternate.has_bonus_display;
+            self.implied_bounds.extend(alternate.implied_bounds.clone());
+            self.leaf_bounds.extend(alternate.leaf_bounds.clone());
+            self.opaque_paths.extend(alternate.opaque_paths.clone());
+        }
+    }
+}
+
+// The formatting trait selected by the format spec, as in `08x` -> LowerHex.
+fn spec_bound(spec: &str) -> Trait {
+    match spec.chars().next_back() {
+        Some('?') => Trait::Debug,
+        Some('o') => Trait::Octal,
+        Some('x') => Trait::LowerHex,
+        Some('X') => Trait::UpperHex,
+        Some('p') => Trait::Pointer,
+        Some('b') => Trait::Binary,
+        Some('e') => Trait::LowerExp,
+        Some('E') => Trait::UpperExp,
+        Some(_) | None => Trait::Display,
+    }
+}
+
+// `inner.code`, as written in the message.
+fn path_string(member: &Member, path: &[Member]) -> String {
+    let mut string = String::new();
+    for member in Some(member).into_iter().chain(path) {
+        if !string.is_empty() {
+            string.push('.');
+        }
+        match member {
+            Member::Named(ident) => string += &ident.unraw().to_string(),
+            Member::Unnamed(index) => string += &index.index.to_string(),
+        }
+    }
+    string
+}
+
+// A field interpolated into a message for defmt or ufmt, which take only
+// positional arguments.
+struct FieldArg {
+    field: usize,
+    // The place of the value, with the span of its field.
+    expr: TokenStream,
+    // Whether the whole field is interpolated, rather than some part of it.
+    whole: bool,
+    // Text between `:` and `}`, if any.
+    spec: String,
+}
+
+impl Display<'_> {
+    // Transform `"error {var:?}"` to `"error {}"` with `var` as the argument,
+    // the way defmt::write! takes it. Must run before expand_shorthand.
+    pub fn expand_defmt(&mut self, fields: &[Field]) -> Result<()> {
+        let (fmt, args) = self.field_args(fields, "#[thiserror(defmt)]", |spec| {
+            // Format specs like `:?` have no defmt counterpart; every value
+            // goes through its Format impl.
+            if spec == "join" {
+                Err("defmt has no way to join a collection")
+            } else {
+                Ok("{}".to_owned())
+            }
+        })?;
+        self.defmt = Some(DefmtMessage {
+            fmt,
+            fields: args
+                .iter()
+                .filter(|arg| arg.whole)
+                .map(|arg| arg.field)
+                .collect(),
+            args: args.into_iter().map(|arg| arg.expr).collect(),
+        });
+        Ok(())
+    }
+
+    // Transform `"error {var:?}"` to `"error {:?}"` with `var` as the
+    // argument, the way ufmt::uwrite! takes it. Must run before
+    // expand_shorthand.
+    pub fn expand_ufmt(&mut self, fields: &[Field]) -> Result<()> {
+        let (fmt, args) = self.field_args(fields, "#[thiserror(ufmt)]", |spec| match spec {
+            "" => Ok("{}".to_owned()),
+            "?" | "#?" => Ok(format!("{{:{}}}", spec)),
+            _ if is_ufmt_hex(spec) => Ok(format!("{{:{}}}", spec)),
+            _ => Err("ufmt only supports {}, {:?}, {:#?} and hexadecimal like {:x} or {:#04x}"),
+        })?;
+        let mut bounds = Set::new();
+        for arg in &args {
+            if arg.whole {
+                let bound = match arg.spec.as_str() {
+                    "" => UfmtTrait::Display,
+                    "?" | "#?" => UfmtTrait::Debug,
+                    _ => UfmtTrait::DisplayHex,
+                };
+                bounds.insert((arg.field, bound));
+            }
+        }
+        self.ufmt = Some(UfmtMessage {
+            fmt,
+            args: args.into_iter().map(|arg| arg.expr).collect(),
+            bounds,
+        });
+        Ok(())
+    }
+
+    fn field_args(
+        &self,
+        fields: &[Field],
+        attr: &str,
+        mut placeholder: impl FnMut(&str) -> std::result::Result<String, &'static str>,
+    ) -> Result<(LitStr, Vec<FieldArg>)> {
+        if self.matching.is_some() {
+            return Err(Error::new_spanned(
+                self.original,
+                format!("{} does not support #[error(match = ...)]", attr),
+            ));
+        }
+        if !self.args.is_empty() {
+            return Err(Error::new_spanned(
+                &self.args,
+                format!(
+                    "{} does not support format arguments; interpolate fields by name instead",
+                    attr,
+                ),
+            ));
+        }
+
+        let mut member_index = Map::new();
+        for (i, field) in fields.iter().enumerate() {
+            member_index.insert(&field.member, i);
+        }
+
+        let span = self.fmt.span();
+        let unsupported = || {
+            Error::new(
+                span,
+                format!(
+                    "{} only supports interpolating fields, like {{field}} or {{0}}",
+                    attr,
+                ),
+            )
+        };
+        let fmt = self.fmt.value();
+        let mut read = fmt.as_str();
+        let mut out = String::new();
+        let mut args = Vec::new();
+
+        while let Some(brace) = read.find('{') {
+            out += &read[..brace];
+            read = &read[brace + 1..];
+            if read.starts_with('{') {
+                out += "{{";
+                read = &read[1..];
+                continue;
+            }
+            let start = read;
+            let member = match read.chars().next() {
+                Some('0'..='9') => {
+                    let int = take_int(&mut read);
+                    match int.parse::<u32>() {
+                        Ok(index) => Member::Unnamed(Index { index, span }),
+                        Err(_) => return Err(unsupported()),
+                    }
+                }
+                Some('a'..='z') | Some('A'..='Z') | Some('_') => {
+                    let mut ident = take_ident(&mut read);
+                    ident.set_span(span);
+                    Member::Named(ident)
+                }
+                _ => return Err(unsupported()),
+            };
+            let field = match member_index.get(&member) {
+                Some(&field) => field,
+                None => return Err(unsupported()),
+            };
+            let path = take_path(&mut read, span);
+            let end_spec = match read.find('}') {
+                Some(end_spec) => end_spec,
+                None => return Err(unsupported()),
+            };
+            let spec = if read.starts_with(':') {
+                &read[1..end_spec]
+            } else if end_spec == 0 {
+                ""
+            } else {
+                return Err(unsupported());
+            };
+            read = &read[end_spec + 1..];
+            match placeholder(spec) {
+                Ok(replacement) => out += &replacement,
+                Err(reason) => {
+                    let original = &start[..start.len() - read.len() - 1];
+                    return Err(Error::new(
+                        span,
+                        format!(
+                            "`{{{}}}` is not supported by {}; {}",
+                            original, attr, reason,
+                        ),
+                    ));
+                }
+            }
+            let field_span = fields[field].original.span();
+            let mut local = match &member {
+                Member::Unnamed(index) => format_ident!("_{}", index),
+                Member::Named(ident) => ident.clone(),
+            };
+            local.set_span(field_span);
+            // Fields are bound by reference. Both macros borrow each argument
+            // again, so pass the place of the value itself.
+            let expr = if path.is_empty() {
+                quote_spanned!(field_span=> *#local)
+            } else {
+                quote_spanned!(field_span=> #local #(.#path)*)
+            };
+            args.push(FieldArg {
+                field,
+                expr,
+                whole: path.is_empty(),
+                spec: spec.to_owned(),
+            });
+        }
+
+        out += read;
+        Ok((LitStr::new(&out, span), args))
+    }
+}
+
+// ufmt's hex format: `x` or `X`, optionally after `#`, a `0` and a width.
+fn is_ufmt_hex(spec: &str) -> bool {
+    let spec = if spec.starts_with('#') {
+        &spec[1..]
+    } else {
+        spec
+    };
+    let digits = spec.trim_end_matches(|ch| ch == 'x' || ch == 'X');
+    spec.len() == digits.len() + 1 && digits.chars().all(|ch| ch.is_ascii_digit())
+}
+
+impl Display<'_> {
+    // The message with `{{` and `}}` unescaped, if it interpolates nothing.
+    pub fn static_message(&self) -> Option<String> {
+        if self.matching.is_some() || !self.args.is_empty() {
+            return None;
+        }
+        let fmt = self.fmt.value();
+        let mut message = String::new();
+        let mut chars = fmt.chars();
+        while let Some(ch) = chars.next() {
+            if (ch == '{' || ch == '}') && chars.next() != Some(ch) {
+                return None;
+            }
+            message.push(ch);
+        }
+        Some(message)
     }
 }
 
@@ -136,6 +471,50 @@ fn explicit_named_args(input: ParseStream) -> Result<Set<Ident>> {
     Ok(named_args)
 }
 
+fn take_path(read: &mut &str, span: Span) -> Vec<Member> {
+    let mut path = Vec::new();
+    while read.starts_with('.') {
+        let member = match read[1..].chars().next() {
+            Some('0'..='9') => {
+                *read = &read[1..];
+                let int = take_int(read);
+                match int.parse::<u32>() {
+                    Ok(index) => Member::Unnamed(Index { index, span }),
+                    Err(_) => break,
+                }
+            }
+            Some('a'..='z') | Some('A'..='Z') | Some('_') => {
+                *read = &read[1..];
+                let mut ident = take_ident(read);
+                ident.set_span(span);
+                Member::Named(ident)
+            }
+            _ => break,
+        };
+        path.push(member);
+    }
+    path
+}
+
+// Consumes `self.method()` if that is what comes next.
+fn take_self_method(read: &mut &str, span: Span) -> Option<Ident> {
+    if !read.starts_with("self.") {
+        return None;
+    }
+    let mut rest = &read["self.".len()..];
+    match rest.chars().next() {
+        Some('a'..='z') | Some('A'..='Z') | Some('_') => {}
+        _ => return None,
+    }
+    let mut method = take_ident(&mut rest);
+    if !rest.starts_with("()") {
+        return None;
+    }
+    *read = &rest[2..];
+    method.set_span(span);
+    Some(method)
+}
+
 fn take_int(read: &mut &str) -> String {
     let mut int = String::new();
     for (i, ch) in read.char_indices() {
@@ -1,9 +1,13 @@
-use proc_macro2::TokenStream;
-use quote::ToTokens;
+use proc_macro2::{TokenStream, TokenTree};
+use quote::{quote, ToTokens};
 use std::collections::btree_map::Entry;
 use std::collections::{BTreeMap as Map, BTreeSet as Set};
+use syn::parse::Parser;
 use syn::punctuated::Punctuated;
-use syn::{parse_quote, GenericArgument, Generics, Ident, PathArguments, Token, Type, WhereClause};
+use syn::{
+    parse_quote, GenericArgument, Generics, Ident, Member, PathArguments, PredicateType, Token,
+    Type, TypeParamBound, WhereClause, WherePredicate,
+};
 
 pub struct ParamsInScope<'a> {
     names: Set<&'a Ident>,
@@ -24,13 +28,33 @@ impl<'a> ParamsInScope<'a> {
 }
 
 fn crawl(in_scope: &ParamsInScope, ty: &Type, found: &mut bool) {
-    if let Type::Path(ty) = ty {
-        if ty.qself.is_none() {
-            if let Some(ident) = ty.path.get_ident() {
-                if in_scope.names.contains(ident) {
-                    *found = true;
-                }
+    match ty {
+        Type::Array(ty) => return crawl(in_scope, &ty.elem, found),
+        Type::Slice(ty) => return crawl(in_scope, &ty.elem, found),
+        Type::Reference(ty) => return crawl(in_scope, &ty.elem, found),
+        Type::Paren(ty) => return crawl(in_scope, &ty.elem, found),
+        Type::Tuple(ty) => {
+            for elem in &ty.elems {
+                crawl(in_scope, elem, found);
             }
+            return;
+        }
+        _ => {}
+    }
+    // A PhantomData<T> holds no value to format or use as a source, so the
+    // parameters it mentions never need bounds on its account.
+    if type_is_phantom_data(ty) {
+        return;
+    }
+    if let Type::Path(ty) = ty {
+        // Either a bare `T` or an associated type like `T::Err`, whose bounds
+        // get written against the full path rather than against `T`.
+        if let Some(qself) = &ty.qself {
+            crawl(in_scope, &qself.ty, found);
+        } else if ty.path.leading_colon.is_none()
+            && in_scope.names.contains(&ty.path.segments[0].ident)
+        {
+            *found = true;
         }
         for segment in &ty.path.segments {
             if let PathArguments::AngleBracketed(arguments) = &segment.arguments {
@@ -44,6 +68,32 @@ fn crawl(in_scope: &ParamsInScope, ty: &Type, found: &mut bool) {
     }
 }
 
+// The type of `field.0.1` given the type of `field` and the path `.0.1`, when
+// that can be seen from the type as written: through tuples.
+pub fn leaf_type<'a>(ty: &'a Type, path: &[Member]) -> Option<&'a Type> {
+    let mut ty = ty;
+    for member in path {
+        while let Type::Paren(paren) = ty {
+            ty = &paren.elem;
+        }
+        ty = match (ty, member) {
+            (Type::Tuple(tuple), Member::Unnamed(index)) => {
+                tuple.elems.iter().nth(index.index as usize)?
+            }
+            _ => return None,
+        };
+    }
+    Some(ty)
+}
+
+pub(crate) fn type_is_phantom_data(ty: &Type) -> bool {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return false,
+    };
+    path.segments.last().unwrap().ident == "PhantomData"
+}
+
 pub struct InferredBounds {
     bounds: Map<String, (Set<String>, Punctuated<TokenStream, Token![+]>)>,
     order: Vec<TokenStream>,
@@ -71,13 +121,95 @@ impl InferredBounds {
         }
     }
 
+    // Drops every inferred bound that mentions one of the explicitly bounded
+    // type parameters, then bounds those parameters as written.
+    pub fn replace(&mut self, predicates: &[&PredicateType]) {
+        if predicates.is_empty() {
+            return;
+        }
+        let params: Set<String> = predicates
+            .iter()
+            .map(|predicate| predicate.bounded_ty.to_token_stream().to_string())
+            .collect();
+        let bounds = &mut self.bounds;
+        self.order.retain(|ty| {
+            let mentions_param = mentions_any(ty.clone(), &params);
+            if mentions_param {
+                bounds.remove(&ty.to_string());
+            }
+            !mentions_param
+        });
+        for predicate in predicates {
+            let lifetimes = &predicate.lifetimes;
+            let ty = &predicate.bounded_ty;
+            for bound in &predicate.bounds {
+                self.insert(quote!(#lifetimes #ty), bound);
+            }
+        }
+    }
+
+    // Appends the inferred predicates to the ones written on the type, leaving
+    // out any bound the user already spelled out the same way.
     pub fn augment_where_clause(&self, generics: &Generics) -> WhereClause {
+        let written = written_bounds(generics);
         let mut generics = generics.clone();
         let where_clause = generics.make_where_clause();
         for ty in &self.order {
-            let (_set, bounds) = &self.bounds[&ty.to_string()];
-            where_clause.predicates.push(parse_quote!(#ty: #bounds));
+            let key = ty.to_string();
+            let (_set, bounds) = &self.bounds[&key];
+            let bounds: Punctuated<&TokenStream, Token![+]> = bounds
+                .iter()
+                .filter(|bound| !is_written(&written, &key, bound))
+                .collect();
+            if !bounds.is_empty() {
+                where_clause.predicates.push(parse_quote!(#ty: #bounds));
+            }
         }
         generics.where_clause.unwrap()
     }
 }
+
+// An inferred bound such as `std::error::Error + 'static` counts as written
+// only if each of its parts is.
+fn is_written(written: &Set<(String, String)>, ty: &str, bound: &TokenStream) -> bool {
+    let parser = Punctuated::<TypeParamBound, Token![+]>::parse_separated_nonempty;
+    match parser.parse2(bound.clone()) {
+        Ok(parts) => parts
+            .iter()
+            .all(|part| written.contains(&(ty.to_owned(), part.to_token_stream().to_string()))),
+        Err(_) => false,
+    }
+}
+
+fn mentions_any(tokens: TokenStream, idents: &Set<String>) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => idents.contains(&ident.to_string()),
+        TokenTree::Group(group) => mentions_any(group.stream(), idents),
+        TokenTree::Punct(_) | TokenTree::Literal(_) => false,
+    })
+}
+
+// Every (bounded type, bound) pair written by the user, either inline on a type
+// parameter or in the where clause.
+fn written_bounds(generics: &Generics) -> Set<(String, String)> {
+    let mut written = Set::new();
+    for param in generics.type_params() {
+        let ident = param.ident.to_string();
+        for bound in &param.bounds {
+            written.insert((ident.clone(), bound.to_token_stream().to_string()));
+        }
+    }
+    if let Some(where_clause) = &generics.where_clause {
+        for predicate in &where_clause.predicates {
+            if let WherePredicate::Type(predicate) = predicate {
+                let lifetimes = &predicate.lifetimes;
+                let ty = &predicate.bounded_ty;
+                let ty = quote!(#lifetimes #ty).to_string();
+                for bound in &predicate.bounds {
+                    written.insert((ty.clone(), bound.to_token_stream().to_string()));
+                }
+            }
+        }
+    }
+    written
+}
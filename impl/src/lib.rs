@@ -1,5 +1,5 @@
 #![allow(
-    clippy::blocks_in_if_conditions,
+    clippy::blocks_in_conditions,
     clippy::cast_lossless,
     clippy::cast_possible_truncation,
     clippy::manual_find,
@@ -17,16 +17,25 @@ extern crate proc_macro;
 
 mod ast;
 mod attr;
+mod compose;
 mod expand;
 mod fmt;
 mod generics;
 mod prop;
+#[cfg(test)]
+mod tests;
 mod valid;
 
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, DeriveInput};
 
-#[proc_macro_derive(Error, attributes(thiserror, backtrace, error, from, source))]
+#[proc_macro_derive(
+    Error,
+    attributes(
+        thiserror, aggregate, backtrace, error, flatten, from, provide, redact, related, sensitive,
+        source, span_trace, try_from
+    )
+)]
 pub fn derive_error(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
 
@@ -34,3 +43,12 @@ pub fn derive_error(input: TokenStream) -> TokenStream {
         .unwrap_or_else(|err| err.to_compile_error())
         .into()
 }
+
+#[proc_macro]
+pub fn compose(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as compose::Compose);
+
+    compose::expand(&input)
+        .unwrap_or_else(|err| err.to_compile_error())
+        .into()
+}
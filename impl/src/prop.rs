@@ -1,23 +1,68 @@
 use crate::ast::{Enum, Field, Struct, Variant};
-use syn::{Member, Type};
+use crate::expand::{type_parameter_of_arc, type_parameter_of_option};
+use crate::generics::type_is_phantom_data;
+use proc_macro2::{Ident, TokenStream};
+use quote::format_ident;
+use syn::ext::IdentExt;
+use syn::{Fields, GenericArgument, Member, PathArguments, Type};
 
 impl Struct<'_> {
-    pub(crate) fn from_field(&self) -> Option<&Field> {
+    pub(crate) fn from_field(&self) -> Option<&Field<'_>> {
         from_field(&self.fields)
     }
 
-    pub(crate) fn source_field(&self) -> Option<&Field> {
+    pub(crate) fn source_field(&self) -> Option<&Field<'_>> {
         source_field(&self.fields)
     }
 
-    pub(crate) fn backtrace_field(&self) -> Option<&Field> {
+    pub(crate) fn backtrace_field(&self) -> Option<&Field<'_>> {
         backtrace_field(&self.fields)
     }
 
-    pub(crate) fn distinct_backtrace_field(&self) -> Option<&Field> {
+    pub(crate) fn aggregate_field(&self) -> Option<&Field<'_>> {
+        aggregate_field(&self.fields)
+    }
+
+    pub(crate) fn provide_fields(&self) -> impl Iterator<Item = &Field<'_>> {
+        provide_fields(&self.fields)
+    }
+
+    pub(crate) fn phantom_fields(&self) -> impl Iterator<Item = &Field<'_>> {
+        phantom_fields(&self.fields)
+    }
+
+    pub(crate) fn span_trace_field(&self) -> Option<&Field<'_>> {
+        self.fields
+            .iter()
+            .find(|field| field.attrs.span_trace.is_some())
+    }
+
+    pub(crate) fn distinct_backtrace_field(&self) -> Option<&Field<'_>> {
         let backtrace_field = self.backtrace_field()?;
         distinct_backtrace_field(backtrace_field, self.from_field())
     }
+
+    pub(crate) fn data_fields(&self) -> Vec<&Field<'_>> {
+        data_fields(&self.fields, self.attrs.transparent.is_some())
+    }
+
+    // The field compared by #[thiserror(partial_eq = "message")]: the source,
+    // or the only field of a transparent error.
+    pub(crate) fn message_field(&self) -> Option<&Field<'_>> {
+        if self.attrs.transparent.is_some() {
+            self.fields.first()
+        } else {
+            self.source_field()
+        }
+    }
+
+    // The field appended to by #[thiserror(context_setter)].
+    pub(crate) fn context_field(&self) -> Option<&Field<'_>> {
+        self.fields.iter().find(|field| match &field.member {
+            Member::Named(ident) => ident == "context",
+            Member::Unnamed(_) => false,
+        })
+    }
 }
 
 impl Enum<'_> {
@@ -27,49 +72,248 @@ impl Enum<'_> {
             .any(|variant| variant.source_field().is_some() || variant.attrs.transparent.is_some())
     }
 
-    pub(crate) fn has_backtrace(&self) -> bool {
+    pub(crate) fn has_provide(&self) -> bool {
+        self.variants.iter().any(|variant| {
+            variant.backtrace_field().is_some()
+                || variant.attrs.transparent.is_some()
+                || variant.provide_fields().next().is_some()
+        })
+    }
+
+    // Whether every variant is a unit variant, so that it can be cast to its
+    // discriminant.
+    pub(crate) fn is_fieldless(&self) -> bool {
         self.variants
             .iter()
-            .any(|variant| variant.backtrace_field().is_some())
+            .all(|variant| match variant.original.fields {
+                Fields::Unit => true,
+                Fields::Named(_) | Fields::Unnamed(_) => false,
+            })
+    }
+
+    // The name of the enum generated by #[thiserror(kind)].
+    pub(crate) fn kind_ident(&self) -> Ident {
+        match &self.attrs.config.kind_ident {
+            Some(ident) => ident.clone(),
+            None => format_ident!("{}Kind", self.ident.unraw(), span = self.ident.span()),
+        }
+    }
+
+    // The extension trait on Result generated by #[thiserror(context)].
+    pub(crate) fn context_trait_ident(&self) -> Ident {
+        format_ident!("{}Context", self.ident.unraw(), span = self.ident.span())
     }
 
     pub(crate) fn has_display(&self) -> bool {
         self.attrs.display.is_some()
             || self.attrs.transparent.is_some()
-            || self
-                .variants
-                .iter()
-                .any(|variant| variant.attrs.display.is_some())
-            || self
-                .variants
-                .iter()
-                .all(|variant| variant.attrs.transparent.is_some())
+            || self.variants.iter().any(|variant| {
+                variant.attrs.display.is_some() || variant.attrs.transparent_str.is_some()
+            })
+            || self.variants.iter().all(|variant| {
+                variant.attrs.transparent.is_some() || variant.aggregate_field().is_some()
+            })
     }
 }
 
 impl Variant<'_> {
-    pub(crate) fn from_field(&self) -> Option<&Field> {
+    pub(crate) fn from_field(&self) -> Option<&Field<'_>> {
         from_field(&self.fields)
     }
 
-    pub(crate) fn source_field(&self) -> Option<&Field> {
+    pub(crate) fn source_field(&self) -> Option<&Field<'_>> {
         source_field(&self.fields)
     }
 
-    pub(crate) fn backtrace_field(&self) -> Option<&Field> {
+    pub(crate) fn backtrace_field(&self) -> Option<&Field<'_>> {
         backtrace_field(&self.fields)
     }
 
-    pub(crate) fn distinct_backtrace_field(&self) -> Option<&Field> {
+    pub(crate) fn aggregate_field(&self) -> Option<&Field<'_>> {
+        aggregate_field(&self.fields)
+    }
+
+    pub(crate) fn provide_fields(&self) -> impl Iterator<Item = &Field<'_>> {
+        provide_fields(&self.fields)
+    }
+
+    pub(crate) fn phantom_fields(&self) -> impl Iterator<Item = &Field<'_>> {
+        phantom_fields(&self.fields)
+    }
+
+    pub(crate) fn span_trace_field(&self) -> Option<&Field<'_>> {
+        self.fields
+            .iter()
+            .find(|field| field.attrs.span_trace.is_some())
+    }
+
+    pub(crate) fn distinct_backtrace_field(&self) -> Option<&Field<'_>> {
         let backtrace_field = self.backtrace_field()?;
         distinct_backtrace_field(backtrace_field, self.from_field())
     }
+
+    pub(crate) fn data_fields(&self) -> Vec<&Field<'_>> {
+        data_fields(&self.fields, self.attrs.transparent.is_some())
+    }
+
+    // The field compared by #[thiserror(partial_eq = "message")]: the source,
+    // or the only field of a transparent error.
+    pub(crate) fn message_field(&self) -> Option<&Field<'_>> {
+        if self.attrs.transparent.is_some() {
+            self.fields.first()
+        } else {
+            self.source_field()
+        }
+    }
+
+    // The name of the function generated by #[thiserror(constructors)], which
+    // is the variant's name in snake_case unless #[error(constructor = ...)]
+    // picks another. None if the snake_case name is a keyword that cannot be
+    // written as a raw identifier, like `self` or `crate`.
+    pub(crate) fn constructor_ident(&self) -> Option<Ident> {
+        if let Some(constructor) = &self.attrs.constructor {
+            return Some(constructor.ident.clone());
+        }
+        let name = snake_case(&self.ident.unraw().to_string());
+        let span = self.ident.span();
+        if syn::parse_str::<Ident>(&name).is_ok() {
+            Some(Ident::new(&name, span))
+        } else if ["self", "super", "crate"].contains(&name.as_str()) {
+            None
+        } else {
+            Some(Ident::new_raw(&name, span))
+        }
+    }
+
+    // The variant of the enum generated by #[thiserror(kind)] that this
+    // variant maps to.
+    pub(crate) fn kind_variant(&self) -> &Ident {
+        match &self.attrs.kind {
+            Some(kind) => &kind.ident,
+            None => &self.ident,
+        }
+    }
+
+    // The context selector generated by #[thiserror(context)] for a variant
+    // that wraps a source error, which carries the rest of its fields.
+    pub(crate) fn context_selector_ident(&self) -> Option<Ident> {
+        if self.attrs.transparent.is_some() || self.source_field().is_none() {
+            return None;
+        }
+        Some(format_ident!(
+            "{}Ctx",
+            self.ident.unraw(),
+            span = self.ident.span()
+        ))
+    }
+
+    // The name returned by variant_name() under #[thiserror(variant_names)],
+    // and deserialized into the variant by #[thiserror(deserialize)].
+    pub(crate) fn name(&self) -> String {
+        match &self.attrs.name {
+            Some(name) => name.value.value(),
+            None => self.ident.unraw().to_string(),
+        }
+    }
+
+    // The name of the method generated by #[thiserror(predicates)].
+    pub(crate) fn predicate_ident(&self) -> Ident {
+        self.prefixed_ident("is")
+    }
+
+    // The names of the methods generated by #[thiserror(accessors)].
+    pub(crate) fn accessor_idents(&self) -> (Ident, Ident) {
+        (self.prefixed_ident("as"), self.prefixed_ident("into"))
+    }
+
+    // The names of the methods generated by #[thiserror(inner_accessors)] for
+    // a transparent variant.
+    pub(crate) fn inner_accessor_idents(&self) -> (Ident, Ident, Ident) {
+        let as_ident = self.prefixed_ident("as");
+        let as_mut_ident = format_ident!("{}_mut", as_ident);
+        (as_ident, as_mut_ident, self.prefixed_ident("into"))
+    }
+
+    fn prefixed_ident(&self, prefix: &str) -> Ident {
+        let name = snake_case(&self.ident.unraw().to_string());
+        format_ident!("{}_{}", prefix, name, span = self.ident.span())
+    }
 }
 
 impl Field<'_> {
     pub(crate) fn is_backtrace(&self) -> bool {
         type_is_backtrace(self.ty)
     }
+
+    // The error inside the Arc of a #[from] field under #[thiserror(clone =
+    // "arc_sources")], which gets its own From impl that wraps it. None for a
+    // generic field, whose impl could overlap the others, and for a field
+    // converted by #[from(into = ...)] or kind dispatch.
+    pub(crate) fn arc_source_type(&self) -> Option<&Type> {
+        let from = self.attrs.from.as_ref()?;
+        if self.contains_generic || from.into.is_some() || !from.kinds.is_empty() {
+            return None;
+        }
+        let ty = type_parameter_of_option(self.ty).unwrap_or(self.ty);
+        type_parameter_of_arc(ty)
+    }
+
+    pub(crate) fn is_wrapped_source(&self) -> bool {
+        self.attrs
+            .source
+            .as_ref()
+            .map_or(false, |source| source.wrap)
+    }
+
+    // Either #[source(deref)], or a Box<Self> whose pointee is the source.
+    pub(crate) fn is_deref_source(&self) -> bool {
+        self.recursive
+            || self
+                .attrs
+                .source
+                .as_ref()
+                .map_or(false, |source| source.deref)
+    }
+
+    // Whether the source is reached through AsRef<dyn Error> rather than
+    // bounded by Error, per #[source(err_as_ref)] or else the given default.
+    pub(crate) fn is_err_as_ref(&self, default: bool) -> bool {
+        self.attrs
+            .source
+            .as_ref()
+            .and_then(|source| source.err_as_ref)
+            .unwrap_or(default)
+    }
+
+    // Whether the field's type contributes inferred bounds to the Display and
+    // Error impls, which #[error(no_bound)] opts out of.
+    pub(crate) fn infers_bounds(&self) -> bool {
+        self.contains_generic && self.attrs.no_bound.is_none()
+    }
+
+    // Whether the source field's type is bounded by Error, or whatever else
+    // the kind of source requires, which #[source(no_bound)] leaves to the
+    // where clause written on the type. A #[source(with = ...)] field need not
+    // be an error at all.
+    pub(crate) fn infers_source_bound(&self) -> bool {
+        self.infers_bounds()
+            && self
+                .attrs
+                .source
+                .as_ref()
+                .map_or(true, |source| !source.no_bound && source.with.is_none())
+    }
+
+    // The expression of #[source(with = ...)], which produces the source in
+    // place of the field itself.
+    pub(crate) fn source_with(&self) -> Option<&TokenStream> {
+        self.attrs.source.as_ref()?.with.as_ref()
+    }
+
+    // The element type of a #[related] or #[aggregate] Vec, array or slice.
+    pub(crate) fn collection_element(&self) -> Option<&Type> {
+        collection_element(self.ty)
+    }
 }
 
 fn from_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
@@ -81,15 +325,37 @@ fn from_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
     None
 }
 
-fn source_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
+fn aggregate_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
     for field in fields {
-        if field.attrs.from.is_some() || field.attrs.source.is_some() {
+        if field.attrs.aggregate.is_some() {
+            return Some(field);
+        }
+    }
+    None
+}
+
+pub(crate) fn source_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
+    for field in fields {
+        // #[from(into = ...)] converts from values that need not be errors, so
+        // it does not make the field a source by itself.
+        let from_source = field
+            .attrs
+            .from
+            .as_ref()
+            .map_or(false, |from| from.into.is_none());
+        if from_source || field.attrs.source.is_some() {
             return Some(field);
         }
     }
     for field in fields {
         match &field.member {
-            Member::Named(ident) if ident == "source" => return Some(field),
+            Member::Named(ident)
+                if ident == "source"
+                    && field.attrs.related.is_none()
+                    && !type_is_phantom_data(field.ty) =>
+            {
+                return Some(field)
+            }
             _ => {}
         }
     }
@@ -110,6 +376,41 @@ fn backtrace_field<'a, 'b>(fields: &'a [Field<'b>]) -> Option<&'a Field<'b>> {
     None
 }
 
+fn provide_fields<'a, 'b>(fields: &'a [Field<'b>]) -> impl Iterator<Item = &'a Field<'b>> {
+    fields
+        .iter()
+        .filter(|field| field.attrs.provide.is_some() || field.attrs.span_trace.is_some())
+}
+
+// Markers that a generated From impl fills in with `PhantomData`.
+fn phantom_fields<'a, 'b>(fields: &'a [Field<'b>]) -> impl Iterator<Item = &'a Field<'b>> {
+    fields.iter().filter(|field| type_is_phantom_data(field.ty))
+}
+
+// The fields that describe the error, as opposed to the source, backtrace,
+// span trace and related errors attached to it. These are what the Hash impl
+// of #[thiserror(hash_ignore_source)] looks at.
+fn data_fields<'a, 'b>(fields: &'a [Field<'b>], transparent: bool) -> Vec<&'a Field<'b>> {
+    if transparent {
+        return Vec::new();
+    }
+    let source_field = source_field(fields);
+    let backtrace_field = backtrace_field(fields);
+    let is_same = |field: &Field, other: Option<&Field>| {
+        other.map_or(false, |other| other.member == field.member)
+    };
+    fields
+        .iter()
+        .filter(|field| {
+            !is_same(field, source_field)
+                && !is_same(field, backtrace_field)
+                && !field.is_backtrace()
+                && field.attrs.span_trace.is_none()
+                && field.attrs.related.is_none()
+        })
+        .collect()
+}
+
 // The #[backtrace] field, if it is not the same as the #[from] field.
 fn distinct_backtrace_field<'a, 'b>(
     backtrace_field: &'a Field<'b>,
@@ -124,6 +425,23 @@ fn distinct_backtrace_field<'a, 'b>(
     }
 }
 
+// `QueryFailed` to `query_failed`, and `HTTPError` to `http_error`.
+fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut snake = String::new();
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = chars.get(i + 1).map_or(false, |next| next.is_lowercase());
+            if prev != '_' && (prev.is_lowercase() || prev.is_numeric() || next_is_lower) {
+                snake.push('_');
+            }
+        }
+        snake.extend(ch.to_lowercase());
+    }
+    snake
+}
+
 fn type_is_backtrace(ty: &Type) -> bool {
     let path = match ty {
         Type::Path(ty) => &ty.path,
@@ -133,3 +451,35 @@ fn type_is_backtrace(ty: &Type) -> bool {
     let last = path.segments.last().unwrap();
     last.ident == "Backtrace" && last.arguments.is_empty()
 }
+
+fn collection_element(ty: &Type) -> Option<&Type> {
+    match ty {
+        Type::Array(ty) => Some(&ty.elem),
+        Type::Slice(ty) => Some(&ty.elem),
+        Type::Reference(ty) => collection_element(&ty.elem),
+        Type::Path(ty) => {
+            let last = ty.path.segments.last().unwrap();
+            let args = match &last.arguments {
+                PathArguments::AngleBracketed(bracketed) if bracketed.args.len() == 1 => {
+                    &bracketed.args
+                }
+                _ => return None,
+            };
+            let arg = match &args[0] {
+                GenericArgument::Type(arg) => arg,
+                _ => return None,
+            };
+            if last.ident == "Vec" {
+                Some(arg)
+            } else if last.ident == "Box" {
+                match arg {
+                    Type::Slice(_) => collection_element(arg),
+                    _ => None,
+                }
+            } else {
+                None
+            }
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,110 @@
+use crate::expand;
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use syn::DeriveInput;
+
+// Whether each generated fn among `names` is marked #[inline], in the order
+// they appear in the expansion of `input`.
+fn inlined(input: &str, names: &[&str]) -> Vec<(String, bool)> {
+    let node: DeriveInput = syn::parse_str(input).unwrap();
+    let mut fns = Vec::new();
+    collect(expand::derive(&node).unwrap(), names, &mut fns);
+    fns
+}
+
+fn collect(tokens: TokenStream, names: &[&str], fns: &mut Vec<(String, bool)>) {
+    let tokens: Vec<TokenTree> = tokens.into_iter().collect();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenTree::Group(group) => collect(group.stream(), names, fns),
+            TokenTree::Ident(ident) if ident == "fn" => {
+                let name = match tokens.get(i + 1) {
+                    Some(TokenTree::Ident(name)) => name.to_string(),
+                    _ => continue,
+                };
+                if names.contains(&name.as_str()) {
+                    fns.push((name, has_inline(&tokens[..i])));
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Looks back over the outer attributes in front of a fn.
+fn has_inline(before: &[TokenTree]) -> bool {
+    let mut rest = before;
+    while let [init @ .., TokenTree::Punct(pound), TokenTree::Group(attr)] = rest {
+        if pound.as_char() != '#' || attr.delimiter() != Delimiter::Bracket {
+            break;
+        }
+        if attr.stream().to_string() == "inline" {
+            return true;
+        }
+        rest = init;
+    }
+    false
+}
+
+fn assert_inline(input: &str, names: &[&str]) {
+    let fns = inlined(input, names);
+    for name in names {
+        assert!(
+            fns.iter().any(|(found, _)| found == name),
+            "no fn {} generated",
+            name,
+        );
+    }
+    for (name, inline) in fns {
+        assert_eq!(
+            cfg!(feature = "inline"),
+            inline,
+            "#[inline] on fn {} with the inline feature {}",
+            name,
+            if cfg!(feature = "inline") {
+                "on"
+            } else {
+                "off"
+            },
+        );
+    }
+}
+
+#[test]
+fn test_inline_struct() {
+    assert_inline(
+        r#"
+        #[error("failed to read")]
+        #[try_from(u16, with = from_status)]
+        pub struct ReadError {
+            #[from]
+            source: std::io::Error,
+        }
+        "#,
+        &["fmt", "source", "from", "try_from"],
+    );
+    assert_inline(
+        r#"
+        #[error("parse failed")]
+        #[thiserror(debug)]
+        pub struct ParseError(#[from(ref)] std::num::ParseIntError);
+        "#,
+        &["fmt", "source", "from"],
+    );
+}
+
+#[test]
+fn test_inline_enum() {
+    assert_inline(
+        r#"
+        #[thiserror(debug)]
+        pub enum FetchError {
+            #[error("failed to read")]
+            Read(#[from] std::io::Error),
+            #[error("parse failed")]
+            #[try_from(u16, with = from_status)]
+            Parse(#[from(ref)] std::num::ParseIntError),
+        }
+        "#,
+        &["fmt", "source", "from", "try_from"],
+    );
+}
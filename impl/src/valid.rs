@@ -1,11 +1,30 @@
 use crate::ast::{Enum, Field, Input, Struct, Variant};
 use crate::attr::Attrs;
+use crate::expand::{type_is_option, type_parameter_of_arc, type_parameter_of_option};
+use crate::generics::type_is_phantom_data;
+use crate::prop;
+use proc_macro2::{Ident, Span};
 use quote::ToTokens;
-use std::collections::BTreeSet as Set;
-use syn::{Error, GenericArgument, Member, PathArguments, Result, Type};
+use std::collections::{BTreeMap as Map, BTreeSet as Set};
+use syn::ext::IdentExt;
+use syn::{
+    Error, Expr, ExprLit, ExprUnary, GenericArgument, Generics, Lit, Member, PathArguments, Result,
+    Type, TypeParamBound, UnOp,
+};
 
 impl Input<'_> {
     pub(crate) fn validate(&self) -> Result<()> {
+        let attrs = match self {
+            Input::Struct(input) => &input.attrs,
+            Input::Enum(input) => &input.attrs,
+        };
+        // thiserror::compose! takes this one out before the enum reaches here.
+        if let Some(document_messages) = attrs.config.document_messages {
+            return Err(Error::new(
+                document_messages,
+                "#[thiserror(document_messages)] is only supported in thiserror::compose!, as a derive cannot change the documentation of the item it is on",
+            ));
+        }
         match self {
             Input::Struct(input) => input.validate(),
             Input::Enum(input) => input.validate(),
@@ -13,122 +32,1563 @@ impl Input<'_> {
     }
 }
 
-impl Struct<'_> {
-    fn validate(&self) -> Result<()> {
-        check_non_field_attrs(&self.attrs)?;
-        if let Some(transparent) = self.attrs.transparent {
-            if self.fields.len() != 1 {
+impl Struct<'_> {
+    fn validate(&self) -> Result<()> {
+        check_non_field_attrs(&self.attrs)?;
+        check_no_flatten(&self.attrs)?;
+        check_transparent_str(&self.attrs, &self.fields)?;
+        if let Some(transparent) = self.attrs.transparent {
+            if self.fields.len() != 1 {
+                return Err(Error::new_spanned(
+                    transparent.original,
+                    "#[error(transparent)] requires exactly one field",
+                ));
+            }
+            if let Some(source) = self
+                .fields
+                .iter()
+                .find_map(|f| f.attrs.source.as_ref().map(|source| source.original))
+            {
+                return Err(Error::new_spanned(
+                    source,
+                    "transparent error struct can't contain #[source]",
+                ));
+            }
+            if let Some(provide) = self.fields.iter().find_map(|f| f.attrs.provide) {
+                return Err(Error::new_spanned(
+                    provide.original,
+                    "transparent error struct can't contain #[provide]",
+                ));
+            }
+            if self.fields[0].recursive {
+                return Err(Error::new_spanned(
+                    self.fields[0].ty,
+                    "transparent error struct can't forward to its own type",
+                ));
+            }
+        }
+        if let Some(code) = &self.attrs.code {
+            return Err(Error::new_spanned(
+                code.original,
+                "not expected here; the #[error(code = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(constructor) = &self.attrs.constructor {
+            return Err(Error::new_spanned(
+                constructor.original,
+                "not expected here; the #[error(constructor = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(kind) = &self.attrs.kind {
+            return Err(Error::new_spanned(
+                kind.original,
+                "not expected here; the #[error(kind = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(name) = &self.attrs.name {
+            return Err(Error::new_spanned(
+                name.original,
+                "not expected here; the #[error(name = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(discriminant_code) = self.attrs.config.discriminant_code {
+            return Err(Error::new(
+                discriminant_code,
+                "#[thiserror(discriminant_code)] is only supported on enums",
+            ));
+        }
+        if let Some(codes) = self.attrs.config.codes {
+            return Err(Error::new(
+                codes,
+                "#[thiserror(codes)] is only supported on enums",
+            ));
+        }
+        if let Some(localize) = &self.attrs.config.localize {
+            return Err(Error::new_spanned(
+                localize,
+                "#[thiserror(localize)] is only supported on enums",
+            ));
+        }
+        if let Some(deserialize) = self.attrs.config.deserialize {
+            return Err(Error::new(
+                deserialize,
+                "#[thiserror(deserialize)] is only supported on enums",
+            ));
+        }
+        if let Some(repr_c_codes) = self.attrs.config.repr_c_codes {
+            return Err(Error::new(
+                repr_c_codes,
+                "#[thiserror(repr_c_codes)] is only supported on enums",
+            ));
+        }
+        if let Some(constructors) = self.attrs.config.constructors {
+            return Err(Error::new(
+                constructors,
+                "#[thiserror(constructors)] is only supported on enums",
+            ));
+        }
+        if let Some(predicates) = self.attrs.config.predicates {
+            return Err(Error::new(
+                predicates,
+                "#[thiserror(predicates)] is only supported on enums",
+            ));
+        }
+        if let Some(accessors) = self.attrs.config.accessors {
+            return Err(Error::new(
+                accessors,
+                "#[thiserror(accessors)] is only supported on enums",
+            ));
+        }
+        if let Some(kind) = self.attrs.config.kind {
+            return Err(Error::new(
+                kind,
+                "#[thiserror(kind)] is only supported on enums",
+            ));
+        }
+        if let Some(context) = self.attrs.config.context {
+            return Err(Error::new(
+                context,
+                "#[thiserror(context)] is only supported on enums",
+            ));
+        }
+        if let Some(auto_transparent) = self.attrs.config.auto_transparent {
+            let is_newtype = match self.fields.as_slice() {
+                [field] => match field.member {
+                    Member::Unnamed(_) => true,
+                    Member::Named(_) => false,
+                },
+                _ => false,
+            };
+            if !is_newtype {
+                return Err(Error::new(
+                    auto_transparent,
+                    "#[thiserror(auto_transparent)] requires a tuple struct with exactly one field",
+                ));
+            }
+        }
+        check_bounds(&self.attrs, self.generics)?;
+        check_require_debug(&self.attrs, self.generics)?;
+        check_const_shadowing(&self.fields, self.generics)?;
+        check_display_match(&self.attrs, &self.fields)?;
+        check_opaque_paths(&[&self.attrs], &self.attrs, &self.fields)?;
+        check_field_attrs(&self.fields)?;
+        check_no_std(&self.attrs, &self.fields)?;
+        check_no_error_impl(&self.attrs, &self.fields)?;
+        check_serialize(&self.attrs, &self.attrs, &self.fields, self.source_field())?;
+        check_debug(&self.attrs, &self.fields)?;
+        check_clone(&self.attrs, self.attrs.transparent.is_some(), &self.fields)?;
+        for field in &self.fields {
+            field.validate()?;
+        }
+        if let Some(from) = self
+            .from_field()
+            .and_then(|field| field.attrs.from.as_ref())
+        {
+            if from.try_into {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "#[from(try_into)] is only supported on enum variants",
+                ));
+            }
+            if !from.kinds.is_empty() {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "#[from(kind = ...)] is only supported on enum variants",
+                ));
+            }
+        }
+        if let Some(eq) = self.attrs.config.eq {
+            return Err(Error::new(
+                eq,
+                "#[thiserror(eq)] is only supported on enums; derive PartialEq instead",
+            ));
+        }
+        check_partial_eq(&self.attrs)?;
+        if let Some(static_messages) = self.attrs.config.static_messages {
+            check_static_message(static_messages, &self.attrs)?;
+        }
+        if let Some(defmt) = self.attrs.config.defmt {
+            if self.attrs.display.is_none() && self.attrs.transparent.is_none() {
+                return Err(Error::new(
+                    defmt,
+                    "#[thiserror(defmt)] requires an #[error(\"...\")] message or #[error(transparent)]",
+                ));
+            }
+        }
+        if let Some(ufmt) = self.attrs.config.ufmt {
+            if self.attrs.display.is_none() && self.attrs.transparent.is_none() {
+                return Err(Error::new(
+                    ufmt,
+                    "#[thiserror(ufmt)] requires an #[error(\"...\")] message or #[error(transparent)]",
+                ));
+            }
+        }
+        if let Some(debug_transparent) = self.attrs.config.debug_transparent {
+            if self.attrs.transparent.is_none() {
+                return Err(Error::new(
+                    debug_transparent,
+                    "#[thiserror(debug_transparent)] requires #[error(transparent)]",
+                ));
+            }
+        }
+        if let Some(inner_accessors) = self.attrs.config.inner_accessors {
+            if self.attrs.transparent.is_none() {
+                return Err(Error::new(
+                    inner_accessors,
+                    "#[thiserror(inner_accessors)] requires #[error(transparent)]",
+                ));
+            }
+        }
+        if let Some(setters) = self.attrs.config.setters {
+            match self.source_field() {
+                Some(source_field) if !type_is_option(source_field.ty) => {
+                    return Err(Error::new_spanned(
+                        source_field.ty,
+                        "#[thiserror(setters)] requires the source field to be an Option",
+                    ));
+                }
+                Some(_) => {}
+                None => {
+                    return Err(Error::new(
+                        setters,
+                        "#[thiserror(setters)] requires an optional source field",
+                    ));
+                }
+            }
+        }
+        if let Some(context_setter) = self.attrs.config.context_setter {
+            match self.context_field() {
+                Some(context_field) => {
+                    let ty = type_parameter_of_option(context_field.ty).unwrap_or(context_field.ty);
+                    if !type_is_string(ty) {
+                        return Err(Error::new_spanned(
+                            context_field.ty,
+                            "#[thiserror(context_setter)] requires the context field to be a String or Option<String>",
+                        ));
+                    }
+                }
+                None => {
+                    return Err(Error::new(
+                        context_setter,
+                        "#[thiserror(context_setter)] requires a field named `context`",
+                    ));
+                }
+            }
+        }
+        if let (Some(try_from), Some(from_field)) = (&self.attrs.try_from, self.from_field()) {
+            if try_from.ty.to_token_stream().to_string()
+                == from_field.ty.to_token_stream().to_string()
+            {
+                return Err(Error::new_spanned(
+                    try_from.original,
+                    "cannot derive TryFrom because From is derived for the same type",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Enum<'_> {
+    fn validate(&self) -> Result<()> {
+        check_non_field_attrs(&self.attrs)?;
+        check_no_flatten(&self.attrs)?;
+        if let Some(transparent_str) = self.attrs.transparent_str {
+            return Err(Error::new_spanned(
+                transparent_str,
+                "not expected here; the #[error(transparent_str)] attribute belongs on top of a struct or an enum variant",
+            ));
+        }
+        if let Some(try_from) = &self.attrs.try_from {
+            return Err(Error::new_spanned(
+                try_from.original,
+                "not expected here; the #[try_from] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(auto_transparent) = self.attrs.config.auto_transparent {
+            return Err(Error::new(
+                auto_transparent,
+                "#[thiserror(auto_transparent)] is only supported on structs",
+            ));
+        }
+        if let Some(context_setter) = self.attrs.config.context_setter {
+            return Err(Error::new(
+                context_setter,
+                "#[thiserror(context_setter)] is only supported on structs",
+            ));
+        }
+        if let Some(code) = &self.attrs.code {
+            return Err(Error::new_spanned(
+                code.original,
+                "not expected here; the #[error(code = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(constructor) = &self.attrs.constructor {
+            return Err(Error::new_spanned(
+                constructor.original,
+                "not expected here; the #[error(constructor = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(kind) = &self.attrs.kind {
+            return Err(Error::new_spanned(
+                kind.original,
+                "not expected here; the #[error(kind = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(name) = &self.attrs.name {
+            return Err(Error::new_spanned(
+                name.original,
+                "not expected here; the #[error(name = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(debug_transparent) = self.attrs.config.debug_transparent {
+            if self
+                .variants
+                .iter()
+                .all(|variant| variant.attrs.transparent.is_none())
+            {
+                return Err(Error::new(
+                    debug_transparent,
+                    "#[thiserror(debug_transparent)] requires at least one #[error(transparent)] variant",
+                ));
+            }
+        }
+        if let Some(inner_accessors) = self.attrs.config.inner_accessors {
+            if self
+                .variants
+                .iter()
+                .all(|variant| variant.attrs.transparent.is_none())
+            {
+                return Err(Error::new(
+                    inner_accessors,
+                    "#[thiserror(inner_accessors)] requires at least one #[error(transparent)] variant",
+                ));
+            }
+            if self.attrs.config.accessors.is_some() {
+                return Err(Error::new(
+                    inner_accessors,
+                    "#[thiserror(inner_accessors)] cannot be combined with #[thiserror(accessors)], which already generates as_* and into_* methods for transparent variants",
+                ));
+            }
+        }
+        check_bounds(&self.attrs, self.generics)?;
+        check_require_debug(&self.attrs, self.generics)?;
+        for variant in &self.variants {
+            check_bounds(&variant.attrs, self.generics)?;
+            check_const_shadowing(&variant.fields, self.generics)?;
+            check_no_std(&self.attrs, &variant.fields)?;
+            check_no_error_impl(&self.attrs, &variant.fields)?;
+            check_serialize(
+                &self.attrs,
+                &variant.attrs,
+                &variant.fields,
+                variant.source_field(),
+            )?;
+            check_debug(&self.attrs, &variant.fields)?;
+            check_opaque_paths(
+                &[&self.attrs, &variant.attrs],
+                &variant.attrs,
+                &variant.fields,
+            )?;
+            let transparent = variant.attrs.transparent.is_some();
+            check_clone(&self.attrs, transparent, &variant.fields)?;
+        }
+        check_partial_eq(&self.attrs)?;
+        if self.attrs.config.eq.is_some() {
+            if let Some(variant) = self.variants.iter().find(|v| !v.fields.is_empty()) {
+                return Err(Error::new_spanned(
+                    &variant.original.fields,
+                    "#[thiserror(eq)] requires variants without fields, as it compares only which variant an error is; derive or implement PartialEq instead",
+                ));
+            }
+        }
+        if let Some(static_messages) = self.attrs.config.static_messages {
+            for variant in &self.variants {
+                check_static_message(static_messages, &variant.attrs)?;
+            }
+        }
+        if self.attrs.config.defmt.is_some() {
+            for variant in &self.variants {
+                if variant.attrs.display.is_none() && variant.attrs.transparent.is_none() {
+                    return Err(Error::new_spanned(
+                        variant.original,
+                        "#[thiserror(defmt)] requires an #[error(\"...\")] message or #[error(transparent)] on each variant",
+                    ));
+                }
+            }
+        }
+        if self.attrs.config.ufmt.is_some() {
+            for variant in &self.variants {
+                if variant.attrs.display.is_none() && variant.attrs.transparent.is_none() {
+                    return Err(Error::new_spanned(
+                        variant.original,
+                        "#[thiserror(ufmt)] requires an #[error(\"...\")] message or #[error(transparent)] on each variant",
+                    ));
+                }
+            }
+        }
+        let mut span_trace_type = None;
+        for variant in &self.variants {
+            if let Some(span_trace_field) = variant.span_trace_field() {
+                let ty =
+                    type_parameter_of_option(span_trace_field.ty).unwrap_or(span_trace_field.ty);
+                let repr = ty.to_token_stream().to_string();
+                match &span_trace_type {
+                    Some(span_trace_type) if *span_trace_type != repr => {
+                        return Err(Error::new_spanned(
+                            span_trace_field.ty,
+                            "#[span_trace] fields must have the same type in every variant",
+                        ));
+                    }
+                    _ => span_trace_type = Some(repr),
+                }
+            }
+        }
+        if let Some(setters) = self.attrs.config.setters {
+            let mut source_type = None;
+            for variant in &self.variants {
+                let source_field = match variant.source_field() {
+                    Some(source_field) if variant.attrs.transparent.is_none() => source_field,
+                    _ => continue,
+                };
+                if !type_is_option(source_field.ty) {
+                    return Err(Error::new_spanned(
+                        source_field.ty,
+                        "#[thiserror(setters)] requires every source field to be an Option",
+                    ));
+                }
+                let repr = source_field.ty.to_token_stream().to_string();
+                match &source_type {
+                    None => source_type = Some(repr),
+                    Some(source_type) if *source_type != repr => {
+                        return Err(Error::new_spanned(
+                            source_field.ty,
+                            "#[thiserror(setters)] requires every source field to have the same type",
+                        ));
+                    }
+                    Some(_) => {}
+                }
+            }
+            if source_type.is_none() {
+                return Err(Error::new(
+                    setters,
+                    "#[thiserror(setters)] requires an optional source field",
+                ));
+            }
+        }
+        let has_display = self.has_display();
+        for variant in &self.variants {
+            variant.validate()?;
+            if has_display
+                && variant.attrs.display.is_none()
+                && variant.attrs.transparent.is_none()
+                && variant.attrs.transparent_str.is_none()
+                && variant.aggregate_field().is_none()
+            {
+                return Err(Error::new_spanned(
+                    variant.original,
+                    "missing #[error(\"...\")] display attribute",
+                ));
+            }
+        }
+        if let Some(from) = self
+            .variants
+            .iter()
+            .filter_map(|variant| variant.from_field()?.attrs.from.as_ref())
+            .filter(|from| from.into.is_some())
+            .nth(1)
+        {
+            return Err(Error::new_spanned(
+                from.original,
+                "only one variant may use #[from(into = ...)], because each one implements From for every type convertible into its field",
+            ));
+        }
+        let mut from_types = Set::new();
+        let mut from_kinds = Set::new();
+        for variant in &self.variants {
+            if let Some(from_field) = variant.from_field() {
+                let repr = from_field.ty.to_token_stream().to_string();
+                let from = from_field.attrs.from.as_ref().unwrap();
+                if !from.kinds.is_empty() {
+                    for kind in &from.kinds {
+                        if !from_kinds.insert((repr.clone(), kind.to_string())) {
+                            return Err(Error::new_spanned(
+                                kind,
+                                "another variant already handles this error kind",
+                            ));
+                        }
+                    }
+                } else if !from_types.insert(repr) {
+                    return Err(Error::new_spanned(
+                        from_field.original,
+                        "cannot derive From because another variant has the same source type",
+                    ));
+                }
+            }
+        }
+        // Types converted by way of a #[from] field, checked once every plain
+        // #[from] type is known so that the error points at the indirect one.
+        for variant in &self.variants {
+            if let Some(from_field) = variant.from_field() {
+                let from = from_field.attrs.from.as_ref().unwrap();
+                let arc_source = if self.attrs.config.clone.is_some() {
+                    from_field.arc_source_type()
+                } else {
+                    None
+                };
+                let flattened = variant.attrs.flatten.iter().flat_map(|f| &f.types);
+                for via in from.via.iter().chain(arc_source).chain(flattened) {
+                    if !from_types.insert(via.to_token_stream().to_string()) {
+                        return Err(Error::new_spanned(
+                            via,
+                            "cannot derive From because another variant already converts from this type",
+                        ));
+                    }
+                }
+            }
+        }
+        for variant in &self.variants {
+            if let Some(from_field) = variant.from_field() {
+                let repr = from_field.ty.to_token_stream().to_string();
+                let from = from_field.attrs.from.as_ref().unwrap();
+                if !from.kinds.is_empty() && !from_types.contains(&repr) {
+                    return Err(Error::new_spanned(
+                        from.original,
+                        "#[from(kind = ...)] requires a catch-all variant with a plain #[from] of the same type",
+                    ));
+                }
+            }
+        }
+        if let Some(from_field) = self
+            .variants
+            .iter()
+            .filter_map(Variant::from_field)
+            .find(|field| is_bare_type_param(field.ty, self.generics))
+        {
+            let other_from = self
+                .variants
+                .iter()
+                .filter_map(Variant::from_field)
+                .any(|field| !std::ptr::eq(field, from_field));
+            let has_try_from = self
+                .variants
+                .iter()
+                .any(|variant| variant.attrs.try_from.is_some());
+            if other_from || has_try_from {
+                return Err(Error::new_spanned(
+                    from_field.attrs.from.as_ref().map(|from| from.original),
+                    format!(
+                        "#[from] on the bare type parameter `{}` overlaps with the other conversions into this enum, because `{}` may be instantiated as any of their source types; remove #[from] and construct this variant directly or through a named constructor instead",
+                        from_field.ty.to_token_stream(),
+                        from_field.ty.to_token_stream(),
+                    ),
+                ));
+            }
+        }
+        let mut try_into_types = Set::new();
+        for from_field in self.variants.iter().filter_map(Variant::from_field) {
+            let from = from_field.attrs.from.as_ref().unwrap();
+            if !from.try_into {
+                continue;
+            }
+            if from_field.recursive {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "#[from(try_into)] cannot convert back into a pointer to the error itself",
+                ));
+            }
+            if is_bare_type_param(from_field.ty, self.generics) || is_unsized(from_field.ty) {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "#[from(try_into)] requires a sized, concrete source type",
+                ));
+            }
+            let repr = from_field.ty.to_token_stream().to_string();
+            if !try_into_types.insert(repr) {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "cannot derive TryFrom because another variant converts back into the same source type",
+                ));
+            }
+        }
+        let mut try_from_types = Set::new();
+        for variant in &self.variants {
+            if let Some(try_from) = &variant.attrs.try_from {
+                let repr = try_from.ty.to_token_stream().to_string();
+                if from_types.contains(&repr) {
+                    return Err(Error::new_spanned(
+                        try_from.original,
+                        "cannot derive TryFrom because another variant derives From for the same type",
+                    ));
+                }
+                if !try_from_types.insert(repr) {
+                    return Err(Error::new_spanned(
+                        try_from.original,
+                        "cannot derive TryFrom because another variant has the same source type",
+                    ));
+                }
+            }
+        }
+        check_discriminant_code(self)?;
+        check_constructors(self)?;
+        check_variant_methods(self)?;
+        check_kind(self)?;
+        check_variant_names(self)?;
+        check_context(self)?;
+        check_localize(self)?;
+        Ok(())
+    }
+}
+
+impl Variant<'_> {
+    fn validate(&self) -> Result<()> {
+        check_non_field_attrs(&self.attrs)?;
+        check_no_config(&self.attrs)?;
+        check_display_match(&self.attrs, &self.fields)?;
+        check_transparent_str(&self.attrs, &self.fields)?;
+        if let Some(flatten) = &self.attrs.flatten {
+            if self.attrs.display.is_some() || self.attrs.transparent_str.is_some() {
+                return Err(Error::new_spanned(
+                    flatten.original,
+                    "#[flatten] takes the message of the error it wraps and cannot be combined with a display attribute",
+                ));
+            }
+            if self.fields.len() != 1 {
+                return Err(Error::new_spanned(
+                    flatten.original,
+                    "#[flatten] requires exactly one field",
+                ));
+            }
+            if !flatten.types.is_empty() && self.from_field().is_none() {
+                return Err(Error::new_spanned(
+                    flatten.original,
+                    "#[flatten(...)] requires a #[from] field to convert the listed types through",
+                ));
+            }
+        }
+        if let Some(transparent) = self.attrs.transparent {
+            if self.fields.len() != 1 {
+                // Transparent inherited from the enum applies to every variant
+                // without a message, so say how to opt this one out.
+                let inherited = !self
+                    .original
+                    .attrs
+                    .iter()
+                    .any(|attr| std::ptr::eq(attr, transparent.original));
+                let msg = if inherited {
+                    "#[error(transparent)] on the enum requires exactly one field in each variant without its own #[error(\"...\")] message"
+                } else {
+                    "#[error(transparent)] requires exactly one field"
+                };
+                return Err(Error::new_spanned(self.original, msg));
+            }
+            if let Some(source) = self
+                .fields
+                .iter()
+                .find_map(|f| f.attrs.source.as_ref().map(|source| source.original))
+            {
+                return Err(Error::new_spanned(
+                    source,
+                    "transparent variant can't contain #[source]",
+                ));
+            }
+            if let Some(provide) = self.fields.iter().find_map(|f| f.attrs.provide) {
+                return Err(Error::new_spanned(
+                    provide.original,
+                    "transparent variant can't contain #[provide]",
+                ));
+            }
+            if self.fields[0].recursive {
+                return Err(Error::new_spanned(
+                    self.fields[0].ty,
+                    "transparent variant can't forward to its own type",
+                ));
+            }
+        }
+        check_field_attrs(&self.fields)?;
+        if let Some(source) = self
+            .fields
+            .iter()
+            .filter_map(|f| f.attrs.source.as_ref())
+            .find(|source| source.with.is_some())
+        {
+            return Err(Error::new_spanned(
+                source.original,
+                "#[source(with = ...)] is only supported on structs, where the expression can use `self`",
+            ));
+        }
+        for field in &self.fields {
+            field.validate()?;
+        }
+        Ok(())
+    }
+}
+
+impl Field<'_> {
+    fn validate(&self) -> Result<()> {
+        check_no_config(&self.attrs)?;
+        check_no_flatten(&self.attrs)?;
+        if let Some(display) = &self.attrs.display {
+            return Err(Error::new_spanned(
+                display.original,
+                "not expected here; the #[error(...)] attribute belongs on top of a struct or an enum variant",
+            ));
+        }
+        if let Some(transparent_str) = self.attrs.transparent_str {
+            return Err(Error::new_spanned(
+                transparent_str,
+                "not expected here; the #[error(transparent_str)] attribute belongs on top of a struct or an enum variant",
+            ));
+        }
+        if let Some(level) = &self.attrs.level {
+            return Err(Error::new_spanned(
+                level.original,
+                "not expected here; the #[error(level = ...)] attribute belongs on top of a struct or an enum variant",
+            ));
+        }
+        if let Some(exit_code) = &self.attrs.exit_code {
+            return Err(Error::new_spanned(
+                exit_code.original,
+                "not expected here; the #[error(exit_code = ...)] attribute belongs on top of a struct or an enum variant",
+            ));
+        }
+        if let Some(code) = &self.attrs.code {
+            return Err(Error::new_spanned(
+                code.original,
+                "not expected here; the #[error(code = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(constructor) = &self.attrs.constructor {
+            return Err(Error::new_spanned(
+                constructor.original,
+                "not expected here; the #[error(constructor = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(kind) = &self.attrs.kind {
+            return Err(Error::new_spanned(
+                kind.original,
+                "not expected here; the #[error(kind = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(name) = &self.attrs.name {
+            return Err(Error::new_spanned(
+                name.original,
+                "not expected here; the #[error(name = ...)] attribute belongs on a specific variant",
+            ));
+        }
+        if let Some(bound) = self.attrs.bounds.first() {
+            return Err(Error::new_spanned(
+                bound.original,
+                "not expected here; the #[error(bound(...))] attribute belongs on top of a struct or an enum variant",
+            ));
+        }
+        if let Some(try_from) = &self.attrs.try_from {
+            return Err(Error::new_spanned(
+                try_from.original,
+                "not expected here; the #[try_from] attribute belongs on top of a struct or an enum variant",
+            ));
+        }
+        if let Some(backtrace) = &self.attrs.backtrace {
+            let is_source = self.attrs.source.is_some()
+                || self.attrs.from.is_some()
+                || match &self.member {
+                    Member::Named(ident) => ident == "source",
+                    Member::Unnamed(_) => false,
+                };
+            if is_source
+                && (backtrace.force || backtrace.disabled_unless.is_some() || backtrace.from_source)
+            {
+                return Err(Error::new_spanned(
+                    backtrace.original,
+                    "backtrace capture options are not supported on a source field, whose backtrace is delegated",
+                ));
+            }
+        }
+        if let Some(source) = &self.attrs.source {
+            if source.err_as_ref.is_some()
+                && (source.wrap || source.deref || self.attrs.aggregate.is_some())
+            {
+                return Err(Error::new_spanned(
+                    source.original,
+                    "#[source(err_as_ref)] cannot be combined with wrap, deref or #[aggregate]",
+                ));
+            }
+            if source.with.is_some()
+                && (source.wrap
+                    || source.deref
+                    || source.err_as_ref.is_some()
+                    || self.attrs.aggregate.is_some())
+            {
+                return Err(Error::new_spanned(
+                    source.original,
+                    "#[source(with = ...)] cannot be combined with wrap, deref, err_as_ref or #[aggregate]",
+                ));
+            }
+        }
+        if let Some(aggregate) = &self.attrs.aggregate {
+            if self.collection_element().is_none() {
+                return Err(Error::new_spanned(
+                    aggregate.original,
+                    "#[aggregate] requires a Vec, array or slice of errors",
+                ));
+            }
+        }
+        if let Some(span_trace) = self.attrs.span_trace {
+            if self.attrs.source.is_some() || self.attrs.from.is_some() {
+                return Err(Error::new_spanned(
+                    span_trace,
+                    "#[span_trace] field cannot also be the #[source]",
+                ));
+            }
+        }
+        if let Some(related) = self.attrs.related {
+            if self.attrs.source.is_some() || self.attrs.from.is_some() {
+                return Err(Error::new_spanned(
+                    related,
+                    "#[related] field cannot also be the #[source]",
+                ));
+            }
+            if self.collection_element().is_none() {
+                return Err(Error::new_spanned(
+                    related,
+                    "#[related] requires a Vec, array or slice of errors",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+// The message of #[error(transparent_str)] is the string in its only field,
+// in place of any other message.
+fn check_transparent_str(attrs: &Attrs, fields: &[Field]) -> Result<()> {
+    let transparent_str = match attrs.transparent_str {
+        Some(transparent_str) => transparent_str,
+        None => return Ok(()),
+    };
+    if attrs.display.is_some() || attrs.transparent.is_some() {
+        return Err(Error::new_spanned(
+            transparent_str,
+            "#[error(transparent_str)] cannot be combined with #[error(transparent)] or a display attribute",
+        ));
+    }
+    if fields.len() != 1 {
+        return Err(Error::new_spanned(
+            transparent_str,
+            "#[error(transparent_str)] requires exactly one field",
+        ));
+    }
+    Ok(())
+}
+
+fn check_non_field_attrs(attrs: &Attrs) -> Result<()> {
+    if let Some(aggregate) = &attrs.aggregate {
+        return Err(Error::new_spanned(
+            aggregate.original,
+            "not expected here; the #[aggregate] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(from) = &attrs.from {
+        return Err(Error::new_spanned(
+            from.original,
+            "not expected here; the #[from] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(source) = &attrs.source {
+        return Err(Error::new_spanned(
+            source.original,
+            "not expected here; the #[source] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(no_bound) = &attrs.no_bound {
+        return Err(Error::new_spanned(
+            no_bound,
+            "not expected here; the #[error(no_bound)] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(serialize) = &attrs.serialize {
+        return Err(Error::new_spanned(
+            serialize,
+            "not expected here; the #[error(serialize)] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(sensitive) = &attrs.sensitive {
+        return Err(Error::new_spanned(
+            sensitive,
+            "not expected here; the #[sensitive] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(redact) = &attrs.redact {
+        return Err(Error::new_spanned(
+            redact,
+            "not expected here; the #[redact] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(backtrace) = &attrs.backtrace {
+        return Err(Error::new_spanned(
+            backtrace.original,
+            "not expected here; the #[backtrace] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(provide) = &attrs.provide {
+        return Err(Error::new_spanned(
+            provide.original,
+            "not expected here; the #[provide] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(span_trace) = &attrs.span_trace {
+        return Err(Error::new_spanned(
+            span_trace,
+            "not expected here; the #[span_trace] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(related) = &attrs.related {
+        return Err(Error::new_spanned(
+            related,
+            "not expected here; the #[related] attribute belongs on a specific field",
+        ));
+    }
+    if let Some(try_from) = &attrs.try_from {
+        if attrs.transparent.is_some() {
+            return Err(Error::new_spanned(
+                try_from.original,
+                "#[try_from] is not supported together with #[error(transparent)]",
+            ));
+        }
+    }
+    if let (Some(display), Some(transparent)) = (&attrs.display, &attrs.transparent) {
+        let msg = "cannot have both #[error(transparent)] and a display attribute";
+        let mut error = Error::new_spanned(transparent.original, msg);
+        error.combine(Error::new_spanned(display.original, msg));
+        return Err(error);
+    }
+    Ok(())
+}
+
+fn check_no_flatten(attrs: &Attrs) -> Result<()> {
+    if let Some(flatten) = &attrs.flatten {
+        return Err(Error::new_spanned(
+            flatten.original,
+            "not expected here; the #[flatten] attribute belongs on top of an enum variant",
+        ));
+    }
+    Ok(())
+}
+
+fn check_no_config(attrs: &Attrs) -> Result<()> {
+    if let Some(debug_transparent) = attrs.config.debug_transparent {
+        return Err(Error::new(
+            debug_transparent,
+            "not expected here; the #[thiserror(debug_transparent)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(inner_accessors) = attrs.config.inner_accessors {
+        return Err(Error::new(
+            inner_accessors,
+            "not expected here; the #[thiserror(inner_accessors)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(defmt) = attrs.config.defmt {
+        return Err(Error::new(
+            defmt,
+            "not expected here; the #[thiserror(defmt)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(ufmt) = attrs.config.ufmt {
+        return Err(Error::new(
+            ufmt,
+            "not expected here; the #[thiserror(ufmt)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(deserialize) = attrs.config.deserialize {
+        return Err(Error::new(
+            deserialize,
+            "not expected here; the #[thiserror(deserialize)] attribute belongs on top of an enum",
+        ));
+    }
+    if let Some(variant_names) = attrs.config.variant_names {
+        return Err(Error::new(
+            variant_names,
+            "not expected here; the #[thiserror(variant_names)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(variants) = attrs.config.variants {
+        return Err(Error::new(
+            variants,
+            "not expected here; the #[thiserror(variants)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(eq) = attrs.config.eq {
+        return Err(Error::new(
+            eq,
+            "not expected here; the #[thiserror(eq)] attribute belongs on top of an enum",
+        ));
+    }
+    if let Some(fields) = attrs.config.fields {
+        return Err(Error::new(
+            fields,
+            "not expected here; the #[thiserror(fields)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(document_messages) = attrs.config.document_messages {
+        return Err(Error::new(
+            document_messages,
+            "not expected here; the #[thiserror(document_messages)] attribute belongs on top of an enum",
+        ));
+    }
+    if let Some(downcast) = attrs.config.downcast {
+        return Err(Error::new(
+            downcast,
+            "not expected here; the #[thiserror(downcast)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(constructors) = attrs.config.constructors {
+        return Err(Error::new(
+            constructors,
+            "not expected here; the #[thiserror(constructors)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(context) = attrs.config.context {
+        return Err(Error::new(
+            context,
+            "not expected here; the #[thiserror(context)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(no_error_impl) = attrs.config.no_error_impl {
+        return Err(Error::new(
+            no_error_impl,
+            "not expected here; the #[thiserror(no_error_impl)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(auto_transparent) = attrs.config.auto_transparent {
+        return Err(Error::new(
+            auto_transparent,
+            "not expected here; the #[thiserror(auto_transparent)] attribute belongs on top of a struct",
+        ));
+    }
+    if let Some(context_setter) = attrs.config.context_setter {
+        return Err(Error::new(
+            context_setter,
+            "not expected here; the #[thiserror(context_setter)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(discriminant_code) = attrs.config.discriminant_code {
+        return Err(Error::new(
+            discriminant_code,
+            "not expected here; the #[thiserror(discriminant_code)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(codes) = attrs.config.codes {
+        return Err(Error::new(
+            codes,
+            "not expected here; the #[thiserror(codes)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(clone) = attrs.config.clone {
+        return Err(Error::new(
+            clone,
+            "not expected here; the #[thiserror(clone)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(repr_c_codes) = attrs.config.repr_c_codes {
+        return Err(Error::new(
+            repr_c_codes,
+            "not expected here; the #[thiserror(repr_c_codes)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(predicates) = attrs.config.predicates {
+        return Err(Error::new(
+            predicates,
+            "not expected here; the #[thiserror(predicates)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(accessors) = attrs.config.accessors {
+        return Err(Error::new(
+            accessors,
+            "not expected here; the #[thiserror(accessors)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(hash_ignore_source) = attrs.config.hash_ignore_source {
+        return Err(Error::new(
+            hash_ignore_source,
+            "not expected here; the #[thiserror(hash_ignore_source)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(partial_eq) = attrs.config.partial_eq {
+        return Err(Error::new(
+            partial_eq,
+            "not expected here; the #[thiserror(partial_eq)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(report) = attrs.config.report {
+        return Err(Error::new(
+            report,
+            "not expected here; the #[thiserror(report)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(root_cause) = attrs.config.root_cause {
+        return Err(Error::new(
+            root_cause,
+            "not expected here; the #[thiserror(root_cause)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(serialize) = attrs.config.serialize {
+        return Err(Error::new(
+            serialize,
+            "not expected here; the #[thiserror(serialize)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(setters) = attrs.config.setters {
+        return Err(Error::new(
+            setters,
+            "not expected here; the #[thiserror(setters)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(static_messages) = attrs.config.static_messages {
+        return Err(Error::new(
+            static_messages,
+            "not expected here; the #[thiserror(static_messages)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(no_std) = attrs.config.no_std {
+        return Err(Error::new(
+            no_std,
+            "not expected here; the #[thiserror(no_std)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(kind) = attrs.config.kind {
+        return Err(Error::new(
+            kind,
+            "not expected here; the #[thiserror(kind)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(legacy_description) = attrs.config.legacy_description {
+        return Err(Error::new(
+            legacy_description,
+            "not expected here; the #[thiserror(legacy_description)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(localize) = &attrs.config.localize {
+        return Err(Error::new_spanned(
+            localize,
+            "not expected here; the #[thiserror(localize)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(related) = attrs.config.related {
+        return Err(Error::new(
+            related,
+            "not expected here; the #[thiserror(related)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(require_debug) = attrs.config.require_debug {
+        return Err(Error::new(
+            require_debug,
+            "not expected here; the #[thiserror(require_debug)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(hide_generated) = attrs.config.hide_generated {
+        return Err(Error::new(
+            hide_generated,
+            "not expected here; the #[thiserror(hide_generated)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(debug) = attrs.config.debug {
+        return Err(Error::new(
+            debug,
+            "not expected here; the #[thiserror(debug)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(result_alias) = attrs.config.result_alias {
+        return Err(Error::new(
+            result_alias,
+            "not expected here; the #[thiserror(result_alias)] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    if let Some(crate_path) = &attrs.config.crate_path {
+        return Err(Error::new_spanned(
+            crate_path,
+            "not expected here; the #[thiserror(crate = \"...\")] attribute belongs on top of a struct or an enum",
+        ));
+    }
+    Ok(())
+}
+
+fn check_bounds(attrs: &Attrs, generics: &Generics) -> Result<()> {
+    for bound in &attrs.bounds {
+        for predicate in &bound.predicates {
+            let param = match &predicate.bounded_ty {
+                Type::Path(ty) if ty.qself.is_none() => ty.path.get_ident(),
+                _ => None,
+            };
+            let is_param = param.map_or(false, |ident| {
+                generics.type_params().any(|param| param.ident == *ident)
+            });
+            if !is_param {
+                return Err(Error::new_spanned(
+                    &predicate.bounded_ty,
+                    format!(
+                        "`{}` is not a type parameter of this type",
+                        predicate.bounded_ty.to_token_stream(),
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+// A const parameter `N` can be interpolated as `{N}`. A field of the same name
+// could not be bound by the generated code, since the pattern `N` would match
+// against the constant instead of binding the field.
+fn check_const_shadowing(fields: &[Field], generics: &Generics) -> Result<()> {
+    for field in fields {
+        let ident = match &field.member {
+            Member::Named(ident) => ident,
+            Member::Unnamed(_) => continue,
+        };
+        if generics.const_params().any(|param| param.ident == *ident) {
+            return Err(Error::new_spanned(
+                ident,
+                format!(
+                    "field `{}` has the same name as a const parameter, which `{{{}}}` in a message refers to; rename one of them",
+                    ident, ident,
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// A path like `{inner.code}` into a field of generic type reaches a value whose
+// type the derive cannot see, so whether it needs a bound is up to the user,
+// unless #[error(bound(...))] already took over the bounds of the message.
+fn check_opaque_paths(bounds: &[&Attrs], attrs: &Attrs, fields: &[Field]) -> Result<()> {
+    let display = match &attrs.display {
+        Some(display) => display,
+        None => return Ok(()),
+    };
+    if bounds
+        .iter()
+        .any(|attrs| attrs.bounds.iter().any(|bound| bound.display))
+    {
+        return Ok(());
+    }
+    for (field, path) in &display.opaque_paths {
+        if fields[*field].infers_bounds() && !fields[*field].recursive {
+            let msg = format!(
+                "cannot infer a bound for {{{}}} through a field of generic type; if the value is generic, bound it with #[error(bound(...))], otherwise pass it as an argument, as in #[error(\"{{}}\", .{})]",
+                path, path,
+            );
+            return Err(Error::new_spanned(&display.fmt, msg));
+        }
+    }
+    Ok(())
+}
+
+// Whether a generic error implements Debug depends on its type arguments, and
+// the other options leave no Debug requirement for the check to explain.
+fn check_require_debug(attrs: &Attrs, generics: &Generics) -> Result<()> {
+    let require_debug = match attrs.config.require_debug {
+        Some(require_debug) => require_debug,
+        None => return Ok(()),
+    };
+    if generics.type_params().next().is_some() || generics.const_params().next().is_some() {
+        return Err(Error::new(
+            require_debug,
+            "#[thiserror(require_debug)] is not supported on generic types",
+        ));
+    }
+    if attrs.config.debug.is_some() || attrs.config.debug_transparent.is_some() {
+        return Err(Error::new(
+            require_debug,
+            "#[thiserror(require_debug)] is not needed when the derive generates Debug",
+        ));
+    }
+    if attrs.config.no_error_impl.is_some() {
+        return Err(Error::new(
+            require_debug,
+            "#[thiserror(require_debug)] is not needed with #[thiserror(no_error_impl)], which leaves Debug optional",
+        ));
+    }
+    Ok(())
+}
+
+fn check_display_match(attrs: &Attrs, fields: &[Field]) -> Result<()> {
+    let matching = match attrs
+        .display
+        .as_ref()
+        .and_then(|display| display.matching.as_ref())
+    {
+        Some(matching) => matching,
+        None => return Ok(()),
+    };
+    if fields.iter().all(|field| field.member != matching.member) {
+        return Err(Error::new_spanned(
+            &matching.member,
+            "#[error(match = ...)] requires a field of this struct or variant to match on",
+        ));
+    }
+    Ok(())
+}
+
+fn check_static_message(static_messages: Span, attrs: &Attrs) -> Result<()> {
+    let display = match &attrs.display {
+        Some(display) => display,
+        None => {
+            let span = attrs
+                .transparent
+                .map_or(static_messages, |transparent| transparent.span);
+            return Err(Error::new(
+                span,
+                "#[thiserror(static_messages)] requires an #[error(\"...\")] message",
+            ));
+        }
+    };
+    if display.matching.is_some() {
+        return Err(Error::new_spanned(
+            display.original,
+            "#[thiserror(static_messages)] does not support #[error(match = ...)]",
+        ));
+    }
+    if display.static_message().is_none() {
+        return Err(Error::new_spanned(
+            &display.fmt,
+            "#[thiserror(static_messages)] does not support interpolation in error messages",
+        ));
+    }
+    Ok(())
+}
+
+// Backtraces, span traces, and the fields(), localized() and to_report()
+// methods all live in std.
+fn check_no_std(attrs: &Attrs, fields: &[Field]) -> Result<()> {
+    if attrs.config.no_std.is_none() {
+        return Ok(());
+    }
+    if let Some(span) = attrs.config.fields {
+        return Err(Error::new(
+            span,
+            "#[thiserror(fields)] is not supported with #[thiserror(no_std)]",
+        ));
+    }
+    if let Some(span) = attrs.config.report {
+        return Err(Error::new(
+            span,
+            "#[thiserror(report)] is not supported with #[thiserror(no_std)]",
+        ));
+    }
+    if let Some(localize) = &attrs.config.localize {
+        return Err(Error::new_spanned(
+            localize,
+            "#[thiserror(localize)] is not supported with #[thiserror(no_std)]",
+        ));
+    }
+    for field in fields {
+        if let Some(backtrace) = &field.attrs.backtrace {
+            return Err(Error::new_spanned(
+                backtrace.original,
+                "#[backtrace] is not supported with #[thiserror(no_std)]",
+            ));
+        }
+        if field.is_backtrace() {
+            return Err(Error::new_spanned(
+                field.ty,
+                "Backtrace fields are not supported with #[thiserror(no_std)]",
+            ));
+        }
+        if let Some(span_trace) = field.attrs.span_trace {
+            return Err(Error::new_spanned(
+                span_trace,
+                "#[span_trace] is not supported with #[thiserror(no_std)]",
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Comparing sources by message formats them into Strings.
+fn check_partial_eq(attrs: &Attrs) -> Result<()> {
+    let partial_eq = match attrs.config.partial_eq {
+        Some(partial_eq) => partial_eq,
+        None => return Ok(()),
+    };
+    if attrs.config.eq.is_some() {
+        return Err(Error::new(
+            partial_eq,
+            "#[thiserror(partial_eq)] cannot be combined with #[thiserror(eq)]",
+        ));
+    }
+    if attrs.config.partial_eq_message && attrs.config.no_std.is_some() {
+        return Err(Error::new(
+            partial_eq,
+            "#[thiserror(partial_eq = \"message\")] is not supported with #[thiserror(no_std)]; use #[thiserror(partial_eq)] to ignore sources instead",
+        ));
+    }
+    Ok(())
+}
+
+// Cloning shares each source through its Arc, and std's Backtrace cannot be
+// cloned at all.
+fn check_clone(attrs: &Attrs, transparent: bool, fields: &[Field]) -> Result<()> {
+    if attrs.config.clone.is_none() {
+        return Ok(());
+    }
+    let source_field = if transparent {
+        fields.first()
+    } else {
+        prop::source_field(fields)
+    };
+    if let Some(source_field) = source_field {
+        let ty = type_parameter_of_option(source_field.ty).unwrap_or(source_field.ty);
+        if type_parameter_of_arc(ty).is_none() {
+            return Err(Error::new_spanned(
+                source_field.ty,
+                "#[thiserror(clone = \"arc_sources\")] requires each source to be an Arc, like `Arc<io::Error>`",
+            ));
+        }
+    }
+    if let Some(backtrace_field) = fields.iter().find(|field| field.is_backtrace()) {
+        return Err(Error::new_spanned(
+            backtrace_field.ty,
+            "#[thiserror(clone = \"arc_sources\")] does not support Backtrace fields, which cannot be cloned",
+        ));
+    }
+    Ok(())
+}
+
+// The serialized object of #[thiserror(serialize)] has `message`, `code` and
+// `chain` entries of its own, next to the fields marked #[error(serialize)].
+// A #[sensitive] field is redacted from the message, which is not possible for
+// a source whose message is all of the chain.
+fn check_serialize(
+    container: &Attrs,
+    attrs: &Attrs,
+    fields: &[Field],
+    source_field: Option<&Field>,
+) -> Result<()> {
+    if container.config.serialize.is_none() {
+        for field in fields {
+            if let Some(serialize) = field.attrs.serialize {
                 return Err(Error::new_spanned(
-                    transparent.original,
-                    "#[error(transparent)] requires exactly one field",
+                    serialize,
+                    "#[error(serialize)] requires #[thiserror(serialize)] on the struct or enum",
                 ));
             }
-            if let Some(source) = self.fields.iter().find_map(|f| f.attrs.source) {
+            if let Some(sensitive) = field.attrs.sensitive {
                 return Err(Error::new_spanned(
-                    source,
-                    "transparent error struct can't contain #[source]",
+                    sensitive,
+                    "#[sensitive] requires #[thiserror(serialize)] on the struct or enum",
                 ));
             }
         }
-        check_field_attrs(&self.fields)?;
-        for field in &self.fields {
-            field.validate()?;
-        }
-        Ok(())
+        return Ok(());
     }
-}
-
-impl Enum<'_> {
-    fn validate(&self) -> Result<()> {
-        check_non_field_attrs(&self.attrs)?;
-        let has_display = self.has_display();
-        for variant in &self.variants {
-            variant.validate()?;
-            if has_display && variant.attrs.display.is_none() && variant.attrs.transparent.is_none()
-            {
+    if let Some(span) = container.config.no_error_impl {
+        return Err(Error::new(
+            span,
+            "#[thiserror(serialize)] is not supported with #[thiserror(no_error_impl)], as the serialized chain comes from source()",
+        ));
+    }
+    for field in fields {
+        if let Some(sensitive) = field.attrs.sensitive {
+            if field.attrs.serialize.is_some() {
                 return Err(Error::new_spanned(
-                    variant.original,
-                    "missing #[error(\"...\")] display attribute",
+                    sensitive,
+                    "#[sensitive] cannot be combined with #[error(serialize)]",
                 ));
             }
-        }
-        let mut from_types = Set::new();
-        for variant in &self.variants {
-            if let Some(from_field) = variant.from_field() {
-                let repr = from_field.ty.to_token_stream().to_string();
-                if !from_types.insert(repr) {
-                    return Err(Error::new_spanned(
-                        from_field.original,
-                        "cannot derive From because another variant has the same source type",
-                    ));
-                }
-            }
-        }
-        Ok(())
-    }
-}
-
-impl Variant<'_> {
-    fn validate(&self) -> Result<()> {
-        check_non_field_attrs(&self.attrs)?;
-        if self.attrs.transparent.is_some() {
-            if self.fields.len() != 1 {
+            let is_source = attrs.transparent.is_some()
+                || source_field.map_or(false, |source| std::ptr::eq(source, field));
+            if is_source {
                 return Err(Error::new_spanned(
-                    self.original,
-                    "#[error(transparent)] requires exactly one field",
+                    sensitive,
+                    "#[sensitive] is not supported on a source, whose message is serialized as part of the chain",
                 ));
             }
-            if let Some(source) = self.fields.iter().find_map(|f| f.attrs.source) {
+            if attrs.display.is_none() && container.config.static_messages.is_none() {
                 return Err(Error::new_spanned(
-                    source,
-                    "transparent variant can't contain #[source]",
+                    sensitive,
+                    "#[sensitive] requires the message to be given by #[error(\"...\")], so that it can be serialized without the field",
                 ));
             }
         }
-        check_field_attrs(&self.fields)?;
-        for field in &self.fields {
-            field.validate()?;
+        if let (Some(serialize), Member::Named(ident)) = (field.attrs.serialize, &field.member) {
+            let name = ident.unraw().to_string();
+            if name == "message" || name == "code" || name == "chain" {
+                return Err(Error::new_spanned(
+                    serialize,
+                    format!(
+                        "a serialized field cannot be named `{}`, which the serialized object uses for its own entry",
+                        name,
+                    ),
+                ));
+            }
         }
-        Ok(())
     }
+    Ok(())
 }
 
-impl Field<'_> {
-    fn validate(&self) -> Result<()> {
-        if let Some(display) = &self.attrs.display {
-            return Err(Error::new_spanned(
-                display.original,
-                "not expected here; the #[error(...)] attribute belongs on top of a struct or an enum variant",
-            ));
+// Without an Error impl there is no source() to return a source from, nor
+// provide() to hand out a backtrace or other field.
+// #[thiserror(debug)] generates the Debug impl that #[redact] fields are left
+// out of, in place of the one debug_transparent would generate.
+fn check_debug(container: &Attrs, fields: &[Field]) -> Result<()> {
+    let debug = match container.config.debug {
+        Some(debug) => debug,
+        None => {
+            for field in fields {
+                if let Some(redact) = field.attrs.redact {
+                    return Err(Error::new_spanned(
+                        redact,
+                        "#[redact] requires #[thiserror(debug)] on the struct or enum",
+                    ));
+                }
+            }
+            return Ok(());
         }
-        Ok(())
+    };
+    if container.config.debug_transparent.is_some() {
+        return Err(Error::new(
+            debug,
+            "#[thiserror(debug)] cannot be combined with #[thiserror(debug_transparent)]",
+        ));
     }
+    Ok(())
 }
 
-fn check_non_field_attrs(attrs: &Attrs) -> Result<()> {
-    if let Some(from) = &attrs.from {
-        return Err(Error::new_spanned(
-            from,
-            "not expected here; the #[from] attribute belongs on a specific field",
-        ));
-    }
-    if let Some(source) = &attrs.source {
-        return Err(Error::new_spanned(
-            source,
-            "not expected here; the #[source] attribute belongs on a specific field",
-        ));
+fn check_no_error_impl(attrs: &Attrs, fields: &[Field]) -> Result<()> {
+    if attrs.config.no_error_impl.is_none() {
+        return Ok(());
     }
-    if let Some(backtrace) = &attrs.backtrace {
-        return Err(Error::new_spanned(
-            backtrace,
-            "not expected here; the #[backtrace] attribute belongs on a specific field",
+    if let Some(span) = attrs.config.legacy_description {
+        return Err(Error::new(
+            span,
+            "#[thiserror(legacy_description)] is not supported with #[thiserror(no_error_impl)]",
         ));
     }
-    if let Some(display) = &attrs.display {
-        if attrs.transparent.is_some() {
+    for field in fields {
+        if let Some(from) = &field.attrs.from {
             return Err(Error::new_spanned(
-                display.original,
-                "cannot have both #[error(transparent)] and a display attribute",
+                from.original,
+                "#[from] is not supported with #[thiserror(no_error_impl)]",
+            ));
+        }
+        if let Some(source) = &field.attrs.source {
+            return Err(Error::new_spanned(
+                source.original,
+                "#[source] is not supported with #[thiserror(no_error_impl)]",
+            ));
+        }
+        if let Some(backtrace) = &field.attrs.backtrace {
+            return Err(Error::new_spanned(
+                backtrace.original,
+                "#[backtrace] is not supported with #[thiserror(no_error_impl)]",
+            ));
+        }
+        if let Some(provide) = &field.attrs.provide {
+            return Err(Error::new_spanned(
+                provide.original,
+                "#[provide] is not supported with #[thiserror(no_error_impl)]",
             ));
         }
     }
@@ -140,58 +1600,141 @@ fn check_field_attrs(fields: &[Field]) -> Result<()> {
     let mut source_field = None;
     let mut backtrace_field = None;
     let mut has_backtrace = false;
+    let mut span_trace_field = None;
+    let mut provided_types = Set::new();
     for field in fields {
-        if let Some(from) = field.attrs.from {
+        if let Some(from) = &field.attrs.from {
+            if from.by_ref.is_some() && !from.kinds.is_empty() {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "#[from(ref)] cannot be combined with kind dispatch",
+                ));
+            }
+            if from.into.is_some() && (from.by_ref.is_some() || !from.kinds.is_empty()) {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "#[from(into = ...)] cannot be combined with #[from(ref)] or kind dispatch",
+                ));
+            }
+            if !from.via.is_empty() && (from.into.is_some() || !from.kinds.is_empty()) {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "#[from(via = ...)] cannot be combined with #[from(into = ...)] or kind dispatch",
+                ));
+            }
+            if from.ctor.is_some()
+                && (from.by_ref.is_some() || !from.kinds.is_empty() || from.into.is_some())
+            {
+                return Err(Error::new_spanned(
+                    from.original,
+                    "#[from(ctor = ...)] cannot be combined with #[from(ref)], #[from(into = ...)] or kind dispatch",
+                ));
+            }
             if from_field.is_some() {
-                return Err(Error::new_spanned(from, "duplicate #[from] attribute"));
+                return Err(Error::new_spanned(
+                    from.original,
+                    "duplicate #[from] attribute",
+                ));
             }
             from_field = Some(field);
         }
-        if let Some(source) = field.attrs.source {
+        if let Some(source) = &field.attrs.source {
             if source_field.is_some() {
-                return Err(Error::new_spanned(source, "duplicate #[source] attribute"));
+                return Err(Error::new_spanned(
+                    source.original,
+                    "duplicate #[source] attribute",
+                ));
             }
             source_field = Some(field);
         }
-        if let Some(backtrace) = field.attrs.backtrace {
+        if let Some(backtrace) = &field.attrs.backtrace {
             if backtrace_field.is_some() {
                 return Err(Error::new_spanned(
-                    backtrace,
+                    backtrace.original,
                     "duplicate #[backtrace] attribute",
                 ));
             }
             backtrace_field = Some(field);
             has_backtrace = true;
         }
+        if let Some(span_trace) = field.attrs.span_trace {
+            if span_trace_field.is_some() {
+                return Err(Error::new_spanned(
+                    span_trace,
+                    "duplicate #[span_trace] attribute",
+                ));
+            }
+            span_trace_field = Some(field);
+        }
         if let Some(transparent) = field.attrs.transparent {
             return Err(Error::new_spanned(
                 transparent.original,
                 "#[error(transparent)] needs to go outside the enum or struct, not on an individual field",
             ));
         }
+        if field.attrs.provide.is_some() || field.attrs.span_trace.is_some() {
+            let ty = type_parameter_of_option(field.ty).unwrap_or(field.ty);
+            if !provided_types.insert(ty.to_token_stream().to_string()) {
+                return Err(Error::new_spanned(
+                    field.ty,
+                    "another field already provides this type; only one of them could be retrieved through request_ref",
+                ));
+            }
+        }
         has_backtrace |= field.is_backtrace();
     }
     if let (Some(from_field), Some(source_field)) = (from_field, source_field) {
         if !same_member(from_field, source_field) {
             return Err(Error::new_spanned(
-                from_field.attrs.from,
+                from_field.attrs.from.as_ref().map(|from| from.original),
                 "#[from] is only supported on the source field, not any other field",
             ));
         }
     }
-    if let Some(from_field) = from_field {
+    if let Some(backtrace) = backtrace_field.and_then(|field| field.attrs.backtrace.as_ref()) {
+        if backtrace.from_source {
+            match from_field.and_then(|field| field.attrs.from.as_ref()) {
+                None => {
+                    return Err(Error::new_spanned(
+                        backtrace.original,
+                        "#[backtrace(from_source)] requires a #[from] field to take the backtrace from",
+                    ));
+                }
+                Some(from) if from.ctor.is_some() => {
+                    return Err(Error::new_spanned(
+                        backtrace.original,
+                        "#[backtrace(from_source)] cannot be combined with #[from(ctor = ...)], which builds the error itself",
+                    ));
+                }
+                Some(_) => {}
+            }
+        }
+    }
+    // A #[from(ctor = ...)] builds the whole error itself, other fields
+    // included.
+    let from_fills_fields = from_field.map_or(false, |from_field| {
+        from_field.attrs.from.as_ref().unwrap().ctor.is_none()
+    });
+    if let Some(from_field) = from_field.filter(|_| from_fills_fields) {
         let max_expected_fields = match backtrace_field {
             Some(backtrace_field) => 1 + !same_member(from_field, backtrace_field) as usize,
             None => 1 + has_backtrace as usize,
-        };
+        } + span_trace_field.is_some() as usize
+            + fields
+                .iter()
+                .filter(|field| type_is_phantom_data(field.ty))
+                .count();
         if fields.len() > max_expected_fields {
             return Err(Error::new_spanned(
-                from_field.attrs.from,
-                "deriving From requires no fields other than source and backtrace",
+                from_field.attrs.from.as_ref().map(|from| from.original),
+                "deriving From requires no fields other than source, backtrace and span trace",
             ));
         }
     }
-    if let Some(source_field) = source_field.or(from_field) {
+    let source_field = source_field
+        .or(from_field)
+        .or_else(|| prop::source_field(fields));
+    if let Some(source_field) = source_field {
         if contains_non_static_lifetime(source_field.ty) {
             return Err(Error::new_spanned(
                 &source_field.original.ty,
@@ -202,6 +1745,338 @@ fn check_field_attrs(fields: &[Field]) -> Result<()> {
     Ok(())
 }
 
+// A variant of an enum with fields cannot be cast to its discriminant, so its
+// code has to be written out, either as the discriminant or as a fallback.
+fn check_discriminant_code(input: &Enum) -> Result<()> {
+    if input.attrs.config.repr_c_codes.is_some() {
+        check_repr_c_codes(input)?;
+    }
+    if input.attrs.config.codes.is_some() {
+        return check_codes(input);
+    }
+    if input.attrs.config.discriminant_code.is_none() {
+        if input.attrs.config.repr_c_codes.is_some() {
+            return Ok(());
+        }
+        if let Some(code) = input
+            .variants
+            .iter()
+            .find_map(|variant| variant.attrs.code.as_ref())
+        {
+            return Err(Error::new_spanned(
+                code.original,
+                "#[error(code = ...)] requires #[thiserror(discriminant_code)], #[thiserror(codes)] or #[thiserror(repr_c_codes)] on the enum",
+            ));
+        }
+        return Ok(());
+    }
+    if input.is_fieldless() {
+        return Ok(());
+    }
+    for variant in &input.variants {
+        if variant.attrs.code.is_none() && variant.original.discriminant.is_none() {
+            return Err(Error::new_spanned(
+                &variant.original.ident,
+                "#[thiserror(discriminant_code)] requires an explicit discriminant or #[error(code = ...)] on every variant of an enum with fields",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn check_constructors(input: &Enum) -> Result<()> {
+    if input.attrs.config.constructors.is_none() {
+        if let Some(constructor) = input
+            .variants
+            .iter()
+            .find_map(|variant| variant.attrs.constructor.as_ref())
+        {
+            return Err(Error::new_spanned(
+                constructor.original,
+                "#[error(constructor = ...)] requires #[thiserror(constructors)] on the enum",
+            ));
+        }
+        return Ok(());
+    }
+    for variant in &input.variants {
+        if variant.constructor_ident().is_none() {
+            return Err(Error::new_spanned(
+                &variant.original.ident,
+                "the name of this variant in snake_case is a keyword; give its constructor another name with #[error(constructor = ...)]",
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Variants like `Io` and `IO` would both get an `is_io` method.
+fn check_variant_methods(input: &Enum) -> Result<()> {
+    if input.attrs.config.predicates.is_some() {
+        let variants = input.variants.iter();
+        check_method_collisions("predicates", variants, |variant| variant.predicate_ident())?;
+    }
+    if input.attrs.config.accessors.is_some() {
+        // Unit variants get no accessors.
+        let variants = input
+            .variants
+            .iter()
+            .filter(|variant| !variant.fields.is_empty());
+        check_method_collisions("accessors", variants, |variant| variant.accessor_idents().0)?;
+    }
+    if input.attrs.config.inner_accessors.is_some() {
+        let variants = input
+            .variants
+            .iter()
+            .filter(|variant| variant.attrs.transparent.is_some());
+        check_method_collisions("inner_accessors", variants, |variant| {
+            variant.inner_accessor_idents().0
+        })?;
+    }
+    Ok(())
+}
+
+fn check_method_collisions<'a, 'b: 'a>(
+    config: &str,
+    variants: impl Iterator<Item = &'a Variant<'b>>,
+    method: fn(&Variant) -> Ident,
+) -> Result<()> {
+    let mut seen = Map::new();
+    for variant in variants {
+        let method = method(variant);
+        if let Some(other) = seen.insert(method.to_string(), &variant.ident) {
+            return Err(Error::new_spanned(
+                &variant.ident,
+                format!(
+                    "#[thiserror({})] would generate `{}` for both `{}` and `{}`",
+                    config, method, other, variant.ident,
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Codes under #[thiserror(codes)] are meant to stay fixed once published, so
+// each has to be written out as a literal that can be checked for collisions
+// here.
+fn check_codes(input: &Enum) -> Result<()> {
+    if let Some(discriminant_code) = input.attrs.config.discriminant_code {
+        return Err(Error::new(
+            discriminant_code,
+            "#[thiserror(discriminant_code)] cannot be combined with #[thiserror(codes)], which generates its own code()",
+        ));
+    }
+    let mut seen = Map::new();
+    for variant in &input.variants {
+        let code = match &variant.attrs.code {
+            Some(code) => code,
+            None => {
+                return Err(Error::new_spanned(
+                    &variant.original.ident,
+                    "#[thiserror(codes)] requires #[error(code = ...)] on every variant",
+                ));
+            }
+        };
+        let value = match &code.expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(lit), ..
+            }) if lit.suffix().is_empty() || lit.suffix() == "u32" => lit.base10_parse::<u32>()?,
+            expr => {
+                return Err(Error::new_spanned(
+                    expr,
+                    "#[thiserror(codes)] requires each code to be an integer literal",
+                ));
+            }
+        };
+        if let Some(other) = seen.insert(value, &variant.ident) {
+            return Err(Error::new_spanned(
+                code.original,
+                format!(
+                    "code {} is used by both `{}` and `{}`",
+                    value, other, variant.ident,
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+// Codes handed across FFI are C ints, and from_ffi_code() matches on them, so
+// each has to be a literal that fits in an i32 and is not shared.
+fn check_repr_c_codes(input: &Enum) -> Result<()> {
+    let mut seen = Map::new();
+    for variant in &input.variants {
+        let code = match &variant.attrs.code {
+            Some(code) => code,
+            None => {
+                return Err(Error::new_spanned(
+                    &variant.original.ident,
+                    "#[thiserror(repr_c_codes)] requires #[error(code = ...)] on every variant",
+                ));
+            }
+        };
+        let value = match ffi_code_value(&code.expr) {
+            Some(value) => value,
+            None => {
+                return Err(Error::new_spanned(
+                    &code.expr,
+                    "#[thiserror(repr_c_codes)] requires each code to be an integer literal that fits in an i32",
+                ));
+            }
+        };
+        if let Some(other) = seen.insert(value, &variant.ident) {
+            return Err(Error::new_spanned(
+                code.original,
+                format!(
+                    "code {} is used by both `{}` and `{}`",
+                    value, other, variant.ident,
+                ),
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn ffi_code_value(expr: &Expr) -> Option<i32> {
+    match expr {
+        Expr::Lit(ExprLit {
+            lit: Lit::Int(lit), ..
+        }) if lit.suffix().is_empty() || lit.suffix() == "i32" => lit.base10_parse::<i32>().ok(),
+        Expr::Unary(ExprUnary {
+            op: UnOp::Neg(_),
+            expr,
+            ..
+        }) => match &**expr {
+            Expr::Lit(ExprLit {
+                lit: Lit::Int(lit), ..
+            }) if lit.suffix().is_empty() || lit.suffix() == "i32" => {
+                format!("-{}", lit.base10_digits()).parse().ok()
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn check_kind(input: &Enum) -> Result<()> {
+    if input.attrs.config.kind.is_some() {
+        return Ok(());
+    }
+    if let Some(kind) = input
+        .variants
+        .iter()
+        .find_map(|variant| variant.attrs.kind.as_ref())
+    {
+        return Err(Error::new_spanned(
+            kind.original,
+            "#[error(kind = ...)] requires #[thiserror(kind)] on the enum",
+        ));
+    }
+    Ok(())
+}
+
+// The names of variants are what variant_name() returns and what
+// #[thiserror(deserialize)] matches, so they have to tell the variants apart.
+fn check_variant_names(input: &Enum) -> Result<()> {
+    if input.attrs.config.deserialize.is_some() {
+        if let Some(variant) = input
+            .variants
+            .iter()
+            .find(|variant| !variant.fields.is_empty())
+        {
+            return Err(Error::new_spanned(
+                &variant.original.ident,
+                "#[thiserror(deserialize)] is only supported on enums whose variants have no fields",
+            ));
+        }
+    }
+    if input.attrs.config.variant_names.is_none() && input.attrs.config.deserialize.is_none() {
+        if let Some(name) = input
+            .variants
+            .iter()
+            .find_map(|variant| variant.attrs.name.as_ref())
+        {
+            return Err(Error::new_spanned(
+                name.original,
+                "#[error(name = ...)] requires #[thiserror(variant_names)] or #[thiserror(deserialize)] on the enum",
+            ));
+        }
+        return Ok(());
+    }
+    let mut seen = Map::new();
+    for variant in &input.variants {
+        let name = variant.name();
+        if let Some(previous) = seen.insert(name.clone(), &variant.ident) {
+            let msg = format!(
+                "variants `{}` and `{}` are both named {:?}",
+                previous.unraw(),
+                variant.ident.unraw(),
+                name,
+            );
+            return Err(match &variant.attrs.name {
+                Some(name) => Error::new_spanned(&name.value, msg),
+                None => Error::new_spanned(&variant.ident, msg),
+            });
+        }
+    }
+    Ok(())
+}
+
+// The context selectors would need the parameters of the error in order to
+// name it, and nothing in a selector constrains them.
+fn check_context(input: &Enum) -> Result<()> {
+    if let Some(context) = input.attrs.config.context {
+        if !input.generics.params.is_empty() {
+            return Err(Error::new(
+                context,
+                "#[thiserror(context)] is not supported on generic enums",
+            ));
+        }
+    }
+    Ok(())
+}
+
+// localized() hands the localizer the code of the error to pick the message.
+fn check_localize(input: &Enum) -> Result<()> {
+    if let Some(localize) = &input.attrs.config.localize {
+        if input.attrs.config.codes.is_none() && input.attrs.config.discriminant_code.is_none() {
+            return Err(Error::new_spanned(
+                localize,
+                "#[thiserror(localize)] requires #[thiserror(codes)] or #[thiserror(discriminant_code)], whose code() selects the message",
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn is_bare_type_param(ty: &Type, generics: &Generics) -> bool {
+    let ident = match ty {
+        Type::Path(ty) if ty.qself.is_none() => match ty.path.get_ident() {
+            Some(ident) => ident,
+            None => return false,
+        },
+        _ => return false,
+    };
+    generics.type_params().any(|param| param.ident == *ident)
+}
+
+fn type_is_string(ty: &Type) -> bool {
+    let path = match ty {
+        Type::Path(ty) if ty.qself.is_none() => &ty.path,
+        _ => return false,
+    };
+    let last = path.segments.last().unwrap();
+    last.ident == "String" && last.arguments.is_empty()
+}
+
+fn is_unsized(ty: &Type) -> bool {
+    match ty {
+        Type::Slice(_) | Type::TraitObject(_) => true,
+        Type::Path(ty) => ty.qself.is_none() && ty.path.is_ident("str"),
+        _ => false,
+    }
+}
+
 fn same_member(one: &Field, two: &Field) -> bool {
     match (&one.member, &two.member) {
         (Member::Named(one), Member::Named(two)) => one == two,
@@ -228,10 +2103,19 @@ fn contains_non_static_lifetime(ty: &Type) -> bool {
             }
             false
         }
-        Type::Reference(ty) => ty
-            .lifetime
-            .as_ref()
-            .map_or(false, |lifetime| lifetime.ident != "static"),
+        Type::Reference(ty) => {
+            ty.lifetime
+                .as_ref()
+                .map_or(false, |lifetime| lifetime.ident != "static")
+                || contains_non_static_lifetime(&ty.elem)
+        }
+        Type::TraitObject(ty) => ty.bounds.iter().any(|bound| match bound {
+            TypeParamBound::Lifetime(lifetime) => lifetime.ident != "static",
+            TypeParamBound::Trait(_) => false,
+        }),
+        Type::Tuple(ty) => ty.elems.iter().any(contains_non_static_lifetime),
+        Type::Paren(ty) => contains_non_static_lifetime(&ty.elem),
+        Type::Group(ty) => contains_non_static_lifetime(&ty.elem),
         _ => false, // maybe implement later if there are common other cases
     }
 }
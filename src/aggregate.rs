@@ -0,0 +1,49 @@
+use std::fmt::{self, Display};
+
+// Display of an #[aggregate] field: a count followed by the message of each
+// item, either on its own indented line or joined by a separator.
+pub struct Aggregate<'a, T: 'a> {
+    items: &'a [T],
+    separator: Option<&'static str>,
+    max: Option<usize>,
+}
+
+impl<'a, T> Aggregate<'a, T> {
+    pub fn new(items: &'a [T], separator: Option<&'static str>, max: Option<usize>) -> Self {
+        Aggregate {
+            items,
+            separator,
+            max,
+        }
+    }
+
+    fn write_separator(&self, formatter: &mut fmt::Formatter, first: bool) -> fmt::Result {
+        match self.separator {
+            None => formatter.write_str("\n    "),
+            Some(_) if first => formatter.write_str(" "),
+            Some(separator) => formatter.write_str(separator),
+        }
+    }
+}
+
+impl<'a, T: Display> Display for Aggregate<'a, T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let count = self.items.len();
+        let plural = if count == 1 { "" } else { "s" };
+        write!(formatter, "{} error{}", count, plural)?;
+        if count == 0 {
+            return Ok(());
+        }
+        formatter.write_str(":")?;
+        let shown = self.max.map_or(count, |max| max.min(count));
+        for (i, item) in self.items[..shown].iter().enumerate() {
+            self.write_separator(formatter, i == 0)?;
+            write!(formatter, "{}", item)?;
+        }
+        if shown < count {
+            self.write_separator(formatter, shown == 0)?;
+            write!(formatter, "\u{2026}and {} more", count - shown)?;
+        }
+        Ok(())
+    }
+}
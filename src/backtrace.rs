@@ -0,0 +1,14 @@
+use std::error::Error;
+
+// Whether the source handed to a From impl already provides a backtrace, for
+// #[backtrace(from_source)]. This is only known through the generic member
+// access API; without it the backtrace is captured as usual.
+#[cfg(error_generic_member_access)]
+pub fn provides_backtrace(source: &dyn Error) -> bool {
+    std::error::request_ref::<std::backtrace::Backtrace>(source).is_some()
+}
+
+#[cfg(not(error_generic_member_access))]
+pub fn provides_backtrace(_source: &dyn Error) -> bool {
+    false
+}
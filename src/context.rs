@@ -0,0 +1,31 @@
+/// Context selector generated by `#[thiserror(context)]`, which builds a
+/// variant of an error from its source error and the rest of its fields.
+///
+/// The extension trait generated alongside, named after the error as in
+/// `ConfigErrorContext`, gives `Result` a `context` method taking any selector
+/// of that error whose source is the error of the `Result`.
+///
+/// ```
+/// use std::io;
+/// use std::path::PathBuf;
+/// use thiserror::Error;
+///
+/// #[derive(Error, Debug)]
+/// #[thiserror(context)]
+/// pub enum ConfigError {
+///     #[error("failed to read {path:?}")]
+///     ReadConfig { path: PathBuf, source: io::Error },
+/// }
+///
+/// fn load(path: &str) -> Result<String, ConfigError> {
+///     std::fs::read_to_string(path).context(ReadConfigCtx { path })
+/// }
+/// #
+/// # assert!(load("/nonexistent").is_err());
+/// ```
+pub trait ContextSelector<E> {
+    /// The source error of the variant.
+    type Source;
+
+    fn into_error(self, source: Self::Source) -> E;
+}
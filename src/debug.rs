@@ -0,0 +1,26 @@
+use std::fmt::{self, Debug};
+
+// Checked by the derive so that an error type missing #[derive(Debug)] gets a
+// diagnostic saying so on compilers that support custom ones.
+#[cfg_attr(
+    not(thiserror_no_diagnostic_namespace),
+    diagnostic::on_unimplemented(
+        message = "`{Self}` doesn't implement `Debug`, which the Error trait requires",
+        label = "Error requires Debug",
+        note = "add #[derive(Debug)] to `{Self}`, or implement Debug for it by hand"
+    )
+)]
+pub trait RequireDebug {}
+
+impl<T: Debug + ?Sized> RequireDebug for T {}
+
+pub fn require_debug<T: RequireDebug + ?Sized>() {}
+
+// Stands in for a #[redact] field in the Debug impl of #[thiserror(debug)].
+pub struct RedactedDebug;
+
+impl Debug for RedactedDebug {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("***")
+    }
+}
@@ -1,4 +1,9 @@
-use std::fmt::Display;
+#[cfg(feature = "std")]
+use std::borrow::Cow;
+#[cfg(feature = "std")]
+use std::ffi::{OsStr, OsString};
+use std::fmt::{self, Display};
+#[cfg(feature = "std")]
 use std::path::{self, Path, PathBuf};
 
 pub trait DisplayAsDisplay {
@@ -11,18 +16,75 @@ impl<T: Display> DisplayAsDisplay for &T {
     }
 }
 
+pub trait JoinAsDisplay: Sized {
+    fn join_display(self) -> Join<Self>;
+}
+
+impl<I> JoinAsDisplay for I
+where
+    I: Iterator + Clone,
+    I::Item: Display,
+{
+    fn join_display(self) -> Join<Self> {
+        Join(self)
+    }
+}
+
+// The items of `{names:join}` with ", " between them, written one at a time so
+// that no String is needed.
+pub struct Join<I>(I);
+
+impl<I> Display for Join<I>
+where
+    I: Iterator + Clone,
+    I::Item: Display,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        for (i, item) in self.0.clone().enumerate() {
+            if i > 0 {
+                formatter.write_str(", ")?;
+            }
+            item.fmt(formatter)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
 pub trait PathAsDisplay {
     fn as_display(&self) -> path::Display<'_>;
 }
 
+#[cfg(feature = "std")]
 impl PathAsDisplay for Path {
     fn as_display(&self) -> path::Display<'_> {
         self.display()
     }
 }
 
+#[cfg(feature = "std")]
 impl PathAsDisplay for PathBuf {
     fn as_display(&self) -> path::Display<'_> {
         self.display()
     }
 }
+
+// Not valid UTF-8 in general, so shown lossily like path::Display does.
+#[cfg(feature = "std")]
+pub trait OsStrAsDisplay {
+    fn as_display(&self) -> Cow<'_, str>;
+}
+
+#[cfg(feature = "std")]
+impl OsStrAsDisplay for OsStr {
+    fn as_display(&self) -> Cow<'_, str> {
+        self.to_string_lossy()
+    }
+}
+
+#[cfg(feature = "std")]
+impl OsStrAsDisplay for OsString {
+    fn as_display(&self) -> Cow<'_, str> {
+        self.to_string_lossy()
+    }
+}
@@ -0,0 +1,34 @@
+/// An error that knows the status a process should exit with when it fails
+/// with that error.
+///
+/// Implemented by `#[derive(Error)]` for errors with an
+/// `#[error(exit_code = N)]` attribute. Transparent variants of such an enum
+/// forward to the error they wrap if it implements this trait.
+pub trait ErrorExitCode {
+    fn exit_code(&self) -> u8;
+}
+
+// Wraps the field of a transparent variant for exit_code(). Method resolution
+// on `(&ExitCodeValue(x))` picks ExitCodeDelegate if the field implements
+// ErrorExitCode, and otherwise autorefs once more to reach ExitCodeDefault.
+pub struct ExitCodeValue<'a, T: ?Sized>(pub &'a T);
+
+pub trait ExitCodeDelegate {
+    fn inner_exit_code(&self) -> Option<u8>;
+}
+
+impl<'a, T: ErrorExitCode + ?Sized> ExitCodeDelegate for ExitCodeValue<'a, T> {
+    fn inner_exit_code(&self) -> Option<u8> {
+        Some(self.0.exit_code())
+    }
+}
+
+pub trait ExitCodeDefault {
+    fn inner_exit_code(&self) -> Option<u8>;
+}
+
+impl<'a, T: ?Sized> ExitCodeDefault for &ExitCodeValue<'a, T> {
+    fn inner_exit_code(&self) -> Option<u8> {
+        None
+    }
+}
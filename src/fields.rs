@@ -0,0 +1,26 @@
+use std::fmt::{Debug, Display};
+
+// Wraps a field for fields(). Method resolution on `(&FieldValue(x))` picks
+// FieldDisplay if the field implements Display, and otherwise autorefs once
+// more to reach FieldDebug.
+pub struct FieldValue<'a, T: ?Sized>(pub &'a T);
+
+pub trait FieldDisplay {
+    fn field_string(&self) -> String;
+}
+
+impl<'a, T: Display + ?Sized> FieldDisplay for FieldValue<'a, T> {
+    fn field_string(&self) -> String {
+        self.0.to_string()
+    }
+}
+
+pub trait FieldDebug {
+    fn field_string(&self) -> String;
+}
+
+impl<'a, T: Debug + ?Sized> FieldDebug for &FieldValue<'a, T> {
+    fn field_string(&self) -> String {
+        format!("{:?}", self.0)
+    }
+}
@@ -0,0 +1,17 @@
+use defmt::Format;
+
+// Checked by #[thiserror(defmt)] for every interpolated field, so that a field
+// without a Format impl is reported at the field.
+#[cfg_attr(
+    not(thiserror_no_diagnostic_namespace),
+    diagnostic::on_unimplemented(
+        message = "`{Self}` doesn't implement `defmt::Format`",
+        label = "field interpolated into the #[thiserror(defmt)] message",
+        note = "implement or derive defmt::Format for `{Self}`, or leave this field out of the message"
+    )
+)]
+pub trait RequireFormat {}
+
+impl<T: Format + ?Sized> RequireFormat for T {}
+
+pub fn require_format<T: RequireFormat + ?Sized>(_: &T) {}
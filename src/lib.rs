@@ -44,9 +44,25 @@
 //!   by hand, and switching from handwritten impls to thiserror or vice versa
 //!   is not a breaking change.
 //!
+//!   The exceptions are a few opt-in attributes whose generated code names a
+//!   type or trait of thiserror, which then appears in your API and ties it to
+//!   the major version of thiserror:
+//!
+//!   - `#[thiserror(context)]` implements `thiserror::ContextSelector` for the
+//!     generated context selectors.
+//!   - `#[error(exit_code = ...)]` implements `thiserror::ErrorExitCode`.
+//!   - `#[thiserror(report)]` generates `to_report()`, which returns a
+//!     `thiserror::Report`.
+//!   - The `source()` of a `#[source(wrap)]` field is a `thiserror::Wrapped`,
+//!     which callers may downcast to.
+//!
 //! - Errors may be enums, structs with named fields, tuple structs, or unit
 //!   structs.
 //!
+//!   An enum with no variants, such as the error type of an infallible
+//!   implementation, needs no `#[error(...)]` message. Its `Display` impl is an
+//!   empty `match *self {}`.
+//!
 //! - A `Display` impl is generated for your error if you provide
 //!   `#[error("...")]` messages on the struct or each variant of your enum, as
 //!   shown above in the example.
@@ -57,6 +73,10 @@
 //!     - `#[error("{0}")]`&ensp;⟶&ensp;`write!("{}", self.0)`
 //!     - `#[error("{var:?}")]`&ensp;⟶&ensp;`write!("{:?}", self.var)`
 //!     - `#[error("{0:?}")]`&ensp;⟶&ensp;`write!("{:?}", self.0)`
+//!     - `#[error("{var.field}")]`&ensp;⟶&ensp;`write!("{}", self.var.field)`
+//!     - `#[error("{var.len()}")]`&ensp;⟶&ensp;`write!("{}", self.var.len())`
+//!     - `#[error("{self.describe()}")]`&ensp;⟶&ensp;`write!("{}", Self::describe(self))`
+//!     - `#[error("{var:join}")]`&ensp;⟶&ensp;each item of `self.var.iter()` in turn, separated by `", "`
 //!
 //!   These shorthands can be used together with any additional format args,
 //!   which may be arbitrary expressions. For example:
@@ -98,6 +118,79 @@
 //!   }
 //!   ```
 //!
+//!   Named arguments take the same expressions, so a message can show a value
+//!   computed from the fields, such as `pct = .done * 100 / .total` or
+//!   `secs = self.started.elapsed().as_secs()`.
+//!
+//!   The arguments are evaluated inside the `Display` impl of the error, so
+//!   `Self` names the error type there, and `#[error("max is {}", Self::MAX)]`
+//!   shows one of its associated constants.
+//!
+//!   Const generic parameters are in scope as well, so `{N}` in the message of a
+//!   `struct LengthError<const N: usize>` interpolates `N`, and the additional
+//!   arguments can use it like any other constant. A field may not have the same
+//!   name as a const parameter.
+//!
+//!   When the message depends on the value of a field, such as a sub-kind enum,
+//!   `#[error(match = .field, ...)]` picks one by matching on it. Each arm is a
+//!   pattern followed by `=>` and a message, and the arms must be exhaustive.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   # #[derive(Debug)]
+//!   # pub enum Access {
+//!   #     Read,
+//!   #     Write,
+//!   # }
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum Error {
+//!       #[error(match = .access, Access::Read => "cannot read {path}", Access::Write => "cannot write {path}")]
+//!       Denied { access: Access, path: String },
+//!   }
+//!   ```
+//!
+//!   A second message, `#[error(alt = "...")]`, is written instead when the error
+//!   is formatted with the alternate flag, as in `{:#}`. It takes the same
+//!   interpolations and arguments as the main message, so it can spell out details
+//!   over several lines while `{}` stays on one.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error("failed to parse {path}")]
+//!   #[error(alt = "failed to parse {path}\n  line {line}: {reason}")]
+//!   pub struct ParseError {
+//!       path: String,
+//!       line: usize,
+//!       reason: String,
+//!   }
+//!   ```
+//!
+//!   An error whose message is just the string in its only field, such as a
+//!   `&'static str`, `String`, `Box<str>` or `Cow<'static, str>`, can use
+//!   `#[error(transparent_str)]`. Display then writes the string with
+//!   `Formatter::write_str`, which skips parsing a format string and ignores width
+//!   and padding flags. The field can be any type that implements `AsRef<str>`.
+//!   Interpolating the same field with `#[error("{message}")]` does not allocate
+//!   either.
+//!
+//!   ```rust
+//!   # use std::borrow::Cow;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(transparent_str)]
+//!   pub struct RejectedError {
+//!       message: Cow<'static, str>,
+//!   }
+//!   #
+//!   # let error = RejectedError { message: Cow::Borrowed("over quota") };
+//!   # assert_eq!(error.to_string(), "over quota");
+//!   ```
+//!
 //! - A `From` impl is generated for each variant containing a `#[from]`
 //!   attribute.
 //!
@@ -118,6 +211,91 @@
 //!   # };
 //!   ```
 //!
+//!   `PhantomData` fields are also allowed and are filled in with `PhantomData`,
+//!   which lets a struct that uses its lifetime only through a marker derive
+//!   `From`.
+//!
+//!   Writing `#[from(ref)]` instead additionally generates `From<&E>` which
+//!   clones the source. For source types that are not `Clone`, such as
+//!   `io::Error`, use `#[from(ref, with = path)]` to name a `fn(&E) -> E` that
+//!   rebuilds the value.
+//!
+//!   Several variants may share an `io::Error` source by routing on its
+//!   `ErrorKind` with `#[from(kind = NotFound)]` or `#[from(kind = A | B)]`.
+//!   Kinds that are not listed go to the variant with a plain `#[from]` of the
+//!   same type, which is required.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum FileError {
+//!       #[error("not found")]
+//!       NotFound(#[from(kind = NotFound)] io::Error),
+//!       #[error("permission denied")]
+//!       Denied(#[from(kind = PermissionDenied)] io::Error),
+//!       #[error("io error")]
+//!       Io(#[from] io::Error),
+//!   }
+//!   ```
+//!
+//!   A `#[from]` field may also be a bare type parameter, as in `Inner(#[from]
+//!   E)`, generating `impl<E> From<E>`. Such an impl overlaps with every other
+//!   conversion into the same type, so it must be the only `#[from]` in the enum.
+//!
+//!   `#[from(into = String)]` instead generates `impl<T: Into<String>>
+//!   From<T>`, so callers can pass anything convertible into the field, like a
+//!   `&str`. Such a field is not a source unless it is also marked `#[source]`.
+//!   The blanket impl can conflict with the other `From` impls of the error,
+//!   including those for foreign types, so only one variant may use it.
+//!
+//!   A nested error can be converted from the errors it wraps in turn with
+//!   `#[from(via = DiskError | QuotaError)] StorageError`, which generates `From`
+//!   impls for each listed type that first convert it into the field's type. This
+//!   lets `?` lift a low-level error straight into the outer error.
+//!
+//!   Adding `try_into`, as in `#[from(try_into)]`, also generates the reverse
+//!   `impl TryFrom<MyError> for E`, which hands back the source for that variant
+//!   and returns the original error unchanged for any other variant.
+//!
+//!   A variant or struct with fields besides the source can still be converted
+//!   from it with `#[from(ctor = |source| Self::Parse { source, line: 0 })]`. The
+//!   generated `From` impl hands the source to the closure, or to a function
+//!   written as a path, which builds the whole error and fills in the other
+//!   fields, backtrace included.
+//!
+//!   A `Vec`, array or slice of errors can be collected into one error by marking
+//!   the field `#[aggregate]`. The field is converted from like `#[from]`, its
+//!   first element is the `source()`, and unless the variant has its own
+//!   `#[error(...)]` message, Display prints the number of errors followed by
+//!   each message on its own indented line. `#[aggregate(separator = "; ", max =
+//!   10)]` joins the messages on one line instead and stops after 10 of them
+//!   with "…and N more".
+//!
+//! - A `TryFrom` impl is generated for each variant with a `#[try_from(T, with =
+//!   path)]` attribute, for conversions that only apply to some values of `T`.
+//!   The function is called as `fn(T) -> Result<Fields, T>` where `Fields` is the
+//!   variant's single field, a tuple of its fields in declaration order, or `()`.
+//!
+//!   ```rust
+//!   # use std::convert::TryFrom;
+//!   # use thiserror::Error;
+//!   #
+//!   # pub struct StatusCode(u16);
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum ApiError {
+//!       #[error("rate limited")]
+//!       #[try_from(StatusCode, with = rate_limited)]
+//!       RateLimited,
+//!   }
+//!
+//!   fn rate_limited(status: StatusCode) -> Result<(), StatusCode> {
+//!       if status.0 == 429 { Ok(()) } else { Err(status) }
+//!   }
+//!   ```
+//!
 //! - The Error trait's `source()` method is implemented to return whichever
 //!   field has a `#[source]` attribute or is named `source`, if any. This is
 //!   for identifying the underlying lower level error that caused your error.
@@ -125,8 +303,14 @@
 //!   The `#[from]` attribute always implies that the same field is `#[source]`,
 //!   so you don't ever need to specify both attributes.
 //!
+//!   The source can also be interpolated into the message like any other field,
+//!   as in `#[error("failed to load config: {source}")]`, and `source()` still
+//!   returns it.
+//!
 //!   Any error type that implements `std::error::Error` or dereferences to `dyn
 //!   std::error::Error` will work as a source.
+//!   `PhantomData` fields are never picked up as a source by name, and they
+//!   never cause bounds to be inferred on the type parameters they mention.
 //!
 //!   ```rust
 //!   # use std::fmt::{self, Display};
@@ -146,6 +330,68 @@
 //!   # }
 //!   ```
 //!
+//!   A source whose type implements `Debug` and `Display` but not
+//!   `std::error::Error` can be marked `#[source(wrap)]`. It is returned from
+//!   `source()` through the [`Wrapped`] adapter.
+//!
+//!   A source held in a smart pointer that implements `Deref` to the actual
+//!   error type can be marked `#[source(deref)]`, so that `source()` returns the
+//!   pointee.
+//!
+//!   A source held in a container without `Deref`, which hands out the error
+//!   through a method instead, can be marked
+//!   `#[source(with = self.slot.as_error())]`. `source()` then returns
+//!   whatever the expression evaluates to, which must be an `Option<&(dyn Error +
+//!   'static)>`, and the field itself need not be an error. This is limited to
+//!   structs, whose expression can refer to `self`.
+//!
+//!   A generic source that is only `AsRef<dyn Error>`, not an error itself, can
+//!   be marked `#[source(err_as_ref)]`. `#[thiserror(generics_err_as_ref)]` on the
+//!   struct or enum makes that the default for all of its sources, including
+//!   `#[from]` and transparent fields, and an individual field opts back out with
+//!   `#[source(err_as_ref = false)]`.
+//!
+//!   A generic source is otherwise bounded by `std::error::Error + 'static` in the
+//!   generated impls. Mark it `#[source(no_bound)]` to leave that bound out and
+//!   rely on the bounds written on the type instead, such as `E: Adapter` where
+//!   `Adapter` has `Error` as a supertrait. Unlike `#[error(no_bound)]`, a
+//!   `Display` bound is still inferred if the message formats the field.
+//!
+//!   A source of type `Box<Self>`, `Arc<Self>` or `Rc<Self>` makes a recursive
+//!   error, such as an interpreter's `InFunction { name: String, #[source] cause:
+//!   Box<EvalError> }`. `source()` returns the inner error rather than the box,
+//!   so it can be downcast to `EvalError`. `#[from] Box<Self>` generates
+//!   `From<Box<Self>>`. A boxing `From<Self>` cannot be generated because it
+//!   would overlap with the standard library's `impl<T> From<T> for T`. Walking
+//!   the chain of such an error takes as many steps as the error is deep.
+//!
+//!   When the source is an `Option` that is only known later, put
+//!   `#[thiserror(setters)]` on the struct or enum to generate `with_source`
+//!   and `set_source` methods that fill it in. On an enum every source field must
+//!   be an `Option` of the same type, and variants without one are left as they
+//!   are.
+//!
+//!   With `#[thiserror(downcast)]` on the struct or enum, an inherent method
+//!   `downcast_source::<T>()` is also generated. It walks the chain of sources
+//!   and returns the first one of type `T`, or `None` if there is none.
+//!
+//!   `#[thiserror(root_cause)]` similarly generates `root_cause()`, which follows
+//!   `source()` to the end of the chain and returns the deepest error as `&(dyn
+//!   Error + 'static)`, or the error itself if it has no source.
+//!
+//!   Secondary errors that accompany the source, such as the other failures of a
+//!   validation pass, can be kept in a `Vec`, array or slice field marked
+//!   `#[related]` on an error marked `#[thiserror(related)]`. They are not
+//!   returned from `source()`, but from a generated inherent method `related()`
+//!   that iterates over them as `&dyn Error`. Without `#[thiserror(related)]`,
+//!   thiserror ignores `#[related]` and leaves it to other derives that use it,
+//!   such as the `Diagnostic` derive of miette.
+//!
+//!   No attribute is needed to pass `&err` where a generic `E: Error` is
+//!   expected. The standard library implements `Error` for `&E` whenever `E`
+//!   does, forwarding `source()` to the error behind the reference, so thiserror
+//!   cannot and does not generate that impl itself.
+//!
 //! - The Error trait's `provide()` method is implemented to provide whichever
 //!   field has a type named `Backtrace`, if any, as a
 //!   `std::backtrace::Backtrace`.
@@ -162,6 +408,48 @@
 //!   # };
 //!   ```
 //!
+//!   A `From` impl generated for a struct or variant with such a field, or with
+//!   an `Option<Backtrace>`, fills it in with `Backtrace::capture()`. That part
+//!   works on any compiler with `std::backtrace`. The `provide()` method itself
+//!   relies on the nightly-only generic member access API; it is generated when
+//!   thiserror's `provide` feature is enabled on a compiler that has the API, and
+//!   the crate deriving Error then needs `#![feature(error_generic_member_access)]`.
+//!
+//!   The capture honors `RUST_BACKTRACE` and `RUST_LIB_BACKTRACE` as
+//!   `Backtrace::capture()` does. Mark the field `#[backtrace(force)]` to use
+//!   `Backtrace::force_capture()` instead, or `#[backtrace(disabled_unless =
+//!   "feature")]` to compile the capture out and store `Backtrace::disabled()`
+//!   unless the named feature of your crate is enabled. The field's type is the
+//!   same either way.
+//!
+//!   When the `#[from]` source captures a backtrace of its own, mark the field
+//!   `#[backtrace(from_source)]` to skip a second capture: if the source provides a
+//!   backtrace, the `From` impl stores `Backtrace::disabled()`, or `None` for an
+//!   `Option`, and `provide()` hands out the source's backtrace instead. Telling
+//!   whether the source provides one needs the generic member access API, so
+//!   without thiserror's `provide` feature the backtrace is always captured.
+//!
+//!   Other fields can be made available to `std::error::request_ref` by marking
+//!   them `#[provide]`. Each is provided by reference under its own type, or under
+//!   the inner type if it is an `Option`. `#[provide(value)]` provides a copy
+//!   instead, for `std::error::request_value`. Only one field per struct or
+//!   variant may provide a given type. Errors using `error(transparent)` forward
+//!   `provide()` wholesale to the underlying error.
+//!
+//!   ```rust
+//!   # const IGNORE: &str = stringify! {
+//!   #[derive(Error, Debug)]
+//!   #[error("request {id} failed")]
+//!   pub struct RequestError {
+//!       #[provide]
+//!       id: RequestId,
+//!       #[provide(value)]
+//!       status: u16,
+//!       backtrace: Backtrace,
+//!   }
+//!   # };
+//!   ```
+//!
 //! - If a field is both a source (named `source`, or has `#[source]` or
 //!   `#[from]` attribute) *and* is marked `#[backtrace]`, then the Error
 //!   trait's `provide()` method is forwarded to the source's `provide` so that
@@ -198,6 +486,11 @@
 //!   }
 //!   ```
 //!
+//!   Put on an enum, `#[error(transparent)]` applies to every variant without an
+//!   `#[error("...")]` message of its own, each of which must then have exactly
+//!   one field. A facade over the errors of several modules needs no attribute on
+//!   its variants at all.
+//!
 //!   Another use case is hiding implementation details of an error
 //!   representation behind an opaque error type, so that the representation is
 //!   able to evolve without breaking the crate's public API.
@@ -223,6 +516,822 @@
 //!   }
 //!   ```
 //!
+//!   A tuple struct with a single field can be made transparent without writing
+//!   `#[error(transparent)]`, by opting in with `#[thiserror(auto_transparent)]`.
+//!   It then takes effect whenever the struct has no `#[error(...)]` attribute of
+//!   its own.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(auto_transparent)]
+//!   pub struct Wrapper(std::io::Error);  // source and Display delegate to io::Error
+//!   ```
+//!
+//!   A variant marked `#[flatten]` is transparent in the same way, for an enum
+//!   that wraps the error enums of its modules and should report their variants
+//!   rather than one wrapper per module. Listing types in the attribute also
+//!   generates `From` for each of them by way of the variant's `#[from]` field,
+//!   so that `?` on an `io::Error` lands in `Storage(StorageError::Io(..))`.
+//!   `#[thiserror(kind)]` and the other generated per-variant methods still see
+//!   the wrapping variant.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use std::num::ParseIntError;
+//!   # use thiserror::Error;
+//!   #
+//!   # #[derive(Error, Debug)]
+//!   # pub enum StorageError {
+//!   #     #[error("failed to read the database")]
+//!   #     Io(#[from] io::Error),
+//!   #     #[error("corrupt page number")]
+//!   #     Page(#[from] ParseIntError),
+//!   # }
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum AppError {
+//!       #[flatten(io::Error, ParseIntError)]
+//!       Storage(#[from] StorageError),
+//!       #[error("shutting down")]
+//!       Shutdown,
+//!   }
+//!   ```
+//!
+//!   To also make `Debug` transparent, leave out `#[derive(Debug)]` and add
+//!   `#[thiserror(debug_transparent)]`. The generated `Debug` impl delegates to
+//!   the inner error for transparent variants and formats the other variants the
+//!   way `#[derive(Debug)]` would.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error)]
+//!   #[error(transparent)]
+//!   #[thiserror(debug_transparent)]
+//!   pub struct PublicError(std::io::Error);  // Debug delegates to io::Error
+//!   ```
+//!
+//! - Error types that carry secrets can have thiserror write their `Debug` impl in
+//!   place of `#[derive(Debug)]` with `#[thiserror(debug)]`. It formats the way
+//!   the derive would, except that each field marked `#[redact]` is shown as `***`.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error)]
+//!   #[thiserror(debug)]
+//!   #[error("login failed for {user}")]
+//!   pub struct LoginError {
+//!       user: String,
+//!       #[redact]
+//!       password: String,  // LoginError { user: "alice", password: *** }
+//!   }
+//!   ```
+//!
+//! - Forgetting `#[derive(Debug)]` fails on the `Debug` supertrait of `Error`.
+//!   `#[thiserror(require_debug)]` on a non-generic error adds a second error
+//!   spelling out that `Error` requires `Debug` and how to provide it, for
+//!   codebases that want that guidance in front of people new to Rust.
+//!
+//! - The bounds on generic parameters of the generated `Display` and `Error`
+//!   impls are inferred from how each field is used. Where the inference is wrong,
+//!   for example because a field is only formatted through a helper function in
+//!   the trailing arguments, `#[error(bound(T: Trait))]` on the struct or a
+//!   variant replaces whatever was inferred for `T` on both impls.
+//!   `#[error(display_bound(...))]` and `#[error(error_bound(...))]` target only
+//!   one of them.
+//!
+//!   ```rust
+//!   # use std::fmt::Debug;
+//!   # use thiserror::Error;
+//!   #
+//!   # fn render<T: Debug>(value: &T) -> String {
+//!   #     format!("{:?}", value)
+//!   # }
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error("invalid value {}", render(&self.0))]
+//!   #[error(display_bound(T: Debug))]
+//!   pub struct InvalidValue<T>(T);
+//!   ```
+//!
+//!   A single field can instead be left out of the inference with
+//!   `#[error(no_bound)]`, for when it is only formatted through a named argument
+//!   like `value = describe(&self.value)`. The expression is used as written, so
+//!   it is up to you that it compiles.
+//!
+//!   Bounds and where clauses written on the type itself are carried over to
+//!   every generated impl, and an inferred bound is not repeated when it is
+//!   already written there in the same form, such as `E: std::error::Error +
+//!   'static`.
+//!
+//!   A path like `{pair.1}` is bounded on the type it reaches when that type is
+//!   written out in the field, as in a tuple. Through any other field of generic
+//!   type, such as `{inner.code}` with `inner: Inner<T>`, the type of the value
+//!   cannot be seen, and the derive asks for `#[error(bound(...))]` or for the
+//!   value to be passed as an argument instead.
+//!
+//! - A struct or variant may declare the status a command-line tool exits with
+//!   when it fails with that error, using `#[error(exit_code = 65)]`. The derive
+//!   then generates `exit_code(&self) -> u8` and an impl of
+//!   `thiserror::ErrorExitCode`. Variants without an exit code return 1, or the
+//!   exit code put on the enum itself, except that transparent variants forward to
+//!   the error they wrap when its type implements `ErrorExitCode`.
+//!
+//!   With the `exit_code` feature of thiserror enabled, the error also converts
+//!   into `std::process::ExitCode`, and `report_and_exit()` prints it and its
+//!   chain of sources to stderr before returning that `ExitCode`.
+//!
+//!   ```rust
+//!   # use std::process::ExitCode;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   pub enum CliError {
+//!       #[error("invalid input on line {0}")]
+//!       #[error(exit_code = 65)]
+//!       DataErr(usize),
+//!       #[error("interrupted")]
+//!       Interrupted,
+//!   }
+//!   #
+//!   # fn run() -> Result<(), CliError> {
+//!   #     Err(CliError::DataErr(3))
+//!   # }
+//!
+//!   # const IGNORE: &str = stringify! {
+//!   fn main() -> ExitCode {
+//!       match run() {
+//!           Ok(()) => ExitCode::SUCCESS,
+//!           Err(error) => error.report_and_exit(),
+//!       }
+//!   }
+//!   # };
+//!   #
+//!   # assert_eq!(CliError::DataErr(3).exit_code(), 65);
+//!   # assert_eq!(CliError::Interrupted.exit_code(), 1);
+//!   ```
+//!
+//! - With the `tracing` feature of thiserror enabled, a struct or variant may
+//!   declare the severity at which it should be logged with
+//!   `#[error(level = "warn")]`. Put on an enum, the level applies to every
+//!   variant that does not declare its own, and the default is `ERROR`. The
+//!   derive then also generates `tracing_level()`, returning a
+//!   `tracing::Level`, and `emit()`, which logs the Display message as an event
+//!   at that level.
+//!
+//!   The `tracing` feature also lets a `tracing_error::SpanTrace` field be marked
+//!   `#[span_trace]`. Generated `From` impls fill it in with `SpanTrace::capture()`,
+//!   recording the spans the error was created in, and a `span_trace()` method
+//!   returns it. With the `provide` feature, it is also provided through
+//!   `Error::provide`.
+//!
+//!   ```rust
+//!   # const IGNORE: &str = stringify! {
+//!   #[derive(Error, Debug)]
+//!   #[error("query failed")]
+//!   pub struct QueryError {
+//!       #[from]
+//!       source: io::Error,
+//!       #[span_trace]
+//!       span_trace: SpanTrace,
+//!   }
+//!   # };
+//!   ```
+//!
+//! - With the `defmt` feature of thiserror enabled, `#[thiserror(defmt)]` also
+//!   generates an impl of `defmt::Format` from the same `#[error("...")]`
+//!   messages, for logging on embedded targets. Every interpolated field becomes
+//!   a `{}` placeholder whose value is formatted with its own `Format` impl, so
+//!   format specs like `:?` are dropped, and messages may only interpolate fields.
+//!   Transparent variants forward to the `Format` impl of their field.
+//!
+//!   ```rust
+//!   # const IGNORE: &str = stringify! {
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(defmt)]
+//!   pub enum SensorError {
+//!       #[error("sensor {id} timed out after {millis}ms")]
+//!       Timeout { id: u8, millis: u32 },
+//!       #[error(transparent)]
+//!       Bus(#[from] BusError),
+//!   }
+//!   # };
+//!   ```
+//!
+//! - With the `ufmt` feature of thiserror enabled, `#[thiserror(ufmt)]` also
+//!   generates an impl of `ufmt::uDisplay` from the same messages, for targets
+//!   where `core::fmt` is too heavy. Messages may only interpolate fields, using
+//!   the placeholders ufmt understands: `{}`, `{:?}`, `{:#?}` and hexadecimal
+//!   forms like `{:x}` or `{:#04x}`. Interpolated fields of generic type are
+//!   bounded by the matching ufmt trait.
+//!
+//!   ```rust
+//!   # const IGNORE: &str = stringify! {
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(ufmt)]
+//!   pub enum BusError {
+//!       #[error("invalid register {register:#x}")]
+//!       InvalidRegister { register: u8 },
+//!       #[error("timed out after {0} polls")]
+//!       Timeout(u32),
+//!   }
+//!   # };
+//!   ```
+//!
+//! - With the `serde` feature of thiserror enabled, `#[thiserror(serialize)]`
+//!   also generates an impl of `serde::Serialize`, for errors sent across an RPC
+//!   boundary. A variant of an enum is serialized externally tagged, as an object
+//!   with a single key naming the variant, while a struct is serialized as the
+//!   object itself. The object has these entries, in this order:
+//!
+//!   - `"message"`: the Display message, as a string.
+//!   - `"code"`: the `code()` of the error, only with `#[thiserror(codes)]` or
+//!     `#[thiserror(discriminant_code)]`.
+//!   - `"chain"`: the message of `source()`, of its source, and so on, as an array
+//!     of strings. It is empty for an error without a source.
+//!   - One entry per field marked `#[error(serialize)]`, keyed by the field name or
+//!     the index of a tuple field, with the field's own `Serialize` output.
+//!
+//!   A field marked `#[sensitive]` is never serialized, and stands in the message
+//!   as `<redacted>` whatever its format spec. Display itself still shows it. A
+//!   sensitive field cannot be a source, and its variant needs an
+//!   `#[error("...")]` message.
+//!
+//!   ```rust
+//!   # const IGNORE: &str = stringify! {
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(serialize)]
+//!   pub enum AuthError {
+//!       #[error("login failed for {user} with password {password:?}")]
+//!       Login {
+//!           #[error(serialize)]
+//!           user: String,
+//!           #[sensitive]
+//!           password: String,
+//!       },
+//!       #[error("backend unavailable")]
+//!       Backend(#[source] io::Error),
+//!   }
+//!   # };
+//!
+//!   // {"Login":{"message":"login failed for bob with password <redacted>","chain":[],"user":"bob"}}
+//!   // {"Backend":{"message":"backend unavailable","chain":["connection refused"]}}
+//!   ```
+//!
+//! - With the `serde` feature of thiserror enabled, `#[thiserror(deserialize)]`
+//!   on an enum whose variants have no fields generates an impl of
+//!   `serde::Deserialize` that reads the name of a variant from a string, such as
+//!   `"NotFound"`, for config files that name an error or a policy.
+//!   `#[error(name = "...")]` on a variant makes it read that name instead of the
+//!   variant's identifier. Any other string is rejected as an unknown variant.
+//!
+//!   ```rust
+//!   # const IGNORE: &str = stringify! {
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(deserialize)]
+//!   pub enum Policy {
+//!       #[error("not found")]
+//!       NotFound,
+//!       #[error("retry later")]
+//!       #[error(name = "retry")]
+//!       RetryLater,
+//!   }
+//!   # };
+//!   ```
+//!
+//! - `#[thiserror(report)]` generates `to_report()`, which returns a
+//!   `thiserror::Report`: an owned struct holding the `type_name` of the error,
+//!   the `variant` of an enum, the Display `message`, the `code` with
+//!   `#[thiserror(codes)]` or `#[thiserror(discriminant_code)]`, and the `chain`
+//!   of messages found by following `source()`. Logging middleware can record one
+//!   uniform shape whichever error reaches it, without the error's fields
+//!   implementing anything. With the `serde` feature, `Report` implements
+//!   `Serialize` as an object with those five entries.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(report)]
+//!   pub enum QueryError {
+//!       #[error("table {0} not found")]
+//!       MissingTable(String),
+//!   }
+//!
+//!   # fn main() {
+//!   let report = QueryError::MissingTable("users".to_owned()).to_report();
+//!   assert_eq!(report.type_name, "QueryError");
+//!   assert_eq!(report.variant, Some("MissingTable"));
+//!   assert_eq!(report.message, "table users not found");
+//!   # }
+//!   ```
+//!
+//! - `#[thiserror(static_messages)]` is for targets that cannot afford the
+//!   formatting machinery. Every message must be a plain string without
+//!   interpolation, which is checked at compile time. Display then writes it with
+//!   `write_str`, and a `const fn message(&self) -> &'static str` returns it
+//!   directly. It combines with `#[thiserror(no_std)]`.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(static_messages)]
+//!   pub enum FlashError {
+//!       #[error("flash is write-protected")]
+//!       WriteProtected,
+//!       #[error("erase timed out")]
+//!       Timeout { sector: u32 },
+//!   }
+//!   ```
+//!
+//! - `#[thiserror(eq)]` on an enum whose variants have no fields implements
+//!   `PartialEq` and `Eq` by comparing which variant each error is. Enums with
+//!   fields can use `#[thiserror(partial_eq)]` below instead.
+//!
+//! - `#[thiserror(clone = "arc_sources")]` implements `Clone` for errors whose
+//!   sources are not `Clone`, like `io::Error`, so they can be handed to several
+//!   waiters or memoized. Each source, including the field of a transparent
+//!   variant, must be written as an `Arc`, like `Arc<io::Error>`, and is shared
+//!   rather than copied by `clone()`. Other fields are cloned, and `Backtrace`
+//!   fields are not supported. A `#[from]` field of type `Arc<E>` also gets a
+//!   `From<E>` impl that wraps the error in the `Arc`, so `?` works on a plain
+//!   `io::Error`, unless `E` is generic.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use std::sync::Arc;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(clone = "arc_sources")]
+//!   pub enum FetchError {
+//!       #[error("failed to read {path}")]
+//!       Read { path: String, source: Arc<io::Error> },
+//!       #[error(transparent)]
+//!       Io(#[from] Arc<io::Error>),
+//!   }
+//!   #
+//!   # let error = FetchError::from(io::Error::new(io::ErrorKind::Other, "reset"));
+//!   # assert_eq!(error.clone().to_string(), "reset");
+//!   ```
+//!
+//! - `#[thiserror(partial_eq)]` implements `PartialEq` for errors whose sources
+//!   are not comparable, such as `io::Error` or `Box<dyn Error>`, so tests can
+//!   `assert_eq!` them. Two errors are equal when they are the same variant and
+//!   their other fields are equal. Sources, backtraces and any other attached
+//!   errors are treated as always equal, as is the field of a transparent variant.
+//!   With `#[thiserror(partial_eq = "message")]`, sources and transparent fields
+//!   are instead compared by their `to_string()`. Generic fields that get compared
+//!   are required to be `PartialEq`, or `Display` for a source compared by message.
+//!
+//! - `#[thiserror(hash_ignore_source)]` implements `Hash` over the fields that
+//!   describe the error, skipping its source, backtrace and any other attached
+//!   error, which are rarely `Hash`. Enums also hash which variant an error is, and
+//!   a transparent variant contributes nothing else. This makes errors usable as
+//!   keys for deduplication, given a `PartialEq` that ignores the same fields, such
+//!   as the one from `#[thiserror(eq)]` or `#[thiserror(partial_eq)]`.
+//!
+//! - `#[thiserror(variants)]` on an enum generates an associated constant
+//!   `VARIANTS: &[&str]` holding the names of its variants in declaration order,
+//!   for tests that pin down the set of errors a library can return. On a struct
+//!   it holds just the struct's name.
+//!
+//! - `#[thiserror(variant_names)]` generates `variant_name(&self) -> &'static
+//!   str`, returning the name of the variant, for metrics labels and the like, and
+//!   an associated constant `VARIANT_NAMES` listing every such name in declaration
+//!   order. On a struct both hold the struct's name. `#[error(name = "...")]` on a
+//!   variant overrides its name, so that a label can stay the same when the
+//!   variant is renamed in code. Two variants cannot share a name.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(variant_names)]
+//!   pub enum UploadError {
+//!       #[error("timed out")]
+//!       #[error(name = "timeout")]
+//!       TimedOut,
+//!       #[error("quota exceeded")]
+//!       QuotaExceeded,
+//!   }
+//!
+//!   # fn main() {
+//!   assert_eq!(UploadError::TimedOut.variant_name(), "timeout");
+//!   assert_eq!(UploadError::VARIANT_NAMES, ["timeout", "QuotaExceeded"]);
+//!   # }
+//!   ```
+//!
+//! - `#[thiserror(discriminant_code)]` on an enum generates a method
+//!   `code(&self) -> i64` returning the discriminant of the variant, such as `404`
+//!   for `NotFound = 404`. Variants of an enum with fields cannot be cast to their
+//!   discriminant, so each of them needs an explicit discriminant, which requires
+//!   a `#[repr(...)]`, or an `#[error(code = N)]`. The latter also overrides the
+//!   discriminant of any variant.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(discriminant_code)]
+//!   #[repr(u16)]
+//!   pub enum HttpError {
+//!       #[error("not found")]
+//!       NotFound = 404,
+//!       #[error("timed out after {0}s")]
+//!       #[error(code = 408)]
+//!       Timeout(u64),
+//!   }
+//!   #
+//!   # assert_eq!(HttpError::NotFound.code(), 404);
+//!   # assert_eq!(HttpError::Timeout(30).code(), 408);
+//!   ```
+//!
+//! - `#[thiserror(codes)]` on an enum is for codes that must never change or
+//!   collide, such as those that support tooling keys off. Every variant needs an
+//!   `#[error(code = N)]` with an integer literal, and two variants with the same
+//!   code are a compile error. It generates `const fn code(&self) -> u32` and an
+//!   associated constant `ALL_CODES: &[(u32, &str)]` pairing each code with the
+//!   name of its variant, from which tests can dump a reference table. It cannot
+//!   be combined with `#[thiserror(discriminant_code)]`.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(codes)]
+//!   pub enum SupportError {
+//!       #[error("failed to parse {0}")]
+//!       #[error(code = 1203)]
+//!       Parse(String),
+//!       #[error("quota exceeded")]
+//!       #[error(code = 1101)]
+//!       Quota,
+//!   }
+//!   #
+//!   # assert_eq!(SupportError::Quota.code(), 1101);
+//!   # assert_eq!(SupportError::ALL_CODES, &[(1203, "Parse"), (1101, "Quota")]);
+//!   ```
+//!
+//! - `#[thiserror(localize = path::to::Localizer)]` on an enum with
+//!   `#[thiserror(codes)]` or `#[thiserror(discriminant_code)]` generates
+//!   `fn localized(&self, localizer: &dyn Localizer) -> String`, for showing the
+//!   error in the language of the user. It calls the `message` method of your
+//!   trait with the `code()` of the error and the name and formatted value of each
+//!   field other than the source and backtrace, with the fields of a tuple variant
+//!   named `"0"`, `"1"` and so on. The `Display` impl is still generated from the
+//!   `#[error("...")]` messages.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   pub trait Localizer {
+//!       fn message(&self, code: u32, args: &[(&str, String)]) -> String;
+//!   }
+//!
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(codes, localize = Localizer)]
+//!   pub enum UploadError {
+//!       #[error("file {name} is too large")]
+//!       #[error(code = 1)]
+//!       TooLarge { name: String },
+//!   }
+//!
+//!   struct French;
+//!
+//!   impl Localizer for French {
+//!       fn message(&self, code: u32, args: &[(&str, String)]) -> String {
+//!           match (code, args) {
+//!               (1, [("name", name)]) => format!("le fichier {} est trop volumineux", name),
+//!               _ => unimplemented!(),
+//!           }
+//!       }
+//!   }
+//!   #
+//!   # let error = UploadError::TooLarge { name: "a.png".to_owned() };
+//!   # assert_eq!(error.localized(&French), "le fichier a.png est trop volumineux");
+//!   ```
+//!
+//! - `#[thiserror(repr_c_codes)]` on an enum is for errors handed across a C FFI
+//!   boundary as integer codes. Every variant needs an `#[error(code = N)]` with an
+//!   integer literal that fits in an `i32`, negative ones included, and no two
+//!   variants may share a code. It generates `const fn to_ffi_code(&self) -> i32`.
+//!   If no variant has fields, it also generates `const fn from_ffi_code(code: i32)
+//!   -> Option<Self>`, which returns `None` for an unknown code. Variants with fields
+//!   cannot be rebuilt from a code alone, so their enums get no `from_ffi_code`. The
+//!   `extern "C"` functions that a library exports can call these.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(repr_c_codes)]
+//!   pub enum FfiError {
+//!       #[error("invalid argument")]
+//!       #[error(code = -22)]
+//!       InvalidArgument,
+//!       #[error("would block")]
+//!       #[error(code = 11)]
+//!       WouldBlock,
+//!   }
+//!
+//!   #[no_mangle]
+//!   pub extern "C" fn ffi_error_is_retryable(code: i32) -> bool {
+//!       matches!(FfiError::from_ffi_code(code), Some(FfiError::WouldBlock))
+//!   }
+//!   #
+//!   # assert_eq!(FfiError::InvalidArgument.to_ffi_code(), -22);
+//!   # assert!(ffi_error_is_retryable(11));
+//!   ```
+//!
+//! - `#[thiserror(constructors)]` on an enum generates a function per variant
+//!   that builds it, named after the variant in snake_case, such as
+//!   `query_failed(query, elapsed, source)` for `QueryFailed { .. }`. A `String`,
+//!   `PathBuf` or `OsString` field is taken as `impl Into<_>`. The backtrace is
+//!   captured rather than passed in, and so is a span trace. Put
+//!   `#[error(constructor = "name")]` on a variant to name its constructor
+//!   differently.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use std::time::Duration;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(constructors)]
+//!   pub enum DbError {
+//!       #[error("query {query:?} failed after {elapsed:?}")]
+//!       QueryFailed {
+//!           query: String,
+//!           elapsed: Duration,
+//!           source: io::Error,
+//!       },
+//!       #[error("pool exhausted")]
+//!       #[error(constructor = "exhausted")]
+//!       PoolExhausted,
+//!   }
+//!   #
+//!   # let reset = io::Error::new(io::ErrorKind::Other, "reset");
+//!   # let error = DbError::query_failed("SELECT 1", Duration::from_millis(5), reset);
+//!   # assert_eq!(error.to_string(), "query \"SELECT 1\" failed after 5ms");
+//!   # let _ = DbError::exhausted();
+//!   ```
+//!
+//! - `#[thiserror(context)]` on an enum generates a context selector per variant
+//!   with a source, named after the variant, such as `ReadConfigCtx { path }` for
+//!   `ReadConfig { path: PathBuf, source: io::Error }`. It holds the other fields
+//!   of the variant, each of which accepts anything that converts `Into` the
+//!   field's type, while the backtrace and span trace are captured. A sealed
+//!   extension trait named after the enum, here `ConfigErrorContext`, then lets the
+//!   error of a `Result` be wrapped in the variant with `.context(selector)`.
+//!   Integer fields need a suffixed literal, like `3u32`, since any integer type
+//!   might convert. The enum cannot be generic.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use std::path::{Path, PathBuf};
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(context)]
+//!   pub enum ConfigError {
+//!       #[error("failed to read {path:?}")]
+//!       ReadConfig { path: PathBuf, source: io::Error },
+//!   }
+//!
+//!   fn read_config(path: &Path) -> Result<String, ConfigError> {
+//!       let config = std::fs::read_to_string(path).context(ReadConfigCtx { path })?;
+//!       Ok(config)
+//!   }
+//!   #
+//!   # assert!(read_config(Path::new("/nonexistent")).is_err());
+//!   ```
+//!
+//! - `#[thiserror(predicates)]` on an enum generates a `#[must_use]` method per
+//!   variant answering whether the error is that variant, named after it in
+//!   snake_case, such as `is_not_found(&self) -> bool` for `NotFound`. Callers can
+//!   then test for one case without matching on, and depending on, all the others.
+//!   Two variants that would get the same method, like `Io` and `IO`, are an error.
+//!
+//! - `#[thiserror(accessors)]` on an enum generates a pair of methods per variant
+//!   with fields, such as `as_parse(&self) -> Option<(&PathBuf, &io::Error)>` and
+//!   `into_parse(self) -> Option<(PathBuf, io::Error)>` for `Parse { path:
+//!   PathBuf, source: io::Error }`. They return the fields of the variant in
+//!   declaration order, by reference or by value, or `None` for any other variant.
+//!   A variant with a single field returns it bare rather than in a 1-tuple, and
+//!   unit variants get no accessors.
+//!
+//! - `#[thiserror(inner_accessors)]` lets callers get at the error that a
+//!   transparent error wraps without downcasting. On a transparent struct it
+//!   generates `into_inner(self)`, `get_ref(&self)` and `get_mut(&mut self)`. On an
+//!   enum it generates `as_io(&self)`, `as_io_mut(&mut self)` and `into_io(self)`
+//!   for a transparent variant `Io`, each returning an `Option` that is `None` for
+//!   any other variant. The methods have the visibility of the error type. Since
+//!   `#[thiserror(accessors)]` already covers transparent variants, the two cannot
+//!   be combined.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[error(transparent)]
+//!   #[thiserror(inner_accessors)]
+//!   pub struct ApiError(io::Error);
+//!   #
+//!   # let error = ApiError(io::Error::new(io::ErrorKind::Other, "reset"));
+//!   # assert_eq!(error.get_ref().kind(), io::ErrorKind::Other);
+//!   # assert_eq!(error.into_inner().to_string(), "reset");
+//!   ```
+//!
+//! - `#[thiserror(kind)]` on an enum generates a fieldless companion enum
+//!   `MyErrorKind`, with the same visibility and a unit variant per variant of the
+//!   error, along with a method `kind(&self) -> MyErrorKind`. Callers can branch
+//!   on the kind without depending on payloads that may change. The kind enum
+//!   derives `Debug`, `Clone`, `Copy`, `PartialEq`, `Eq` and `Hash`. Name it
+//!   differently with `#[thiserror(kind = "FrobKind")]`, and map a variant to a
+//!   kind of another name with `#[error(kind = Io)]`. Variants mapped to the same
+//!   name share one kind.
+//!
+//!   ```rust
+//!   # use std::io;
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(kind)]
+//!   pub enum StoreError {
+//!       #[error("failed to read {0}")]
+//!       #[error(kind = Io)]
+//!       Read(String, #[source] io::Error),
+//!       #[error("failed to write {0}")]
+//!       #[error(kind = Io)]
+//!       Write(String, #[source] io::Error),
+//!       #[error("store is read-only")]
+//!       ReadOnly,
+//!   }
+//!   #
+//!   # assert_eq!(StoreError::ReadOnly.kind(), StoreErrorKind::ReadOnly);
+//!   ```
+//!
+//! - `#[thiserror(result_alias)]` generates the `Result` alias that usually sits
+//!   below an error type, `type Result<T> = std::result::Result<T, MyError>`, with
+//!   the same visibility as the error. The parameters of a generic error follow
+//!   `T`, along with their defaults. A derive cannot see whether the module has a
+//!   `Result` already, so where it does, name the alias differently with
+//!   `#[thiserror(result_alias = "StoreResult")]`.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(result_alias = "StoreResult")]
+//!   pub enum StoreError {
+//!       #[error("key {0} not found")]
+//!       NotFound(String),
+//!   }
+//!
+//!   pub fn get(key: &str) -> StoreResult<u32> {
+//!       Err(StoreError::NotFound(key.to_owned()))
+//!   }
+//!   ```
+//!
+//! - `#[thiserror(fields)]` generates a method `fields()` returning a
+//!   `Vec<(&'static str, String)>` with the name and value of each named field,
+//!   for attaching structured context to log records. The source, backtrace and
+//!   span trace are left out. A value is formatted with Display if its type
+//!   implements it, and with Debug otherwise.
+//!
+//! - `#[thiserror(context_setter)]` on a struct with a `context: String` or
+//!   `context: Option<String>` field generates a chainable `with_context(ctx)`
+//!   method. It appends `ctx` to whatever context is already there, separated by
+//!   `": "`, so call sites can attach context the same way everywhere.
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(context_setter)]
+//!   #[error("failed to open {path}: {context}")]
+//!   pub struct OpenError {
+//!       path: String,
+//!       context: String,
+//!   }
+//!   #
+//!   # let error = OpenError { path: String::new(), context: String::new() };
+//!   # let error = error.with_context("loading index").with_context("attempt 2");
+//!   # assert_eq!(error.context, "loading index: attempt 2");
+//!   ```
+//!
+//! - `#[thiserror(no_error_impl)]` keeps everything about the `Display` impl,
+//!   including the inferred bounds, but leaves out the impl of `Error`, for types
+//!   like progress reports that are displayed but are not errors, or errors that
+//!   implement `Error` by hand. Without `source()` and `provide()` to generate,
+//!   `#[source]`, `#[from]`, `#[backtrace]` and `#[provide]` are rejected, and
+//!   `Debug` is no longer required.
+//!
+//! - `#[thiserror(legacy_description)]` is for interop with old code that still
+//!   calls the deprecated `Error::description`. It implements `description()` to
+//!   return the name of the struct or of the variant. New code should format the
+//!   error with its `Display` impl instead.
+//!
+//! - `#[thiserror(hide_generated)]` on the struct or enum marks the inherent
+//!   methods and constants generated for it, such as `with_source`, `fields` or
+//!   `VARIANTS`, as `#[doc(hidden)]`. They remain callable but stay out of the
+//!   rendered documentation of the error type. Trait impls are unaffected.
+//!
+//! - `thiserror::compose!` declares an enum that is the union of several
+//!   existing error types, for a crate-level error that only gathers the errors
+//!   of its modules. Each listed type becomes an `#[error(transparent)]` variant
+//!   with `#[from]`, and the enum goes through the same expansion as
+//!   `#[derive(Error)]`, so attributes such as `#[thiserror(predicates)]` apply
+//!   to it as well.
+//!
+//!   A variant is named after the module of a type called `Error`, or else after
+//!   the type less any `Error` suffix: `storage::Error` becomes `Storage` and
+//!   `ParseIntError` becomes `ParseInt`. Name it yourself with `as`. Variants of
+//!   your own go in braces after the list.
+//!
+//!   ```rust
+//!   # mod storage {
+//!   #     #[derive(thiserror::Error, Debug)]
+//!   #     #[error("disk full")]
+//!   #     pub struct Error;
+//!   # }
+//!   #
+//!   # mod net {
+//!   #     #[derive(thiserror::Error, Debug)]
+//!   #     #[error("connection refused")]
+//!   #     pub struct Error;
+//!   # }
+//!   #
+//!   thiserror::compose! {
+//!       #[derive(Debug)]
+//!       pub enum AppError from (storage::Error, net::Error, std::io::Error as Fs);
+//!   }
+//!
+//!   thiserror::compose! {
+//!       #[derive(Debug)]
+//!       pub enum CliError from (std::num::ParseIntError, AppError as App) {
+//!           #[error("unknown command {0:?}")]
+//!           UnknownCommand(String),
+//!       }
+//!   }
+//!   ```
+//!
+//!   With `#[thiserror(document_messages)]` on the enum, compose! also appends
+//!   each variant's message to its documentation, as in `Display: "unknown
+//!   command {0:?}"`, so that the rendered docs show what every variant prints. A
+//!   derive cannot change the item it is on, so this is only available here.
+//!
+//! - Thiserror can be used from a `no_std` crate by turning off its default `std`
+//!   feature and putting `#[thiserror(no_std)]` on each error. The generated impls
+//!   then refer to `core::error::Error` (Rust 1.81+) and the rest of `core` instead
+//!   of `std`. Backtraces, `#[span_trace]`, `#[thiserror(fields)]`,
+//!   `#[thiserror(report)]` and `#[from(kind = ...)]` depend on std and are
+//!   unavailable there.
+//!
+//!   ```toml
+//!   [dependencies]
+//!   thiserror = { version = "1.0", default-features = false }
+//!   ```
+//!
+//!   ```rust
+//!   # use thiserror::Error;
+//!   #
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(no_std)]
+//!   pub enum ParseError {
+//!       #[error("empty input")]
+//!       Empty,
+//!       #[error("invalid digit {0:?}")]
+//!       InvalidDigit(char),
+//!   }
+//!   ```
+//!
+//! - A crate that re-exports thiserror, so that its users need not depend on it,
+//!   can have their errors name it by its path with `#[thiserror(crate = "...")]`,
+//!   like `#[serde(crate = "...")]`. Every reference the generated code makes to
+//!   thiserror then goes through that path.
+//!
+//!   ```rust
+//!   # mod my_framework {
+//!   #     pub mod thiserror {
+//!   #         pub use ::thiserror::*;
+//!   #     }
+//!   # }
+//!   #
+//!   use my_framework::thiserror::Error;
+//!
+//!   #[derive(Error, Debug)]
+//!   #[thiserror(crate = "my_framework::thiserror")]
+//!   #[error("request timed out")]
+//!   pub struct TimeoutError;
+//!   ```
+//!
 //! - See also the [`anyhow`] library for a convenient single error type to use
 //!   in application code.
 //!
@@ -235,20 +1344,205 @@
     clippy::return_self_not_must_use,
     clippy::wildcard_imports,
 )]
-#![cfg_attr(provide_any, feature(provide_any))]
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(error_generic_member_access, feature(error_generic_member_access))]
+
+// Lets the modules name core's items through std paths when built without std.
+#[cfg(not(feature = "std"))]
+extern crate core as std;
 
+mod aggregate;
 mod aserror;
+#[cfg(feature = "std")]
+mod backtrace;
+mod context;
+mod debug;
 mod display;
-#[cfg(provide_any)]
+mod exit;
+#[cfg(feature = "std")]
+mod fields;
+#[cfg(feature = "defmt")]
+mod format;
+#[cfg(error_generic_member_access)]
 mod provide;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "serde")]
+mod serialize;
+mod wrap;
 
+pub use crate::context::ContextSelector;
+pub use crate::exit::ErrorExitCode;
+#[cfg(feature = "std")]
+pub use crate::report::Report;
+pub use crate::wrap::Wrapped;
 pub use thiserror_impl::*;
 
 // Not public API.
 #[doc(hidden)]
 pub mod __private {
+    pub use crate::aggregate::Aggregate;
     pub use crate::aserror::AsDynError;
-    pub use crate::display::{DisplayAsDisplay, PathAsDisplay};
-    #[cfg(provide_any)]
+    #[cfg(feature = "std")]
+    pub use crate::backtrace::provides_backtrace;
+    pub use crate::debug::{require_debug, RedactedDebug};
+    pub use crate::display::{DisplayAsDisplay, JoinAsDisplay};
+    #[cfg(feature = "std")]
+    pub use crate::display::{OsStrAsDisplay, PathAsDisplay};
+    pub use crate::exit::{ExitCodeDefault, ExitCodeDelegate, ExitCodeValue};
+    #[cfg(feature = "std")]
+    pub use crate::fields::{FieldDebug, FieldDisplay, FieldValue};
+    #[cfg(feature = "defmt")]
+    pub use crate::format::require_format;
+    #[cfg(error_generic_member_access)]
     pub use crate::provide::ThiserrorProvide;
+    #[cfg(feature = "std")]
+    pub use crate::report::report_code;
+    #[cfg(feature = "serde")]
+    pub use crate::serialize::{deserialize_variant, message, Chain, Redacted};
+    pub use crate::wrap::wrap_ref;
+    #[cfg(feature = "defmt")]
+    pub use defmt;
+    #[cfg(feature = "serde")]
+    pub use serde;
+    #[cfg(feature = "tracing")]
+    pub use tracing;
+    #[cfg(feature = "ufmt")]
+    pub use ufmt;
+}
+
+// Not public API. Keeps the generated Error::provide method only on compilers
+// that have the generic member access API, so that backtrace fields still work
+// elsewhere.
+#[cfg(error_generic_member_access)]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __provide_impl {
+    ($($tt:tt)*) => {
+        $($tt)*
+    };
+}
+
+#[cfg(not(error_generic_member_access))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __provide_impl {
+    ($($tt:tt)*) => {};
+}
+
+// Not public API. Keeps the methods generated for #[error(level = "...")] only
+// when the tracing feature is enabled.
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tracing_impl {
+    ($($tt:tt)*) => {
+        $($tt)*
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tracing_impl {
+    ($($tt:tt)*) => {};
+}
+
+// Not public API. Keeps the std::process::ExitCode conversion and
+// report_and_exit() generated for #[error(exit_code = ...)] only when the
+// exit_code feature is enabled.
+#[cfg(feature = "exit_code")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __exit_code_impl {
+    ($($tt:tt)*) => {
+        $($tt)*
+    };
+}
+
+#[cfg(not(feature = "exit_code"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __exit_code_impl {
+    ($($tt:tt)*) => {};
+}
+
+// Not public API. Keeps the defmt::Format impl generated for
+// #[thiserror(defmt)], which needs the defmt feature.
+#[cfg(feature = "defmt")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __defmt_impl {
+    ($($tt:tt)*) => {
+        $($tt)*
+    };
+}
+
+#[cfg(not(feature = "defmt"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __defmt_impl {
+    ($($tt:tt)*) => {
+        compile_error!("#[thiserror(defmt)] requires the `defmt` feature of thiserror");
+    };
+}
+
+// Not public API. Keeps the serde impls generated for #[thiserror(serialize)]
+// and #[thiserror(deserialize)], which need the serde feature.
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __serde_impl {
+    ($($tt:tt)*) => {
+        $($tt)*
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __serde_impl {
+    ($($tt:tt)*) => {
+        compile_error!("#[thiserror(serialize)] and #[thiserror(deserialize)] require the `serde` feature of thiserror");
+    };
+}
+
+// Not public API. Keeps the ufmt::uDisplay impl generated for
+// #[thiserror(ufmt)], which needs the ufmt feature.
+#[cfg(feature = "ufmt")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ufmt_impl {
+    ($($tt:tt)*) => {
+        $($tt)*
+    };
+}
+
+#[cfg(not(feature = "ufmt"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __ufmt_impl {
+    ($($tt:tt)*) => {
+        compile_error!("#[thiserror(ufmt)] requires the `ufmt` feature of thiserror");
+    };
+}
+
+// Not public API. Keeps the span_trace() method generated for a #[span_trace]
+// field, which needs the tracing feature.
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __span_trace_impl {
+    ($($tt:tt)*) => {
+        $($tt)*
+    };
+}
+
+#[cfg(not(feature = "tracing"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __span_trace_impl {
+    ($($tt:tt)*) => {
+        compile_error!("#[span_trace] requires the `tracing` feature of thiserror");
+    };
 }
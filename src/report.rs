@@ -0,0 +1,95 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+/// A structured, owned view of an error, for logging middleware that wants
+/// one uniform thing to record whichever error type it is handed.
+///
+/// Returned by the `to_report()` method that `#[derive(Error)]` generates for
+/// errors with a `#[thiserror(report)]` attribute.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct Report {
+    /// The name of the error type, without its module path or generics.
+    pub type_name: &'static str,
+    /// The name of the variant, for an enum.
+    pub variant: Option<&'static str>,
+    /// The Display message of the error.
+    pub message: String,
+    /// The code of the error, with `#[thiserror(codes)]` or a
+    /// `#[thiserror(discriminant_code)]` that fits in a `u32`.
+    pub code: Option<u32>,
+    /// The messages of the source of the error, of its source, and so on.
+    pub chain: Vec<String>,
+}
+
+impl Report {
+    #[doc(hidden)]
+    pub fn __new(
+        type_name: &'static str,
+        variant: Option<&'static str>,
+        code: Option<u32>,
+        error: &dyn Error,
+    ) -> Self {
+        let mut chain = Vec::new();
+        let mut next = error.source();
+        while let Some(source) = next {
+            chain.push(source.to_string());
+            next = source.source();
+        }
+        Report {
+            type_name,
+            variant,
+            message: error.to_string(),
+            code,
+            chain,
+        }
+    }
+}
+
+// The message followed by each message of the chain, as in
+// "failed to load config: permission denied".
+impl Display for Report {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(&self.message)?;
+        for message in &self.chain {
+            write!(formatter, ": {}", message)?;
+        }
+        Ok(())
+    }
+}
+
+// With the serde feature, a report serializes as an object with the fields
+// above as entries, `variant` and `code` being null when absent.
+#[cfg(feature = "serde")]
+impl serde::Serialize for Report {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut state = serializer.serialize_struct("Report", 5)?;
+        state.serialize_field("type_name", self.type_name)?;
+        state.serialize_field("variant", &self.variant)?;
+        state.serialize_field("message", self.message.as_str())?;
+        state.serialize_field("code", &self.code)?;
+        state.serialize_field("chain", &Messages(&self.chain))?;
+        state.end()
+    }
+}
+
+// Serializes the chain as strs, which unlike String are Serialize without the
+// std feature of serde.
+#[cfg(feature = "serde")]
+struct Messages<'a>(&'a [String]);
+
+#[cfg(feature = "serde")]
+impl<'a> serde::Serialize for Messages<'a> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.0.iter().map(String::as_str))
+    }
+}
+
+// The code() of #[thiserror(discriminant_code)] is an i64.
+pub fn report_code(code: i64) -> Option<u32> {
+    if code >= 0 && code >> 32 == 0 {
+        Some(code as u32)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,114 @@
+use serde::de::{self, Deserializer, Visitor};
+use serde::ser::{Serialize, Serializer};
+use std::error::Error;
+use std::fmt::{self, Display};
+
+// The `message` entry of #[thiserror(serialize)], written by a closure so that
+// the message of a variant with #[sensitive] fields can be rendered without
+// them. Serialized with collect_str, which needs no allocation.
+pub struct Message<F>(F);
+
+pub fn message<F>(write: F) -> Message<F>
+where
+    F: Fn(&mut fmt::Formatter) -> fmt::Result,
+{
+    Message(write)
+}
+
+impl<F> Display for Message<F>
+where
+    F: Fn(&mut fmt::Formatter) -> fmt::Result,
+{
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        (self.0)(formatter)
+    }
+}
+
+impl<F> Serialize for Message<F>
+where
+    F: Fn(&mut fmt::Formatter) -> fmt::Result,
+{
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+// The `chain` entry: the message of the source, of its source, and so on.
+pub struct Chain<'a>(pub Option<&'a (dyn Error + 'static)>);
+
+impl<'a> Serialize for Chain<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(Sources { next: self.0 })
+    }
+}
+
+struct Sources<'a> {
+    next: Option<&'a (dyn Error + 'static)>,
+}
+
+impl<'a> Iterator for Sources<'a> {
+    type Item = ChainEntry<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let error = self.next?;
+        self.next = error.source();
+        Some(ChainEntry(error))
+    }
+}
+
+struct ChainEntry<'a>(&'a (dyn Error + 'static));
+
+impl<'a> Serialize for ChainEntry<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self.0)
+    }
+}
+
+// Stands in for a #[sensitive] field in the serialized message, whatever
+// format spec the message gives it.
+pub struct Redacted;
+
+macro_rules! redacted {
+    ($($fmt:ident)*) => {
+        $(
+            impl fmt::$fmt for Redacted {
+                fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                    formatter.write_str("<redacted>")
+                }
+            }
+        )*
+    };
+}
+
+redacted!(Display Debug Octal LowerHex UpperHex Binary LowerExp UpperExp);
+
+// The variant of a #[thiserror(deserialize)] enum named by a string, as its
+// index among the names of the variants.
+pub fn deserialize_variant<'de, D>(
+    deserializer: D,
+    names: &'static [&'static str],
+) -> Result<usize, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    deserializer.deserialize_str(VariantVisitor { names })
+}
+
+struct VariantVisitor {
+    names: &'static [&'static str],
+}
+
+impl<'de> Visitor<'de> for VariantVisitor {
+    type Value = usize;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("the name of a variant")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<usize, E> {
+        self.names
+            .iter()
+            .position(|name| *name == value)
+            .ok_or_else(|| E::unknown_variant(value, self.names))
+    }
+}
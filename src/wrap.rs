@@ -0,0 +1,80 @@
+use std::error::Error;
+use std::fmt::{self, Debug, Display};
+use std::ops::Deref;
+
+/// Adapter through which a `#[source(wrap)]` field is returned from
+/// `Error::source()`.
+///
+/// Some libraries expose error types that implement `Debug` and `Display` but
+/// not `std::error::Error`. Such a field marked `#[source(wrap)]` keeps its
+/// declared type, and `source()` views it as a `Wrapped<T>` which implements
+/// `Error` by delegating to the wrapped value.
+///
+/// ```
+/// # use std::fmt::{self, Display};
+/// use std::error::Error as _;
+/// use thiserror::{Error, Wrapped};
+///
+/// #[derive(Debug)]
+/// pub struct Unspecified;
+///
+/// impl Display for Unspecified {
+///     fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+///         formatter.write_str("unspecified")
+///     }
+/// }
+///
+/// #[derive(Error, Debug)]
+/// #[error("key derivation failed")]
+/// pub struct KeyError {
+///     #[source(wrap)]
+///     cause: Unspecified,
+/// }
+///
+/// let error = KeyError { cause: Unspecified };
+/// let source = error.source().unwrap();
+/// assert_eq!(source.to_string(), "unspecified");
+/// let wrapped = source.downcast_ref::<Wrapped<Unspecified>>().unwrap();
+/// let _: &Unspecified = wrapped.get_ref();
+/// ```
+// repr(transparent) is what makes the cast in wrap_ref sound.
+#[repr(transparent)]
+pub struct Wrapped<T>(T);
+
+// Views a #[source(wrap)] field as the adapter returned from source(). Not
+// public API.
+pub fn wrap_ref<T>(value: &T) -> &Wrapped<T> {
+    // SAFETY: Wrapped<T> is #[repr(transparent)] over its only field, a T, so
+    // it has the same size, alignment and validity as T, and a &T may be read
+    // as a &Wrapped<T>. The result borrows from `value` for the same lifetime.
+    unsafe { &*(value as *const T as *const Wrapped<T>) }
+}
+
+impl<T> Wrapped<T> {
+    /// Returns a reference to the original value.
+    pub fn get_ref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T> Deref for Wrapped<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Debug> Debug for Wrapped<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.0, formatter)
+    }
+}
+
+impl<T: Display> Display for Wrapped<T> {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.0, formatter)
+    }
+}
+
+impl<T: Debug + Display> Error for Wrapped<T> {}
@@ -0,0 +1,143 @@
+#![no_std]
+
+use core::fmt;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(no_std)]
+#[error("invalid digit {digit:?} at offset {offset}")]
+pub struct InvalidDigit {
+    pub digit: char,
+    pub offset: usize,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(no_std, context)]
+pub enum ParseError {
+    #[error("empty input")]
+    Empty,
+    #[error(transparent)]
+    InvalidDigit(#[from] InvalidDigit),
+    #[error("value out of range")]
+    Overflow {
+        #[source]
+        source: core::num::TryFromIntError,
+    },
+    #[error("{0}")]
+    Format(fmt::Error),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(no_std)]
+#[error("while reading {name}")]
+pub struct Context<E> {
+    pub name: &'static str,
+    #[source]
+    pub source: E,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(no_std)]
+#[error("unknown flags: {flags:join}")]
+pub struct UnknownFlags {
+    pub flags: &'static [&'static str],
+}
+
+#[derive(Error, Debug)]
+#[thiserror(no_std, static_messages, kind, result_alias = "FlashResult")]
+pub enum FlashError {
+    #[error("flash is write-protected")]
+    WriteProtected,
+    #[error("erase timed out")]
+    Timeout { sector: u32 },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::convert::TryFrom;
+    use core::fmt::Write;
+
+    // Writes into a fixed buffer, in place of the String that format! would need.
+    struct Buffer {
+        bytes: [u8; 64],
+        len: usize,
+    }
+
+    impl Buffer {
+        fn as_str(&self) -> &str {
+            core::str::from_utf8(&self.bytes[..self.len]).unwrap()
+        }
+    }
+
+    impl Write for Buffer {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.bytes
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    fn render(error: &dyn core::error::Error) -> Buffer {
+        let mut buffer = Buffer {
+            bytes: [0; 64],
+            len: 0,
+        };
+        write!(buffer, "{}", error).unwrap();
+        buffer
+    }
+
+    #[test]
+    fn test_no_std() {
+        let error = ParseError::from(InvalidDigit {
+            digit: 'x',
+            offset: 3,
+        });
+        assert_eq!("invalid digit 'x' at offset 3", render(&error).as_str());
+        assert!(core::error::Error::source(&error).is_none());
+
+        let error = Context {
+            name: "config",
+            source: error,
+        };
+        assert_eq!("while reading config", render(&error).as_str());
+        let source = core::error::Error::source(&error).unwrap();
+        assert_eq!("invalid digit 'x' at offset 3", render(source).as_str());
+
+        let error = u8::try_from(300u32).context(OverflowCtx).unwrap_err();
+        assert!(core::error::Error::source(&error).is_some());
+        assert_eq!("empty input", render(&ParseError::Empty).as_str());
+    }
+
+    #[test]
+    fn test_join() {
+        let error = UnknownFlags {
+            flags: &["-x", "-y"],
+        };
+        assert_eq!("unknown flags: -x, -y", render(&error).as_str());
+    }
+
+    #[test]
+    fn test_static_messages() {
+        let error = FlashError::Timeout { sector: 7 };
+        assert_eq!("erase timed out", error.message());
+        assert_eq!("erase timed out", render(&error).as_str());
+        assert_eq!(
+            "flash is write-protected",
+            FlashError::WriteProtected.message()
+        );
+        let result: FlashResult<()> = Err(FlashError::WriteProtected);
+        assert!(matches!(result, Err(FlashError::WriteProtected)));
+    }
+
+    #[test]
+    fn test_kind() {
+        let error = FlashError::Timeout { sector: 7 };
+        assert_eq!(FlashErrorKind::Timeout, error.kind());
+        assert_ne!(FlashErrorKind::WriteProtected, error.kind());
+    }
+}
@@ -0,0 +1,4 @@
+// Stands in for a framework that re-exports thiserror, so that its users need
+// not depend on thiserror themselves.
+
+pub use thiserror;
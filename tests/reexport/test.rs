@@ -0,0 +1,60 @@
+// Depends on thiserror only through thiserror-reexport-framework, so every
+// path the derive generates has to go through #[thiserror(crate = "...")].
+
+use std::io;
+use std::path::PathBuf;
+use thiserror_reexport_framework::thiserror::{self as te, Error};
+
+#[derive(Error, Debug)]
+#[thiserror(crate = "thiserror_reexport_framework::thiserror", context, report)]
+pub enum ConfigError {
+    #[error("failed to read {}", path.display())]
+    Read { path: PathBuf, source: io::Error },
+    #[error("missing key {0}")]
+    Missing(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(crate = "thiserror_reexport_framework::thiserror")]
+#[error("cannot open {path}")]
+pub struct OpenError {
+    path: PathBuf,
+    #[source]
+    source: ConfigError,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(crate = "te")]
+#[error("sync failed")]
+pub struct SyncError(#[from] OpenError);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    fn denied() -> io::Result<()> {
+        Err(io::Error::new(io::ErrorKind::PermissionDenied, "denied"))
+    }
+
+    #[test]
+    fn test_reexported_crate() {
+        let error = denied().context(ReadCtx { path: "app.toml" }).unwrap_err();
+        assert_eq!("failed to read app.toml", error.to_string());
+        assert_eq!("denied", error.source().unwrap().to_string());
+
+        let report: te::Report = error.to_report();
+        assert_eq!("failed to read app.toml: denied", report.to_string());
+
+        let error = SyncError::from(OpenError {
+            path: PathBuf::from("app.toml"),
+            source: ConfigError::Missing("port".to_owned()),
+        });
+        assert_eq!("sync failed", error.to_string());
+        let open = error.source().unwrap();
+        assert_eq!("cannot open app.toml", open.to_string());
+        assert_eq!("missing key port", open.source().unwrap().to_string());
+    }
+}
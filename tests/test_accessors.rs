@@ -0,0 +1,92 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::io;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(accessors)]
+pub enum LoadError {
+    #[error("failed to parse {path:?}")]
+    Parse { path: PathBuf, source: io::Error },
+    #[error("unexpected status {0}")]
+    Status(u16),
+    #[error("not loaded")]
+    NotLoaded,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(accessors)]
+pub enum Single<T> {
+    #[error("invalid")]
+    Invalid(T),
+}
+
+#[derive(Error, Debug)]
+#[error(transparent)]
+#[thiserror(inner_accessors)]
+pub struct ApiError(io::Error);
+
+#[derive(Error, Debug)]
+#[thiserror(inner_accessors)]
+pub enum FetchError {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Load {
+        #[from]
+        inner: LoadError,
+    },
+    #[error("timed out")]
+    Timeout,
+}
+
+#[test]
+fn test_accessors() {
+    let error = LoadError::Parse {
+        path: PathBuf::from("config.toml"),
+        source: io::Error::new(io::ErrorKind::Other, "eof"),
+    };
+    let (path, source) = error.as_parse().unwrap();
+    assert_eq!(Path::new("config.toml"), path);
+    assert_eq!("eof", source.to_string());
+    assert!(error.as_status().is_none());
+
+    let (path, source) = error.into_parse().unwrap();
+    assert_eq!(PathBuf::from("config.toml"), path);
+    assert_eq!(io::ErrorKind::Other, source.kind());
+
+    let error = LoadError::Status(503);
+    assert_eq!(Some(&503), error.as_status());
+    assert!(error.as_parse().is_none());
+    assert_eq!(Some(503), error.into_status());
+
+    assert!(LoadError::NotLoaded.into_parse().is_none());
+
+    assert_eq!(
+        Some("x".to_owned()),
+        Single::Invalid("x".to_owned()).into_invalid()
+    );
+}
+
+#[test]
+fn test_inner_accessors() {
+    let mut error = ApiError(io::Error::new(io::ErrorKind::Other, "reset"));
+    assert_eq!("reset", error.get_ref().to_string());
+    *error.get_mut() = io::Error::new(io::ErrorKind::TimedOut, "slow");
+    assert_eq!(io::ErrorKind::TimedOut, error.into_inner().kind());
+
+    let mut error = FetchError::from(io::Error::new(io::ErrorKind::Other, "eof"));
+    assert_eq!("eof", error.as_io().unwrap().to_string());
+    assert!(error.as_load().is_none());
+    *error.as_io_mut().unwrap() = io::Error::new(io::ErrorKind::NotFound, "gone");
+    assert_eq!(io::ErrorKind::NotFound, error.into_io().unwrap().kind());
+
+    let error = FetchError::from(LoadError::Status(404));
+    assert_eq!(Some(&404), error.as_load().unwrap().as_status());
+    assert!(error.into_io().is_none());
+
+    let mut error = FetchError::Timeout;
+    assert!(error.as_io_mut().is_none());
+    assert!(error.into_load().is_none());
+}
@@ -0,0 +1,83 @@
+use std::error::Error as StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("item {0} failed")]
+pub struct ItemError(usize);
+
+#[derive(Error, Debug)]
+pub enum BatchError {
+    #[error("batch is empty")]
+    Empty,
+    Many(#[aggregate] Vec<ItemError>),
+}
+
+#[derive(Error, Debug)]
+pub enum CappedError {
+    Capped {
+        #[aggregate(separator = "; ", max = 2)]
+        errors: Vec<ItemError>,
+    },
+}
+
+#[derive(Error, Debug)]
+pub struct Failures<E> {
+    #[aggregate(max = 1)]
+    errors: Box<[E]>,
+}
+
+fn items(n: usize) -> Vec<ItemError> {
+    (1..=n).map(ItemError).collect()
+}
+
+#[test]
+fn test_display() {
+    let error = BatchError::from(items(3));
+    assert_eq!(
+        "3 errors:\n    item 1 failed\n    item 2 failed\n    item 3 failed",
+        error.to_string(),
+    );
+
+    let error = BatchError::Many(items(1));
+    assert_eq!("1 error:\n    item 1 failed", error.to_string());
+
+    let error = CappedError::Capped { errors: items(2) };
+    assert_eq!("2 errors: item 1 failed; item 2 failed", error.to_string());
+
+    let error = CappedError::Capped { errors: items(5) };
+    assert_eq!(
+        "5 errors: item 1 failed; item 2 failed; \u{2026}and 3 more",
+        error.to_string(),
+    );
+
+    let error = Failures {
+        errors: items(2).into_boxed_slice(),
+    };
+    assert_eq!(
+        "2 errors:\n    item 1 failed\n    \u{2026}and 1 more",
+        error.to_string(),
+    );
+}
+
+#[test]
+fn test_source() {
+    let error = BatchError::from(items(2));
+    let source = error.source().unwrap();
+    assert_eq!(1, source.downcast_ref::<ItemError>().unwrap().0);
+
+    let error = Failures {
+        errors: items(3).into_boxed_slice(),
+    };
+    assert_eq!("item 1 failed", error.source().unwrap().to_string());
+}
+
+#[test]
+fn test_empty() {
+    let error = BatchError::from(Vec::new());
+    assert_eq!("0 errors", error.to_string());
+    assert!(error.source().is_none());
+
+    let error = CappedError::Capped { errors: Vec::new() };
+    assert_eq!("0 errors", error.to_string());
+    assert!(error.source().is_none());
+}
@@ -1,7 +1,4 @@
-#![cfg_attr(
-    thiserror_nightly_testing,
-    feature(error_generic_member_access, provide_any)
-)]
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
 
 use thiserror::Error;
 
@@ -19,8 +16,8 @@ pub struct InnerBacktrace {
 #[cfg(thiserror_nightly_testing)]
 pub mod structs {
     use super::{Inner, InnerBacktrace};
-    use std::any;
     use std::backtrace::Backtrace;
+    use std::error;
     use std::error::Error;
     use std::sync::Arc;
     use thiserror::Error;
@@ -106,56 +103,61 @@ pub mod structs {
         let error = PlainBacktrace {
             backtrace: Backtrace::capture(),
         };
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = ExplicitBacktrace {
             backtrace: Backtrace::capture(),
         };
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = OptBacktrace {
             backtrace: Some(Backtrace::capture()),
         };
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = ArcBacktrace {
             backtrace: Arc::new(Backtrace::capture()),
         };
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = BacktraceFrom::from(Inner);
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = CombinedBacktraceFrom::from(InnerBacktrace {
             backtrace: Backtrace::capture(),
         });
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = OptBacktraceFrom::from(Inner);
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = ArcBacktraceFrom::from(Inner);
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = AnyhowBacktrace {
             source: anyhow::Error::msg("..."),
         };
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = BoxDynErrorBacktrace {
             source: Box::new(PlainBacktrace {
                 backtrace: Backtrace::capture(),
             }),
         };
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
     }
 
     // https://github.com/dtolnay/thiserror/issues/185 -- std::error::Error and
-    // std::any::Provide both have a method called 'provide', so directly
-    // calling it from generated code could be ambiguous.
+    // another trait in scope may both have a method called 'provide', so
+    // directly calling it from generated code could be ambiguous.
     #[test]
+    #[allow(dead_code)]
     fn test_provide_name_collision() {
-        use std::any::Provider;
+        trait Provider {
+            fn provide(&self) {}
+        }
+
+        impl<T: ?Sized> Provider for T {}
 
         #[derive(Error, Debug)]
         #[error("...")]
@@ -173,8 +175,8 @@ pub mod structs {
 #[cfg(thiserror_nightly_testing)]
 pub mod enums {
     use super::{Inner, InnerBacktrace};
-    use std::any;
     use std::backtrace::Backtrace;
+    use std::error;
     use std::sync::Arc;
     use thiserror::Error;
 
@@ -254,44 +256,250 @@ pub mod enums {
         },
     }
 
+    #[derive(Error, Debug)]
+    pub enum MixedBacktrace {
+        #[error("...")]
+        Delegated {
+            #[from]
+            #[backtrace]
+            source: InnerBacktrace,
+        },
+        #[error("...")]
+        Local {
+            #[from]
+            source: Inner,
+            backtrace: Backtrace,
+        },
+    }
+
     #[test]
     fn test_backtrace() {
         let error = PlainBacktrace::Test {
             backtrace: Backtrace::capture(),
         };
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = ExplicitBacktrace::Test {
             backtrace: Backtrace::capture(),
         };
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = OptBacktrace::Test {
             backtrace: Some(Backtrace::capture()),
         };
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = ArcBacktrace::Test {
             backtrace: Arc::new(Backtrace::capture()),
         };
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = BacktraceFrom::from(Inner);
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = CombinedBacktraceFrom::from(InnerBacktrace {
             backtrace: Backtrace::capture(),
         });
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = OptBacktraceFrom::from(Inner);
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
 
         let error = ArcBacktraceFrom::from(Inner);
-        assert!(any::request_ref::<Backtrace>(&error).is_some());
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
+
+        // The backtrace of the source is the only one in the chain.
+        let error = MixedBacktrace::from(InnerBacktrace {
+            backtrace: Backtrace::capture(),
+        });
+        let provided = error::request_ref::<Backtrace>(&error).unwrap();
+        match &error {
+            MixedBacktrace::Delegated { source } => {
+                assert!(std::ptr::eq(provided, &source.backtrace));
+            }
+            MixedBacktrace::Local { .. } => unreachable!(),
+        }
+
+        let error = MixedBacktrace::from(Inner);
+        let provided = error::request_ref::<Backtrace>(&error).unwrap();
+        match &error {
+            MixedBacktrace::Local { backtrace, .. } => assert!(std::ptr::eq(provided, backtrace)),
+            MixedBacktrace::Delegated { .. } => unreachable!(),
+        }
     }
 }
 
 #[test]
 #[cfg_attr(not(thiserror_nightly_testing), ignore)]
 fn test_backtrace() {}
+
+// Capturing a backtrace in From impls does not depend on the generic member
+// access API.
+#[rustversion::since(1.65)]
+#[allow(clippy::incompatible_msrv)]
+pub mod capture {
+    use std::backtrace::{Backtrace, BacktraceStatus};
+    use std::fmt::{Debug, Display};
+    use std::io;
+    use thiserror::Error;
+
+    #[derive(Error, Debug)]
+    #[error("io error: {source}")]
+    pub struct IoError {
+        #[from]
+        source: io::Error,
+        #[backtrace]
+        backtrace: Backtrace,
+    }
+
+    #[derive(Error, Debug)]
+    pub enum AppError<T: Debug + Display> {
+        #[error("io error {0}: {}", 1)]
+        Io(#[from] io::Error, #[backtrace] Option<Backtrace>),
+        #[error("{0} at {1}")]
+        Other(T, Backtrace),
+    }
+
+    #[derive(Error, Debug)]
+    pub enum Layered {
+        // Uses the backtrace already captured by IoError instead of taking
+        // another one.
+        #[error("layered")]
+        Io {
+            #[from]
+            #[backtrace]
+            source: IoError,
+        },
+        #[error("fmt")]
+        Fmt(#[from] std::fmt::Error, Backtrace),
+    }
+
+    #[derive(Error, Debug)]
+    pub enum Configured {
+        #[error("forced")]
+        Forced(#[from] io::Error, #[backtrace(force)] Backtrace),
+        // Any feature of this crate serves as the switch.
+        #[error("switched")]
+        Switched(
+            #[from] std::fmt::Error,
+            #[backtrace(disabled_unless = "inline")] Option<Backtrace>,
+        ),
+    }
+
+    #[derive(Error, Debug)]
+    #[error("request failed")]
+    pub struct RequestError {
+        #[from]
+        source: IoError,
+        #[backtrace(force, from_source)]
+        backtrace: Option<Backtrace>,
+    }
+
+    #[derive(Error, Debug)]
+    pub enum Reused {
+        #[error("request")]
+        Request(
+            #[from] RequestError,
+            #[backtrace(force, from_source)] Backtrace,
+        ),
+        #[error("io")]
+        Io(
+            #[from] io::Error,
+            #[backtrace(force, from_source)] Backtrace,
+        ),
+    }
+
+    fn io() -> io::Error {
+        io::Error::new(io::ErrorKind::Other, "oh no!")
+    }
+
+    #[test]
+    fn test_capture() {
+        let error = IoError::from(io());
+        assert_ne!(error.backtrace.status(), BacktraceStatus::Unsupported);
+        assert_eq!("io error: oh no!", error.to_string());
+
+        let error = AppError::<u8>::from(io());
+        assert_eq!("io error oh no!: 1", error.to_string());
+        match error {
+            AppError::Io(_, backtrace) => assert!(backtrace.is_some()),
+            AppError::Other(..) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_delegate() {
+        let error = Layered::from(IoError::from(io()));
+        assert_eq!("layered", error.to_string());
+        match error {
+            Layered::Io { source } => assert_eq!("io error: oh no!", source.to_string()),
+            Layered::Fmt(..) => unreachable!(),
+        }
+
+        let error = Layered::from(std::fmt::Error);
+        match error {
+            Layered::Fmt(_, backtrace) => {
+                assert_ne!(backtrace.status(), BacktraceStatus::Unsupported);
+            }
+            Layered::Io { .. } => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_capture_options() {
+        match Configured::from(io()) {
+            Configured::Forced(_, backtrace) => {
+                assert_eq!(backtrace.status(), BacktraceStatus::Captured);
+            }
+            Configured::Switched(..) => unreachable!(),
+        }
+
+        match Configured::from(std::fmt::Error) {
+            Configured::Switched(_, backtrace) => {
+                let status = backtrace.unwrap().status();
+                if cfg!(feature = "inline") {
+                    assert_ne!(status, BacktraceStatus::Unsupported);
+                } else {
+                    assert_eq!(status, BacktraceStatus::Disabled);
+                }
+            }
+            Configured::Forced(..) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_from_source() {
+        // io::Error provides no backtrace, so one is captured.
+        match Reused::from(io()) {
+            Reused::Io(_, backtrace) => {
+                assert_eq!(backtrace.status(), BacktraceStatus::Captured);
+            }
+            Reused::Request(..) => unreachable!(),
+        }
+
+        let error = RequestError::from(IoError::from(io()));
+        let reuses = cfg!(all(thiserror_nightly_testing, feature = "provide"));
+        assert_eq!(reuses, error.backtrace.is_none());
+
+        #[cfg(all(thiserror_nightly_testing, feature = "provide"))]
+        {
+            use std::error::request_ref;
+
+            // The outer layers hand out the backtrace captured by IoError.
+            let inner = request_ref::<Backtrace>(&error.source).unwrap();
+            let outer = request_ref::<Backtrace>(&error).unwrap();
+            assert!(std::ptr::eq(inner, outer));
+            assert_eq!(inner.to_string(), error.source.backtrace.to_string());
+
+            let error = Reused::from(error);
+            let outer = request_ref::<Backtrace>(&error).unwrap();
+            match &error {
+                Reused::Request(request, backtrace) => {
+                    assert_eq!(backtrace.status(), BacktraceStatus::Disabled);
+                    assert!(std::ptr::eq(&request.source.backtrace, outer));
+                }
+                Reused::Io(..) => unreachable!(),
+            }
+        }
+    }
+}
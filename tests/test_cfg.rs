@@ -0,0 +1,56 @@
+// The compiler strips variants and fields whose #[cfg] is false before a
+// derive macro sees the item, so the generated matches and From impls only
+// ever mention what is compiled in. Within this test `cfg(not(test))` is never
+// enabled and `cfg(test)` always is, standing in for a feature turned off and
+// on.
+
+use std::fmt;
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(kind, predicates, variants)]
+pub enum ConfigError {
+    #[error("missing key {0}")]
+    Missing(String),
+    #[cfg(not(test))]
+    #[error("io error")]
+    Io(#[from] io::Error),
+    #[cfg(test)]
+    #[error("format error")]
+    Fmt(#[from] fmt::Error),
+    #[cfg_attr(not(test), error("never used"))]
+    #[cfg_attr(test, error("invalid value for {key}"))]
+    Invalid {
+        key: String,
+        #[cfg(not(test))]
+        #[source]
+        source: io::Error,
+    },
+}
+
+// Would conflict with the derived impl if the Io variant were compiled in.
+impl From<io::Error> for ConfigError {
+    fn from(error: io::Error) -> Self {
+        ConfigError::Missing(error.to_string())
+    }
+}
+
+#[test]
+fn test_cfg_variants() {
+    assert_eq!(ConfigError::VARIANTS, &["Missing", "Fmt", "Invalid"]);
+
+    let error = ConfigError::from(fmt::Error);
+    assert!(error.is_fmt());
+    assert_eq!(ConfigErrorKind::Fmt, error.kind());
+    assert_eq!("format error", error.to_string());
+
+    let error = ConfigError::Invalid {
+        key: "port".to_owned(),
+    };
+    assert_eq!("invalid value for port", error.to_string());
+    assert!(std::error::Error::source(&error).is_none());
+
+    let error = ConfigError::from(io::Error::new(io::ErrorKind::Other, "port"));
+    assert_eq!("missing key port", error.to_string());
+}
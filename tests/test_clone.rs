@@ -0,0 +1,85 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::error::Error as _;
+use std::io;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(clone = "arc_sources")]
+pub enum FetchError {
+    #[error("failed to read {path}")]
+    Read {
+        path: String,
+        source: Arc<io::Error>,
+    },
+    #[error(transparent)]
+    Io(#[from] Arc<io::Error>),
+    #[error("cache miss after {0} tries")]
+    Miss(u32, #[source] Option<Arc<FetchError>>),
+    #[error("cancelled")]
+    Cancelled,
+}
+
+#[derive(Error, Debug)]
+#[error("query {query} failed")]
+#[thiserror(clone = "arc_sources")]
+pub struct QueryError<T> {
+    query: T,
+    source: Arc<io::Error>,
+}
+
+#[derive(Error, Debug)]
+#[error("connection lost")]
+#[thiserror(clone = "arc_sources")]
+pub struct ConnectionError(#[from] Arc<io::Error>);
+
+fn other(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, message)
+}
+
+fn fetch() -> Result<(), FetchError> {
+    Err(other("reset"))?;
+    Ok(())
+}
+
+#[test]
+fn test_clone_shares_source() {
+    let error = FetchError::Read {
+        path: "a.txt".to_owned(),
+        source: Arc::new(other("denied")),
+    };
+    let clone = error.clone();
+    assert_eq!("failed to read a.txt", clone.to_string());
+    assert_eq!("denied", clone.source().unwrap().to_string());
+    match (&error, &clone) {
+        (FetchError::Read { source: a, .. }, FetchError::Read { source: b, .. }) => {
+            assert!(Arc::ptr_eq(a, b));
+        }
+        _ => unreachable!(),
+    }
+
+    let error = FetchError::Miss(3, Some(Arc::new(FetchError::Cancelled)));
+    let clone = error.clone();
+    assert_eq!("cancelled", clone.source().unwrap().to_string());
+    assert!(FetchError::Miss(0, None).clone().source().is_none());
+}
+
+#[test]
+fn test_from_wraps_in_arc() {
+    let error = fetch().unwrap_err();
+    assert!(matches!(error, FetchError::Io(_)));
+    let clone = error.clone();
+    assert_eq!("reset", clone.to_string());
+
+    let error = ConnectionError::from(other("closed"));
+    assert_eq!("closed", error.clone().source().unwrap().to_string());
+
+    let error = QueryError {
+        query: "SELECT 1".to_owned(),
+        source: Arc::new(other("timed out")),
+    };
+    let clone = error.clone();
+    assert_eq!("query SELECT 1 failed", clone.to_string());
+    assert_eq!("timed out", clone.source().unwrap().to_string());
+}
@@ -0,0 +1,78 @@
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(codes)]
+pub enum SupportError {
+    #[error("failed to parse {0}")]
+    #[error(code = 1203)]
+    Parse(String),
+    #[error("quota exceeded")]
+    #[error(code = 1101)]
+    Quota,
+    #[error("timed out after {secs}s")]
+    #[error(code = 1500u32)]
+    Timeout { secs: u64 },
+}
+
+#[derive(Error, Debug, PartialEq)]
+#[thiserror(repr_c_codes)]
+pub enum FfiError {
+    #[error("invalid argument")]
+    #[error(code = -22)]
+    InvalidArgument,
+    #[error("out of memory")]
+    #[error(code = -12)]
+    OutOfMemory,
+    #[error("would block")]
+    #[error(code = 11)]
+    WouldBlock,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(repr_c_codes)]
+pub enum StatusError {
+    #[error("failed to open {0}")]
+    #[error(code = 2)]
+    Open(String),
+    #[error("busy")]
+    #[error(code = 16i32)]
+    Busy,
+}
+
+const QUOTA_CODE: u32 = SupportError::Quota.code();
+const BLOCK_CODE: i32 = FfiError::WouldBlock.to_ffi_code();
+
+#[test]
+fn test_codes() {
+    assert_eq!(1203, SupportError::Parse("x".to_owned()).code());
+    assert_eq!(1101, QUOTA_CODE);
+    assert_eq!(1500, SupportError::Timeout { secs: 3 }.code());
+    assert_eq!(
+        SupportError::ALL_CODES,
+        &[(1203, "Parse"), (1101, "Quota"), (1500, "Timeout")],
+    );
+
+    let table: BTreeMap<u32, &str> = SupportError::ALL_CODES.iter().cloned().collect();
+    assert_eq!(Some(&"Quota"), table.get(&1101));
+}
+
+#[test]
+fn test_ffi_codes() {
+    for error in [
+        FfiError::InvalidArgument,
+        FfiError::OutOfMemory,
+        FfiError::WouldBlock,
+    ] {
+        assert_eq!(
+            Some(&error),
+            FfiError::from_ffi_code(error.to_ffi_code()).as_ref()
+        );
+    }
+    assert_eq!(-22, FfiError::InvalidArgument.to_ffi_code());
+    assert_eq!(11, BLOCK_CODE);
+    assert_eq!(None, FfiError::from_ffi_code(0));
+
+    assert_eq!(2, StatusError::Open("a.db".to_owned()).to_ffi_code());
+    assert_eq!(16, StatusError::Busy.to_ffi_code());
+}
@@ -0,0 +1,137 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::error::Error as _;
+use std::io;
+use std::num::ParseIntError;
+
+mod storage {
+    use thiserror::Error;
+
+    #[derive(Error, Debug)]
+    pub enum Error {
+        #[error("disk full")]
+        DiskFull,
+        #[error("failed to write {path}")]
+        Write {
+            path: String,
+            source: std::io::Error,
+        },
+    }
+}
+
+mod net {
+    use thiserror::Error;
+
+    #[derive(Error, Debug)]
+    #[error("connection to {0} refused")]
+    pub struct Error(pub String);
+}
+
+mod auth_service {
+    use thiserror::Error;
+
+    #[derive(Error, Debug)]
+    pub enum Error {
+        #[error("token expired")]
+        Expired,
+    }
+}
+
+thiserror::compose! {
+    /// Everything that can go wrong in the application.
+    #[derive(Debug)]
+    pub enum AppError from (storage::Error, net::Error, auth_service::Error);
+}
+
+thiserror::compose! {
+    #[derive(Debug)]
+    #[thiserror(predicates)]
+    pub enum CliError from (
+        io::Error,
+        ParseIntError,
+        storage::Error as Store,
+    ) {
+        #[error("unknown command {0:?}")]
+        UnknownCommand(String),
+        #[error("interrupted")]
+        Interrupted,
+    }
+}
+
+/// Errors of the server, every variant documented by its message.
+#[deny(missing_docs)]
+pub mod server {
+    use std::io;
+
+    thiserror::compose! {
+        /// Anything the server reports.
+        #[derive(Debug)]
+        #[thiserror(partial_eq, document_messages)]
+        pub enum ServerError from (io::Error) {
+            /// The port is taken.
+            #[error("port {0} is in use")]
+            PortInUse(u16),
+            #[error("shutting down")]
+            ShuttingDown,
+        }
+    }
+}
+
+fn connect() -> Result<(), AppError> {
+    Err(net::Error("db:5432".to_owned()))?;
+    Ok(())
+}
+
+fn parse(input: &str) -> Result<u16, CliError> {
+    Ok(input.parse()?)
+}
+
+#[test]
+fn test_transparent_variants() {
+    let error = AppError::Storage(storage::Error::DiskFull);
+    assert_eq!("disk full", error.to_string());
+
+    let error = AppError::from(storage::Error::Write {
+        path: "a.db".to_owned(),
+        source: io::Error::new(io::ErrorKind::Other, "denied"),
+    });
+    assert_eq!("failed to write a.db", error.to_string());
+    assert_eq!("denied", error.source().unwrap().to_string());
+
+    let error = connect().unwrap_err();
+    assert!(matches!(error, AppError::Net(_)));
+    assert_eq!("connection to db:5432 refused", error.to_string());
+
+    let error = AppError::from(auth_service::Error::Expired);
+    assert!(matches!(error, AppError::AuthService(_)));
+}
+
+#[test]
+fn test_naming_and_extra_variants() {
+    let error = parse("x").unwrap_err();
+    assert!(error.is_parse_int());
+    assert_eq!("invalid digit found in string", error.to_string());
+
+    let error = CliError::from(io::Error::new(io::ErrorKind::Other, "closed"));
+    assert!(error.is_io());
+    assert_eq!("closed", error.to_string());
+
+    let error = CliError::from(storage::Error::DiskFull);
+    assert!(error.is_store());
+
+    let error = CliError::UnknownCommand("frob".to_owned());
+    assert_eq!("unknown command \"frob\"", error.to_string());
+    assert!(error.source().is_none());
+    assert_eq!("interrupted", CliError::Interrupted.to_string());
+}
+
+#[test]
+fn test_document_messages() {
+    use server::ServerError;
+
+    assert_eq!("port 80 is in use", ServerError::PortInUse(80).to_string());
+    assert_eq!("shutting down", ServerError::ShuttingDown.to_string());
+    assert_eq!(ServerError::ShuttingDown, ServerError::ShuttingDown);
+    let error = ServerError::from(io::Error::new(io::ErrorKind::Other, "reset"));
+    assert_eq!("reset", error.to_string());
+}
@@ -0,0 +1,82 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::error::Error as _;
+use std::io;
+use std::marker::PhantomData;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(constructors)]
+pub enum QueryError {
+    #[error("query {query:?} failed after {elapsed:?}")]
+    QueryFailed {
+        query: String,
+        elapsed: Duration,
+        source: io::Error,
+    },
+    #[error("no such table {0:?}")]
+    NotFound(PathBuf),
+    #[error("HTTP error {status}")]
+    HTTPError { status: u16 },
+    #[error("pool exhausted")]
+    #[error(constructor = "exhausted")]
+    PoolExhausted,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(constructors)]
+pub enum Marked<T> {
+    #[error("invalid {0}")]
+    Invalid(u8, PhantomData<T>),
+}
+
+#[test]
+fn test_constructors() {
+    let error = QueryError::query_failed(
+        "SELECT 1",
+        Duration::from_millis(5),
+        io::Error::new(io::ErrorKind::Other, "reset"),
+    );
+    assert_eq!("query \"SELECT 1\" failed after 5ms", error.to_string());
+    assert_eq!("reset", error.source().unwrap().to_string());
+
+    let error = QueryError::not_found(Path::new("users"));
+    assert_eq!("no such table \"users\"", error.to_string());
+
+    let error = QueryError::http_error(503);
+    assert_eq!("HTTP error 503", error.to_string());
+
+    let error = QueryError::exhausted();
+    assert!(matches!(error, QueryError::PoolExhausted));
+
+    let error = QueryError::io(io::Error::new(io::ErrorKind::Other, "closed"));
+    assert_eq!("closed", error.to_string());
+
+    let error = Marked::<String>::invalid(7);
+    assert_eq!("invalid 7", error.to_string());
+}
+
+#[cfg(thiserror_nightly_testing)]
+pub mod backtrace {
+    use std::backtrace::Backtrace;
+    use std::error::request_ref;
+    use thiserror::Error;
+
+    #[derive(Error, Debug)]
+    #[thiserror(constructors)]
+    pub enum TracedError {
+        #[error("timed out after {secs}s")]
+        TimedOut { secs: u64, backtrace: Backtrace },
+    }
+
+    #[test]
+    fn test_backtrace() {
+        let error = TracedError::timed_out(30);
+        assert_eq!("timed out after 30s", error.to_string());
+        assert!(request_ref::<Backtrace>(&error).is_some());
+    }
+}
@@ -0,0 +1,38 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(context_setter)]
+#[error("failed to open {path}: {context}")]
+pub struct OpenError {
+    path: String,
+    context: String,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(context_setter)]
+#[error("sync failed")]
+pub struct SyncError {
+    context: Option<String>,
+}
+
+#[test]
+fn test_with_context() {
+    let error = OpenError {
+        path: "db.sqlite".to_owned(),
+        context: String::new(),
+    }
+    .with_context("loading index")
+    .with_context(format!("attempt {}", 2));
+    assert_eq!("loading index: attempt 2", error.context);
+    assert_eq!(
+        "failed to open db.sqlite: loading index: attempt 2",
+        error.to_string(),
+    );
+}
+
+#[test]
+fn test_with_optional_context() {
+    let error = SyncError { context: None };
+    let error = error.with_context("pushing").with_context("remote origin");
+    assert_eq!(Some("pushing: remote origin"), error.context.as_deref());
+}
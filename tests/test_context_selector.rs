@@ -0,0 +1,100 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::error::Error as _;
+use std::io;
+use std::num::ParseIntError;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(context)]
+pub enum ConfigError {
+    #[error("failed to read {path:?}")]
+    ReadConfig { path: PathBuf, source: io::Error },
+    #[error("failed to write {path:?} after {attempts} attempts")]
+    WriteConfig {
+        path: PathBuf,
+        attempts: u32,
+        source: io::Error,
+    },
+    #[error("invalid port on line {0}")]
+    Port(usize, #[source] ParseIntError),
+    #[error("lock poisoned")]
+    Poisoned {
+        #[source]
+        source: Option<io::Error>,
+    },
+    #[error("no home directory")]
+    NoHome,
+    #[error(transparent)]
+    Other(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+fn denied() -> io::Result<()> {
+    Err(io::Error::new(io::ErrorKind::PermissionDenied, "denied"))
+}
+
+#[test]
+fn test_named() {
+    let path = Path::new("/etc/app.toml");
+    let error = denied().context(ReadConfigCtx { path }).unwrap_err();
+    assert_eq!("failed to read \"/etc/app.toml\"", error.to_string());
+    assert_eq!("denied", error.source().unwrap().to_string());
+
+    let error = denied()
+        .context(WriteConfigCtx {
+            path: "app.toml",
+            attempts: 3u32,
+        })
+        .unwrap_err();
+    assert_eq!(
+        "failed to write \"app.toml\" after 3 attempts",
+        error.to_string(),
+    );
+}
+
+#[test]
+fn test_unnamed() {
+    let error = "x".parse::<u16>().context(PortCtx(7usize)).unwrap_err();
+    assert_eq!("invalid port on line 7", error.to_string());
+    assert!(error.source().unwrap().is::<ParseIntError>());
+}
+
+#[test]
+fn test_optional_source() {
+    let error = denied().context(PoisonedCtx).unwrap_err();
+    assert!(matches!(error, ConfigError::Poisoned { source: Some(_) }));
+}
+
+#[test]
+fn test_ok() {
+    let value = Ok::<_, io::Error>(1).context(PoisonedCtx).unwrap();
+    assert_eq!(1, value);
+}
+
+#[cfg(thiserror_nightly_testing)]
+pub mod backtrace {
+    use std::backtrace::Backtrace;
+    use std::error::request_ref;
+    use std::io;
+    use thiserror::Error;
+
+    #[derive(Error, Debug)]
+    #[thiserror(context)]
+    pub enum StoreError {
+        #[error("failed to open {name}")]
+        Open {
+            name: String,
+            source: io::Error,
+            backtrace: Backtrace,
+        },
+    }
+
+    #[test]
+    fn test_backtrace() {
+        let result: io::Result<()> = Err(io::Error::new(io::ErrorKind::Other, "gone"));
+        let error = result.context(OpenCtx { name: "users" }).unwrap_err();
+        assert_eq!("failed to open users", error.to_string());
+        assert!(request_ref::<Backtrace>(&error).is_some());
+    }
+}
@@ -0,0 +1,85 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::io;
+use thiserror::Error;
+
+#[derive(Error)]
+#[thiserror(debug)]
+#[error("login failed for {user}")]
+pub struct LoginError {
+    user: String,
+    #[redact]
+    password: String,
+}
+
+#[derive(Error)]
+#[thiserror(debug)]
+pub enum ApiError {
+    #[error("invalid token")]
+    InvalidToken(#[redact] String, u16),
+    #[error("request to {url} failed")]
+    Request {
+        url: String,
+        #[redact]
+        api_key: String,
+        source: io::Error,
+    },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("rate limited")]
+    RateLimited,
+}
+
+#[derive(Error)]
+#[thiserror(debug)]
+#[error("bad value")]
+pub struct ValueError<T, S>(T, #[redact] S);
+
+#[derive(Error)]
+#[thiserror(debug)]
+#[error("timed out")]
+pub struct Timeout;
+
+#[derive(Error)]
+#[thiserror(debug)]
+pub enum Never {}
+
+#[test]
+fn test_redacted_struct() {
+    let error = LoginError {
+        user: "alice".to_owned(),
+        password: "hunter2".to_owned(),
+    };
+    let debug = format!("{:?}", error);
+    assert_eq!("LoginError { user: \"alice\", password: *** }", debug);
+    assert!(!format!("{:#?}", error).contains("hunter2"));
+    assert_eq!("login failed for alice", error.to_string());
+}
+
+#[test]
+fn test_redacted_enum() {
+    let error = ApiError::InvalidToken("s3cr3t".to_owned(), 401);
+    assert_eq!("InvalidToken(***, 401)", format!("{:?}", error));
+
+    let error = ApiError::Request {
+        url: "https://example.com".to_owned(),
+        api_key: "s3cr3t".to_owned(),
+        source: io::Error::new(io::ErrorKind::Other, "reset"),
+    };
+    let debug = format!("{:?}", error);
+    assert!(!debug.contains("s3cr3t"));
+    assert!(debug.starts_with("Request { url: \"https://example.com\", api_key: ***, source: "));
+
+    let error = ApiError::from(io::Error::new(io::ErrorKind::Other, "closed"));
+    assert!(format!("{:?}", error).starts_with("Io("));
+    assert_eq!("RateLimited", format!("{:?}", ApiError::RateLimited));
+}
+
+#[test]
+fn test_generic_and_unit() {
+    // Only the field that is shown needs Debug.
+    struct Secret;
+    let error = ValueError(7, Secret);
+    assert_eq!("ValueError(7, ***)", format!("{:?}", error));
+    assert_eq!("Timeout", format!("{:?}", Timeout));
+}
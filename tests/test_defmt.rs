@@ -0,0 +1,47 @@
+#![cfg(feature = "defmt")]
+
+use std::fmt::Debug;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(defmt)]
+#[error("invalid digit {digit} at offset {offset:?}")]
+pub struct InvalidDigit {
+    digit: char,
+    offset: usize,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(defmt)]
+#[error(transparent)]
+pub struct Transparent(InvalidDigit);
+
+#[derive(Error, Debug)]
+#[thiserror(defmt)]
+pub enum ParseError {
+    #[error("empty input")]
+    Empty,
+    #[error("{{{0}}} is too long by {1}")]
+    TooLong(u32, u8),
+    #[error(transparent)]
+    InvalidDigit(#[from] InvalidDigit),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(defmt)]
+pub enum Generic<E: Debug> {
+    #[error("while reading: {source}")]
+    Read { source: E },
+    #[error(transparent)]
+    Other(E),
+}
+
+fn assert_format<T: defmt::Format>() {}
+
+#[test]
+fn test_defmt() {
+    assert_format::<InvalidDigit>();
+    assert_format::<Transparent>();
+    assert_format::<ParseError>();
+    assert_format::<Generic<InvalidDigit>>();
+}
@@ -8,3 +8,11 @@ pub enum Error {
     #[error("...")]
     Deprecated,
 }
+
+#[derive(Error, Debug)]
+#[thiserror(legacy_description)]
+pub enum Legacy {
+    #[deprecated]
+    #[error("...")]
+    Deprecated,
+}
@@ -0,0 +1,45 @@
+#![cfg(feature = "serde")]
+
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+#[thiserror(deserialize)]
+pub enum Policy {
+    #[error("not found")]
+    NotFound,
+    #[error("retry later")]
+    #[error(name = "retry")]
+    RetryLater,
+    #[error("denied")]
+    Denied {},
+}
+
+#[derive(Error, Debug)]
+#[thiserror(deserialize)]
+pub enum Never {}
+
+#[test]
+fn test_deserialize() {
+    let policy: Policy = serde_json::from_str("\"NotFound\"").unwrap();
+    assert_eq!(Policy::NotFound, policy);
+
+    let policy: Policy = serde_json::from_str("\"retry\"").unwrap();
+    assert_eq!(Policy::RetryLater, policy);
+
+    let policies: Vec<Policy> = serde_json::from_str("[\"Denied\", \"NotFound\"]").unwrap();
+    assert_eq!(vec![Policy::Denied {}, Policy::NotFound], policies);
+}
+
+#[test]
+fn test_unknown_variant() {
+    let error = serde_json::from_str::<Policy>("\"RetryLater\"").unwrap_err();
+    assert_eq!(
+        "unknown variant `RetryLater`, expected one of `NotFound`, `retry`, `Denied` at line 1 column 12",
+        error.to_string(),
+    );
+
+    let error = serde_json::from_str::<Policy>("404").unwrap_err();
+    assert!(error.to_string().starts_with("invalid type: integer `404`"));
+
+    assert!(serde_json::from_str::<Never>("\"NotFound\"").is_err());
+}
@@ -0,0 +1,43 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(discriminant_code)]
+pub enum HttpError {
+    #[error("not found")]
+    NotFound = 404,
+    #[error("gone")]
+    Gone = 410,
+    #[error("length required")]
+    LengthRequired,
+    #[error("teapot")]
+    #[error(code = 418)]
+    Teapot,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(discriminant_code)]
+#[repr(u16)]
+pub enum RequestError {
+    #[error("bad header {0}")]
+    BadHeader(String) = 400,
+    #[error("timed out after {secs}s")]
+    Timeout { secs: u64 } = 408,
+    #[error("server error")]
+    #[error(code = -1)]
+    Server(u16),
+}
+
+#[test]
+fn test_fieldless() {
+    assert_eq!(404, HttpError::NotFound.code());
+    assert_eq!(410, HttpError::Gone.code());
+    assert_eq!(411, HttpError::LengthRequired.code());
+    assert_eq!(418, HttpError::Teapot.code());
+}
+
+#[test]
+fn test_fields() {
+    assert_eq!(400, RequestError::BadHeader("Host".to_owned()).code());
+    assert_eq!(408, RequestError::Timeout { secs: 30 }.code());
+    assert_eq!(-1, RequestError::Server(503).code());
+}
@@ -172,12 +172,32 @@ fn test_nested_display() {
 
 #[test]
 fn test_void() {
-    #[allow(clippy::empty_enum)]
+    #[allow(clippy::empty_enums)]
     #[derive(Error, Debug)]
     #[error("...")]
     pub enum Error {}
 
+    #[allow(clippy::empty_enums)]
+    #[derive(Error, Debug)]
+    pub enum NoMessage {}
+
+    struct Port(u16);
+
+    impl std::str::FromStr for Port {
+        type Err = NoMessage;
+
+        fn from_str(_: &str) -> Result<Self, Self::Err> {
+            Ok(Port(80))
+        }
+    }
+
+    fn assert_error<E: std::error::Error>() {}
+    assert_error::<Error>();
+    assert_error::<NoMessage>();
+
     let _: Error;
+    let port: Port = "http".parse().unwrap();
+    assert_eq!(80, port.0);
 }
 
 #[test]
@@ -299,3 +319,314 @@ fn test_keyword() {
 
     assert("error: 1", Error);
 }
+
+#[test]
+fn test_nested_field_path() {
+    #[derive(Debug)]
+    struct Inner {
+        code: u32,
+    }
+
+    #[derive(Error, Debug)]
+    #[error("code {inner.code}, hex {inner.code:08x}")]
+    struct Error {
+        inner: Inner,
+    }
+
+    assert(
+        "code 255, hex 000000ff",
+        Error {
+            inner: Inner { code: 255 },
+        },
+    );
+}
+
+#[test]
+fn test_nested_field_path_deep() {
+    #[derive(Debug)]
+    struct C {
+        name: &'static str,
+    }
+
+    #[derive(Debug)]
+    struct B {
+        c: C,
+    }
+
+    #[derive(Error, Debug)]
+    enum Error {
+        #[error("tuple: {0.c.name:?} at {1}")]
+        Tuple(B, usize),
+        #[error("named: {b.c.name} and {}", .b.c.name.len())]
+        Named { b: B },
+    }
+
+    let b = || B {
+        c: C { name: "leaf" },
+    };
+    assert("tuple: \"leaf\" at 1", Error::Tuple(b(), 1));
+    assert("named: leaf and 4", Error::Named { b: b() });
+}
+
+#[test]
+fn test_nested_field_path_generic() {
+    // The type at the end of `pair.1` is seen through the tuple and bounded by
+    // LowerHex.
+    #[derive(Error, Debug)]
+    #[error("{pair.0} failed with {pair.1:#x}")]
+    pub struct TupleError<N, C> {
+        pair: (N, C),
+    }
+
+    #[derive(Debug)]
+    pub struct Inner<T> {
+        code: T,
+    }
+
+    // Not seen through a struct, so written out.
+    #[derive(Error, Debug)]
+    #[error("code {inner.code}")]
+    #[error(bound(T: Display))]
+    pub struct StructError<T> {
+        inner: Inner<T>,
+    }
+
+    assert(
+        "read failed with 0xff",
+        TupleError {
+            pair: ("read", 255),
+        },
+    );
+    assert(
+        "code 7",
+        StructError {
+            inner: Inner { code: 7 },
+        },
+    );
+}
+
+#[test]
+fn test_shorthand_with_positional() {
+    #[derive(Error, Debug)]
+    #[error("{field} at {}", self.offset)]
+    struct One {
+        field: &'static str,
+        offset: usize,
+    }
+
+    #[derive(Error, Debug)]
+    enum Two {
+        #[error("{field} at {}:{}, {field:?} again", .line, .column + 1)]
+        Named {
+            field: &'static str,
+            line: usize,
+            column: usize,
+        },
+        #[error("{1} {} {0} {}", .0.len(), "!")]
+        Tuple(&'static str, usize),
+    }
+
+    assert(
+        "token at 9",
+        One {
+            field: "token",
+            offset: 9,
+        },
+    );
+    assert(
+        "token at 1:2, \"token\" again",
+        Two::Named {
+            field: "token",
+            line: 1,
+            column: 1,
+        },
+    );
+    assert("3 4 abcd !", Two::Tuple("abcd", 3));
+}
+
+#[test]
+fn test_self_method() {
+    #[derive(Error, Debug)]
+    #[error("{self.describe()} ({self.describe():?}, code {code})")]
+    struct Error {
+        code: u16,
+    }
+
+    impl Error {
+        fn describe(&self) -> String {
+            match self.code {
+                404 => "not found".to_owned(),
+                _ => "unknown".to_owned(),
+            }
+        }
+    }
+
+    #[derive(Error, Debug)]
+    enum Status {
+        #[error("status: {self.describe()}")]
+        Code(u16),
+        #[error("{self.describe()} again")]
+        Other,
+    }
+
+    impl Status {
+        fn describe(&self) -> &'static str {
+            match self {
+                Status::Code(_) => "code",
+                Status::Other => "other",
+            }
+        }
+    }
+
+    assert("not found (\"not found\", code 404)", Error { code: 404 });
+    assert("status: code", Status::Code(1));
+    assert("other again", Status::Other);
+}
+
+#[test]
+fn test_field_method() {
+    #[derive(Debug)]
+    struct FieldError;
+
+    #[derive(Error, Debug)]
+    #[error("{errors.len()} validation errors (none: {errors.is_empty()})")]
+    struct ValidationError<E> {
+        errors: Vec<E>,
+    }
+
+    #[derive(Error, Debug)]
+    enum Error {
+        #[error("{0.len():>3} pending, first {1.0.len()}")]
+        Pending(Vec<u8>, (String,)),
+    }
+
+    assert(
+        "2 validation errors (none: false)",
+        ValidationError {
+            errors: vec![FieldError, FieldError],
+        },
+    );
+    assert(
+        "0 validation errors (none: true)",
+        ValidationError::<FieldError> { errors: Vec::new() },
+    );
+    assert(
+        "  1 pending, first 4",
+        Error::Pending(vec![7], ("head".to_owned(),)),
+    );
+}
+
+#[test]
+fn test_join() {
+    #[derive(Error, Debug)]
+    enum Error {
+        #[error("unknown: {names:join}")]
+        Unknown { names: Vec<String> },
+        #[error("bad ports {0:join} ({0:?})")]
+        Ports(Vec<u32>),
+        #[error("none of [{}] in {inner.ids:join}", .inner.ids.len())]
+        Nested { inner: Inner },
+    }
+
+    #[derive(Debug)]
+    struct Inner {
+        ids: [u8; 2],
+    }
+
+    assert(
+        "unknown: a, b",
+        Error::Unknown {
+            names: vec!["a".to_owned(), "b".to_owned()],
+        },
+    );
+    assert("bad ports 80, 443 ([80, 443])", Error::Ports(vec![80, 443]));
+    assert(
+        "none of [2] in 1, 2",
+        Error::Nested {
+            inner: Inner { ids: [1, 2] },
+        },
+    );
+    assert("unknown: ", Error::Unknown { names: Vec::new() });
+}
+
+#[test]
+fn test_match_field() {
+    #[derive(Debug)]
+    enum Kind {
+        Read,
+        Write,
+    }
+
+    #[derive(Error, Debug)]
+    enum Error {
+        #[error(match = .kind, Kind::Read => "failed to read {path}", Kind::Write => "failed to write {path:?}")]
+        Io { kind: Kind, path: String },
+        #[error(match = self.0, Some(_) => "bad address {0:?}", None => "no address")]
+        Address(Option<u32>),
+    }
+
+    #[derive(Error, Debug)]
+    #[error(match = .1, 0 => "empty {0}", _ => "{1} items in {0}")]
+    struct Collection(&'static str, usize);
+
+    assert(
+        "failed to read a.txt",
+        Error::Io {
+            kind: Kind::Read,
+            path: "a.txt".to_owned(),
+        },
+    );
+    assert(
+        "failed to write \"b.txt\"",
+        Error::Io {
+            kind: Kind::Write,
+            path: "b.txt".to_owned(),
+        },
+    );
+    assert("bad address Some(1)", Error::Address(Some(1)));
+    assert("no address", Error::Address(None));
+    assert("empty cart", Collection("cart", 0));
+    assert("3 items in cart", Collection("cart", 3));
+}
+
+#[test]
+fn test_alternate() {
+    #[derive(Error, Debug)]
+    #[error("failed to parse {path}")]
+    #[error(alt = "failed to parse {path}\n  line {line}: {0}", .reason)]
+    struct ParseError {
+        path: String,
+        line: usize,
+        reason: &'static str,
+    }
+
+    #[derive(Error, Debug)]
+    enum Error {
+        #[error(alt = "unexpected token {0:?}\n  expected one of {1:?}")]
+        #[error("unexpected token {0:?}")]
+        Unexpected(char, &'static [char]),
+        #[error("end of input")]
+        Eof,
+    }
+
+    let error = ParseError {
+        path: "Cargo.toml".to_owned(),
+        line: 3,
+        reason: "missing `=`",
+    };
+    assert("failed to parse Cargo.toml", &error);
+    assert_eq!(
+        "failed to parse Cargo.toml\n  line 3: missing `=`",
+        format!("{:#}", error),
+    );
+
+    let error = Error::Unexpected('}', &['=', '.']);
+    assert("unexpected token '}'", &error);
+    assert_eq!(
+        "unexpected token '}'\n  expected one of ['=', '.']",
+        format!("{:#}", error),
+    );
+
+    assert("end of input", Error::Eof);
+    assert_eq!("end of input", format!("{:#}", Error::Eof));
+}
@@ -0,0 +1,26 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(eq)]
+pub enum ConfigError {
+    #[error("missing field")]
+    MissingField,
+    #[error("duplicate key")]
+    DuplicateKey(),
+    #[error("unsupported version")]
+    UnsupportedVersion {},
+}
+
+fn assert_eq_impl<T: Eq>() {}
+
+#[test]
+fn test_eq() {
+    assert_eq_impl::<ConfigError>();
+    assert_eq!(ConfigError::MissingField, ConfigError::MissingField);
+    assert_eq!(ConfigError::DuplicateKey(), ConfigError::DuplicateKey());
+    assert_ne!(ConfigError::MissingField, ConfigError::DuplicateKey());
+    assert_ne!(
+        ConfigError::UnsupportedVersion {},
+        ConfigError::MissingField,
+    );
+}
@@ -54,3 +54,25 @@ unimplemented_display!(UnitError);
 unimplemented_display!(WithSource);
 unimplemented_display!(WithAnyhow);
 unimplemented_display!(EnumError);
+
+#[test]
+fn test_variants() {
+    #[derive(Error, Debug)]
+    #[thiserror(variants)]
+    enum MyError {
+        #[error("a")]
+        A,
+        #[error("b")]
+        B(usize),
+        #[error("c")]
+        C { r#type: usize },
+    }
+
+    #[derive(Error, Debug)]
+    #[error("...")]
+    #[thiserror(variants)]
+    struct Single;
+
+    assert_eq!(MyError::VARIANTS, &["A", "B", "C"]);
+    assert_eq!(Single::VARIANTS, &["Single"]);
+}
@@ -0,0 +1,97 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+#![allow(clippy::incompatible_msrv)]
+
+use std::io;
+use thiserror::{Error, ErrorExitCode};
+
+#[derive(Error, Debug)]
+pub enum CliError {
+    #[error("invalid input on line {0}")]
+    #[error(exit_code = 65)]
+    DataErr(usize),
+    #[error("cannot open {0}")]
+    #[error(exit_code = 66)]
+    NoInput(String),
+    #[error("interrupted")]
+    Interrupted,
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[error("bad config key {key}")]
+#[error(exit_code = 78)]
+pub struct ConfigError {
+    key: String,
+}
+
+#[derive(Error, Debug)]
+#[error(exit_code = 70)]
+pub enum InternalError {
+    #[error("bug")]
+    Bug,
+    #[error("not permitted")]
+    #[error(exit_code = 77)]
+    NoPerm,
+    #[error(transparent)]
+    Io(io::Error),
+}
+
+fn exit_code_of(error: &dyn ErrorExitCode) -> u8 {
+    error.exit_code()
+}
+
+#[test]
+fn test_exit_code() {
+    assert_eq!(65, CliError::DataErr(3).exit_code());
+    assert_eq!(66, CliError::NoInput("a.txt".to_owned()).exit_code());
+    assert_eq!(1, CliError::Interrupted.exit_code());
+
+    let config = ConfigError {
+        key: "colour".to_owned(),
+    };
+    assert_eq!(78, config.exit_code());
+    assert_eq!(78, CliError::from(config).exit_code());
+
+    let io = io::Error::new(io::ErrorKind::Other, "broken pipe");
+    assert_eq!(1, CliError::from(io).exit_code());
+}
+
+#[test]
+fn test_enum_default() {
+    assert_eq!(70, InternalError::Bug.exit_code());
+    assert_eq!(77, InternalError::NoPerm.exit_code());
+    let io = io::Error::new(io::ErrorKind::Other, "broken pipe");
+    assert_eq!(70, InternalError::Io(io).exit_code());
+}
+
+#[test]
+fn test_trait() {
+    assert_eq!(65, exit_code_of(&CliError::DataErr(3)));
+    assert_eq!(77, exit_code_of(&InternalError::NoPerm));
+}
+
+#[cfg(feature = "exit_code")]
+mod process {
+    use super::{CliError, ConfigError};
+    use std::process::ExitCode;
+
+    #[test]
+    fn test_exit_code_conversion() {
+        let error = CliError::DataErr(3);
+        assert_eq!(
+            format!("{:?}", ExitCode::from(65)),
+            format!("{:?}", ExitCode::from(error)),
+        );
+
+        let error = CliError::from(ConfigError {
+            key: "colour".to_owned(),
+        });
+        assert_eq!(
+            format!("{:?}", ExitCode::from(78)),
+            format!("{:?}", error.report_and_exit()),
+        );
+    }
+}
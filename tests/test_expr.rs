@@ -1,4 +1,8 @@
-#![allow(clippy::iter_cloned_collect, clippy::option_if_let_else)]
+#![allow(
+    clippy::incompatible_msrv,
+    clippy::iter_cloned_collect,
+    clippy::option_if_let_else
+)]
 
 use std::fmt::Display;
 use thiserror::Error;
@@ -86,3 +90,95 @@ fn test_rustup() {
         },
     );
 }
+
+pub struct Point {
+    pub x: u64,
+}
+
+// Named arguments computed from fields with method calls and arithmetic.
+#[derive(Error, Debug)]
+pub enum TransferError {
+    #[error("stalled at {pct}% after {secs}s", pct = .done * 100 / .total, secs = self.elapsed().as_secs())]
+    Stalled { done: u64, total: u64, millis: u64 },
+    #[error("short by {missing} bytes ({ratio:.1}x)", missing = .expected - .actual, ratio = *.expected as f64 / *.actual as f64)]
+    Short { expected: u64, actual: u64 },
+    #[error("offset {}", -(*.0 as i64) + Point { x: *.1 }.x as i64)]
+    Offset(u64, u64),
+    #[error("{} chunks, {} after the header", Point { x: .0.len() as u64 / 4 }.x, (1...0.len()).len())]
+    Chunks(Vec<u8>),
+    #[error("{total}", total = [.0, .1].iter().map(|n| *n % 10).sum::<u64>())]
+    Digits(u64, u64),
+}
+
+impl TransferError {
+    fn elapsed(&self) -> std::time::Duration {
+        match self {
+            TransferError::Stalled { millis, .. } => std::time::Duration::from_millis(*millis),
+            _ => std::time::Duration::default(),
+        }
+    }
+}
+
+#[test]
+fn test_expression_args() {
+    assert(
+        "stalled at 25% after 3s",
+        TransferError::Stalled {
+            done: 1,
+            total: 4,
+            millis: 3500,
+        },
+    );
+    assert(
+        "short by 2 bytes (1.5x)",
+        TransferError::Short {
+            expected: 6,
+            actual: 4,
+        },
+    );
+    assert("offset -2", TransferError::Offset(5, 3));
+    assert("10", TransferError::Digits(13, 27));
+    assert(
+        "2 chunks, 7 after the header",
+        TransferError::Chunks(vec![0; 8]),
+    );
+}
+
+// Associated items of the error type, named through Self.
+#[derive(Error, Debug)]
+pub enum LimitError {
+    #[error("length {0} exceeds the maximum of {}", Self::MAX)]
+    TooLong(usize),
+    #[error("at most {max} retries", max = Self::MAX_RETRIES)]
+    TooManyRetries,
+    #[error("{} is reserved", Self::describe(*.0))]
+    Reserved(u8),
+}
+
+impl LimitError {
+    const MAX: usize = 255;
+    const MAX_RETRIES: u32 = 3;
+
+    fn describe(byte: u8) -> String {
+        format!("byte {:#04x}", byte)
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("limit of {} reached for {}", Self::LIMIT, .0)]
+pub struct QuotaError<T: Display>(T);
+
+impl<T: Display> QuotaError<T> {
+    const LIMIT: u32 = 10;
+}
+
+#[test]
+fn test_self_associated_items() {
+    assert(
+        "length 300 exceeds the maximum of 255",
+        LimitError::TooLong(300),
+    );
+    assert("at most 3 retries", LimitError::TooManyRetries);
+    assert("byte 0x7f is reserved", LimitError::Reserved(127));
+    assert("limit of 10 reached for uploads", QuotaError("uploads"));
+}
@@ -0,0 +1,67 @@
+use std::fmt::Debug;
+use std::io;
+use std::path::PathBuf;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("failed to read {path:?} at line {line}")]
+#[thiserror(fields)]
+pub struct ReadError {
+    path: PathBuf,
+    line: usize,
+    source: io::Error,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(fields)]
+pub enum RequestError<T: Debug> {
+    #[error("request {id} to {host} failed")]
+    Failed {
+        id: u64,
+        host: String,
+        r#type: T,
+        #[source]
+        cause: io::Error,
+    },
+    #[error("timed out after {0}s")]
+    Timeout(u64),
+    #[error("cancelled")]
+    Cancelled,
+}
+
+fn pairs<'a>(fields: &'a [(&'static str, String)]) -> Vec<(&'static str, &'a str)> {
+    fields
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()))
+        .collect()
+}
+
+#[test]
+fn test_struct() {
+    let error = ReadError {
+        path: PathBuf::from("config.toml"),
+        line: 3,
+        source: io::Error::new(io::ErrorKind::Other, "oh no!"),
+    };
+    assert_eq!(
+        [("path", "\"config.toml\""), ("line", "3")],
+        *pairs(&error.fields()),
+    );
+}
+
+#[test]
+fn test_enum() {
+    let error = RequestError::Failed {
+        id: 7,
+        host: "example.com".to_owned(),
+        r#type: Some('x'),
+        cause: io::Error::new(io::ErrorKind::Other, "oh no!"),
+    };
+    assert_eq!(
+        [("id", "7"), ("host", "example.com"), ("type", "Some('x')")],
+        *pairs(&error.fields()),
+    );
+
+    assert!(RequestError::<()>::Timeout(30).fields().is_empty());
+    assert!(RequestError::<()>::Cancelled.fields().is_empty());
+}
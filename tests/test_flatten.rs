@@ -0,0 +1,64 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::error::Error as _;
+use std::io;
+use std::num::ParseIntError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("failed to read the database")]
+    Io(#[from] io::Error),
+    #[error("corrupt page number")]
+    Page(#[from] ParseIntError),
+    #[error("database is locked")]
+    Locked,
+}
+
+#[derive(Error, Debug)]
+pub enum NetError {
+    #[error("connection refused")]
+    Refused,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(root_cause)]
+pub enum AppError {
+    #[flatten(io::Error, ParseIntError)]
+    Storage(#[from] StorageError),
+    #[flatten]
+    Net(#[from] NetError),
+    #[error("shutting down")]
+    Shutdown,
+}
+
+fn read_page(input: &str) -> Result<u32, AppError> {
+    Ok(input.parse()?)
+}
+
+#[test]
+fn test_skips_wrapper() {
+    let error = AppError::from(io::Error::new(io::ErrorKind::Other, "oh no!"));
+    assert!(matches!(error, AppError::Storage(StorageError::Io(_))));
+    assert_eq!("failed to read the database", error.to_string());
+    assert_eq!("oh no!", error.source().unwrap().to_string());
+    assert_eq!("oh no!", error.root_cause().to_string());
+
+    let error = AppError::from(StorageError::Locked);
+    assert_eq!("database is locked", error.to_string());
+    assert!(error.source().is_none());
+
+    let error = AppError::from(NetError::Refused);
+    assert_eq!("connection refused", error.to_string());
+    assert!(error.source().is_none());
+
+    assert_eq!("shutting down", AppError::Shutdown.to_string());
+}
+
+#[test]
+fn test_listed_from() {
+    let error = read_page("x").unwrap_err();
+    assert!(matches!(error, AppError::Storage(StorageError::Page(_))));
+    assert_eq!("corrupt page number", error.to_string());
+    assert!(error.source().unwrap().is::<ParseIntError>());
+}
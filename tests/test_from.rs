@@ -1,3 +1,5 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
 use std::io;
 use thiserror::Error;
 
@@ -60,3 +62,260 @@ fn test_from() {
     assert_impl::<ErrorEnumOptional>();
     assert_impl::<Many>();
 }
+
+#[derive(Error, Debug, Clone, PartialEq)]
+#[error("parse error at {0}")]
+pub struct ParseError(usize);
+
+#[derive(Error, Debug)]
+pub enum ByRef {
+    #[error("...")]
+    Parse(#[from(ref)] ParseError),
+    #[error("...")]
+    Io {
+        #[from(ref, with = rebuild_io_error)]
+        source: io::Error,
+    },
+}
+
+fn rebuild_io_error(error: &io::Error) -> io::Error {
+    io::Error::new(error.kind(), error.to_string())
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct ByRefGeneric<E: std::error::Error + 'static>(#[from(ref)] E);
+
+#[test]
+fn test_from_ref() {
+    assert_impl::<ByRef>();
+
+    let parse = ParseError(7);
+    match ByRef::from(&parse) {
+        ByRef::Parse(error) => assert_eq!(parse, error),
+        ByRef::Io { .. } => unreachable!(),
+    }
+    match ByRef::from(parse.clone()) {
+        ByRef::Parse(error) => assert_eq!(parse, error),
+        ByRef::Io { .. } => unreachable!(),
+    }
+
+    let io = io::Error::new(io::ErrorKind::NotFound, "oh no!");
+    let error = ByRef::from(&io);
+    let source = std::error::Error::source(&error).unwrap();
+    let source = source.downcast_ref::<io::Error>().unwrap();
+    assert_eq!(io::ErrorKind::NotFound, source.kind());
+    assert_eq!("oh no!", source.to_string());
+
+    let error = ByRefGeneric::from(&parse);
+    assert_eq!(parse, error.0);
+}
+
+#[derive(Error, Debug)]
+pub enum FileError {
+    #[error("not found")]
+    NotFound(#[from(kind = NotFound)] io::Error),
+    #[error("permission denied")]
+    Denied(#[from(kind = PermissionDenied)] io::Error),
+    #[error("already exists")]
+    Exists {
+        #[from(kind = AlreadyExists | AddrInUse)]
+        source: io::Error,
+    },
+    #[error("io error")]
+    Io(#[from] io::Error),
+}
+
+#[test]
+fn test_from_kind() {
+    fn convert(kind: io::ErrorKind) -> FileError {
+        FileError::from(io::Error::new(kind, "oh no!"))
+    }
+
+    assert!(matches!(
+        convert(io::ErrorKind::NotFound),
+        FileError::NotFound(_)
+    ));
+    assert!(matches!(
+        convert(io::ErrorKind::PermissionDenied),
+        FileError::Denied(_)
+    ));
+    assert!(matches!(
+        convert(io::ErrorKind::AlreadyExists),
+        FileError::Exists { .. }
+    ));
+    assert!(matches!(
+        convert(io::ErrorKind::AddrInUse),
+        FileError::Exists { .. }
+    ));
+    assert!(matches!(convert(io::ErrorKind::TimedOut), FileError::Io(_)));
+
+    let error = convert(io::ErrorKind::NotFound);
+    let source = std::error::Error::source(&error).unwrap();
+    assert_eq!("oh no!", source.to_string());
+}
+
+#[derive(Error, Debug)]
+pub enum Narrowing {
+    #[error("parse")]
+    Parse(#[from(try_into)] ParseError),
+    #[error("io")]
+    Io {
+        #[from(ref, try_into, with = rebuild_io_error)]
+        source: Option<io::Error>,
+    },
+    #[error("other")]
+    Other,
+}
+
+#[test]
+fn test_from_try_into() {
+    use std::convert::TryFrom;
+
+    let error = Narrowing::from(ParseError(3));
+    assert_eq!(ParseError(3), ParseError::try_from(error).unwrap());
+
+    let error = Narrowing::Other;
+    let error = ParseError::try_from(error).unwrap_err();
+    assert!(matches!(error, Narrowing::Other));
+
+    let io = io::Error::new(io::ErrorKind::Other, "oh no!");
+    let error = Narrowing::from(&io);
+    assert_eq!("oh no!", io::Error::try_from(error).unwrap().to_string());
+    let error = Narrowing::Io { source: None };
+    assert!(io::Error::try_from(error).is_err());
+}
+
+#[derive(Error, Debug)]
+#[error("{message}")]
+pub struct Message {
+    #[from(into = String)]
+    message: String,
+}
+
+#[derive(Error, Debug)]
+pub enum Loose {
+    #[error("parse error")]
+    Parse(#[from] ParseError),
+    #[error("{0}")]
+    Other(#[from(into = String)] String),
+}
+
+#[test]
+fn test_from_into() {
+    let error = Message::from("invalid header");
+    assert_eq!("invalid header", error.to_string());
+    assert!(std::error::Error::source(&error).is_none());
+
+    let error = Message::from(String::from("invalid body"));
+    assert_eq!("invalid body", error.to_string());
+
+    let error = Loose::from('x');
+    assert_eq!("x", error.to_string());
+    let error = Loose::from(ParseError(1));
+    assert!(std::error::Error::source(&error).is_some());
+}
+
+#[derive(Error, Debug)]
+pub enum Config {
+    #[error("parse error on line {line}")]
+    Parse {
+        #[from(ctor = |source| Self::Parse { source, line: 0 })]
+        source: ParseError,
+        line: usize,
+    },
+    #[error("io error in {path}")]
+    Io {
+        #[from(ctor = Config::unknown_path)]
+        source: io::Error,
+        path: String,
+    },
+}
+
+impl Config {
+    fn unknown_path(source: io::Error) -> Self {
+        Config::Io {
+            source,
+            path: "<unknown>".to_owned(),
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("bad header after {offset} bytes")]
+pub struct HeaderError {
+    #[from(ctor = |source| HeaderError { source, offset: 0 })]
+    source: ParseError,
+    offset: u64,
+}
+
+#[test]
+fn test_from_ctor() {
+    let error = Config::from(ParseError(1));
+    assert_eq!("parse error on line 0", error.to_string());
+    assert!(std::error::Error::source(&error).is_some());
+
+    let error = Config::from(io::Error::new(io::ErrorKind::Other, "oh no!"));
+    assert_eq!("io error in <unknown>", error.to_string());
+
+    let error = HeaderError::from(ParseError(1));
+    assert_eq!("bad header after 0 bytes", error.to_string());
+}
+
+// Server <- Storage <- (DiskError, QuotaError).
+#[derive(Error, Debug)]
+#[error("disk failure")]
+pub struct DiskError;
+
+#[derive(Error, Debug)]
+#[error("quota exceeded")]
+pub struct QuotaError;
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error(transparent)]
+    Disk(#[from] DiskError),
+    #[error(transparent)]
+    Quota(#[from] QuotaError),
+}
+
+#[derive(Error, Debug)]
+pub enum ServerError {
+    #[error("storage unavailable")]
+    Storage(#[from(via = DiskError | QuotaError)] StorageError),
+    #[error("bad request")]
+    BadRequest,
+}
+
+#[derive(Error, Debug)]
+#[error("while handling a request")]
+pub struct RequestError {
+    #[from(via = DiskError)]
+    source: StorageError,
+}
+
+fn write_block() -> Result<(), ServerError> {
+    Err(DiskError)?;
+    Ok(())
+}
+
+#[test]
+fn test_from_via() {
+    let error = ServerError::from(DiskError);
+    assert!(matches!(
+        error,
+        ServerError::Storage(StorageError::Disk(DiskError)),
+    ));
+
+    let error = ServerError::from(QuotaError);
+    assert!(matches!(
+        error,
+        ServerError::Storage(StorageError::Quota(QuotaError)),
+    ));
+
+    let error = write_block().unwrap_err();
+    assert_eq!("storage unavailable", error.to_string());
+
+    let error = RequestError::from(DiskError);
+    assert!(matches!(error.source, StorageError::Disk(DiskError)));
+}
@@ -1,6 +1,10 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
 #![allow(clippy::needless_late_init)]
 
 use std::fmt::{self, Debug, Display};
+use std::marker::PhantomData;
+use std::num::ParseIntError;
+use std::str::FromStr;
 use thiserror::Error;
 
 pub struct NoFormat;
@@ -147,6 +151,152 @@ fn test_enum_source_generic_err_as_ref() {
     });
 }
 
+// Should expand to:
+//
+//     impl<A, E> std::fmt::Display for EnumSourceGenericErrAsRefMixed<A, E>;
+//
+//     impl<A, E> std::error::Error for EnumSourceGenericErrAsRefMixed<A, E>
+//     where
+//         A: AsRef<dyn std::error::Error + 'static>,
+//         E: std::error::Error + 'static,
+//         Self: std::fmt::Debug + std::fmt::Display;
+//
+#[derive(Error, Debug)]
+#[thiserror(generics_err_as_ref)]
+pub enum EnumSourceGenericErrAsRefMixed<A, E> {
+    #[error("enum with an AsRef source")]
+    AsRef(#[source] A),
+    #[error("enum with an Error source")]
+    Error(#[source(err_as_ref = false)] E),
+}
+
+// Should expand to:
+//
+//     impl<A, E> std::fmt::Display for EnumSourceGenericFieldErrAsRef<A, E>;
+//
+//     impl<A, E> std::error::Error for EnumSourceGenericFieldErrAsRef<A, E>
+//     where
+//         A: AsRef<dyn std::error::Error + 'static>,
+//         E: std::error::Error + 'static,
+//         Self: std::fmt::Debug + std::fmt::Display;
+//
+#[derive(Error, Debug)]
+pub enum EnumSourceGenericFieldErrAsRef<A, E> {
+    #[error("enum with an AsRef source")]
+    AsRef(#[source(err_as_ref)] A),
+    #[error("enum with an Error source")]
+    Error(#[source] E),
+}
+
+// Should expand to:
+//
+//     impl<E> From<E> for EnumFromGenericErrAsRef<E>;
+//
+//     impl<E> std::fmt::Display for EnumFromGenericErrAsRef<E>;
+//
+//     impl<E> std::error::Error for EnumFromGenericErrAsRef<E>
+//     where
+//         E: AsRef<dyn std::error::Error + 'static>,
+//         Self: std::fmt::Debug + std::fmt::Display;
+//
+#[derive(Error, Debug)]
+#[thiserror(generics_err_as_ref)]
+pub enum EnumFromGenericErrAsRef<E> {
+    #[error("enum with a generic source")]
+    Source(#[from] E),
+}
+
+// Should expand to:
+//
+//     impl<E> From<E> for StructFromGenericErrAsRef<E>;
+//
+//     impl<E> std::fmt::Display for StructFromGenericErrAsRef<E>;
+//
+//     impl<E> std::error::Error for StructFromGenericErrAsRef<E>
+//     where
+//         E: AsRef<dyn std::error::Error + 'static>,
+//         Self: std::fmt::Debug + std::fmt::Display;
+//
+#[derive(Error, Debug)]
+#[thiserror(generics_err_as_ref)]
+#[error("struct with a generic source")]
+pub struct StructFromGenericErrAsRef<E> {
+    #[from]
+    source: E,
+}
+
+#[test]
+fn test_enum_from_generic_err_as_ref() {
+    #[derive(Debug)]
+    struct SourceError {
+        inner: EnumDebugGeneric<u64>,
+    }
+
+    impl AsRef<dyn std::error::Error + 'static> for SourceError {
+        fn as_ref(&self) -> &(dyn std::error::Error + 'static) {
+            &self.inner
+        }
+    }
+
+    let err_with_generic_source = EnumFromGenericErrAsRef::from(SourceError {
+        inner: EnumDebugGeneric::FatalError(100),
+    });
+
+    assert!(match &err_with_generic_source {
+        EnumFromGenericErrAsRef::Source(source_err) => source_err.inner.to_string() == "100",
+    });
+
+    let err: &dyn std::error::Error = &err_with_generic_source;
+
+    assert!(match err.source() {
+        Some(err_source) => err_source.to_string() == "100",
+        None => false,
+    });
+
+    let err = StructFromGenericErrAsRef::from(SourceError {
+        inner: EnumDebugGeneric::FatalError(200),
+    });
+    let err: &dyn std::error::Error = &err;
+    assert_eq!("200", err.source().unwrap().to_string());
+}
+
+#[test]
+fn test_enum_source_generic_err_as_ref_mixed() {
+    #[derive(Debug)]
+    struct SourceError {
+        inner: EnumDebugGeneric<u64>,
+    }
+
+    impl AsRef<dyn std::error::Error + 'static> for SourceError {
+        fn as_ref(&self) -> &(dyn std::error::Error + 'static) {
+            &self.inner
+        }
+    }
+
+    let error =
+        EnumSourceGenericErrAsRefMixed::<SourceError, EnumDebugGeneric<u64>>::AsRef(SourceError {
+            inner: EnumDebugGeneric::FatalError(100),
+        });
+    let error: &dyn std::error::Error = &error;
+    assert_eq!("100", error.source().unwrap().to_string());
+
+    let error =
+        EnumSourceGenericErrAsRefMixed::<SourceError, _>::Error(EnumDebugGeneric::FatalError(200));
+    let error: &dyn std::error::Error = &error;
+    assert_eq!("200", error.source().unwrap().to_string());
+
+    let error = EnumSourceGenericFieldErrAsRef::<_, EnumDebugGeneric<u64>>::AsRef(SourceError {
+        inner: EnumDebugGeneric::FatalError(300),
+    });
+    let error: &dyn std::error::Error = &error;
+    assert_eq!("300", error.source().unwrap().to_string());
+
+    let error =
+        EnumSourceGenericFieldErrAsRef::<SourceError, _>::Error(EnumDebugGeneric::FatalError(400));
+    let error: &dyn std::error::Error = &error;
+    assert_eq!("400", error.source().unwrap().to_string());
+}
+
 // Should expand to:
 //
 //     impl<HasDisplay, HasDebug, HasNeither> Display
@@ -250,3 +400,541 @@ pub struct StructFromGeneric<E> {
 #[derive(Error, Debug)]
 #[error(transparent)]
 pub struct StructTransparentGeneric<E>(E);
+
+// Should expand to:
+//
+//     impl<E> From<E> for EnumFromBareGeneric<E>;
+//
+//     impl<E> Error for EnumFromBareGeneric<E>
+//     where
+//         E: Error + 'static,
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+pub enum EnumFromBareGeneric<E> {
+    #[error("wrapped")]
+    Inner(#[from] E),
+    #[error("other")]
+    Other,
+}
+
+#[test]
+fn test_enum_from_bare_generic() {
+    let error = EnumFromBareGeneric::from(std::fmt::Error);
+    assert!(match error {
+        EnumFromBareGeneric::Inner(std::fmt::Error) => true,
+        EnumFromBareGeneric::Other => false,
+    });
+    let error: &dyn std::error::Error = &error;
+    assert!(error.source().unwrap().is::<std::fmt::Error>());
+}
+
+// Should expand to:
+//
+//     impl<E> Display for StructWhereClause<E>
+//     where
+//         E: Clone + Default,
+//         E: Display;
+//
+//     impl<E> Error for StructWhereClause<E>
+//     where
+//         E: Clone + Default,
+//         E: Error + 'static,
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+#[error("{source} ({count})")]
+pub struct StructWhereClause<E>
+where
+    E: Clone + Default,
+{
+    source: E,
+    count: usize,
+}
+
+// Should expand to:
+//
+//     impl<E> From<E> for EnumWhereClause<E>
+//     where
+//         E: Clone + Default;
+//
+//     impl<E> Display for EnumWhereClause<E>
+//     where
+//         E: Clone + Default,
+//         E: Debug;
+//
+//     impl<E> Error for EnumWhereClause<E>
+//     where
+//         E: Clone + Default,
+//         E: Error + 'static,
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+#[thiserror(variants)]
+pub enum EnumWhereClause<E>
+where
+    E: Clone + Default,
+{
+    #[error("wrapped {0:?}")]
+    Inner(#[from] E),
+    #[error("default {:?}", E::default())]
+    Default,
+}
+
+#[derive(Error, Clone, Default, Debug)]
+#[error("cloneable")]
+pub struct Cloneable;
+
+#[test]
+fn test_where_clause() {
+    let error = StructWhereClause {
+        source: Cloneable,
+        count: 1,
+    };
+    assert_eq!("cloneable (1)", error.to_string());
+    let error: &dyn std::error::Error = &error;
+    assert!(error.source().unwrap().is::<Cloneable>());
+
+    let error = EnumWhereClause::from(Cloneable);
+    assert_eq!("wrapped Cloneable", error.to_string());
+    let error: &dyn std::error::Error = &error;
+    assert!(error.source().unwrap().is::<Cloneable>());
+    assert_eq!(
+        "default Cloneable",
+        EnumWhereClause::<Cloneable>::Default.to_string(),
+    );
+    assert_eq!(["Inner", "Default"], EnumWhereClause::<Cloneable>::VARIANTS);
+}
+
+fn render<T: Debug>(value: &T) -> String {
+    format!("<{:?}>", value)
+}
+
+// Should expand to:
+//
+//     impl<T> Display for StructDisplayBound<T>
+//     where
+//         T: Debug;
+//
+#[derive(Error, Debug)]
+#[error("rendered {}", render(&self.0))]
+#[error(display_bound(T: Debug))]
+pub struct StructDisplayBound<T>(T);
+
+// Should expand to:
+//
+//     impl<E> Display for EnumErrorBound<E>
+//     where
+//         E: Display;
+//
+//     impl<E> Error for EnumErrorBound<E>
+//     where
+//         E: Error + Send + Sync + 'static,
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+pub enum EnumErrorBound<E> {
+    #[error("io failed: {0}")]
+    #[error(error_bound(E: std::error::Error + Send + Sync + 'static))]
+    Io(#[source] E),
+    #[error("closed")]
+    Closed,
+}
+
+// Should expand to:
+//
+//     impl<T> Display for StructBound<T>
+//     where
+//         T: Display + Send;
+//
+//     impl<T> Error for StructBound<T>
+//     where
+//         T: Display + Send,
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+#[error("{0}")]
+#[error(bound(T: Display + Send))]
+pub struct StructBound<T>(T);
+
+#[test]
+fn test_explicit_bounds() {
+    fn assert_error<E: std::error::Error + Send + Sync + 'static>(_: &E) {}
+
+    let error = StructDisplayBound(DebugOnly);
+    assert_eq!("rendered <DebugOnly>", error.to_string());
+    assert_error(&error);
+
+    let error = EnumErrorBound::Io(std::io::Error::new(std::io::ErrorKind::Other, "eof"));
+    assert_eq!("io failed: eof", error.to_string());
+    assert_error(&error);
+    let error: &dyn std::error::Error = &error;
+    assert!(error.source().unwrap().is::<std::io::Error>());
+
+    let error = StructBound(DisplayOnly);
+    assert_eq!("display only", error.to_string());
+}
+
+fn describe<T>(_: &T) -> &'static str {
+    "opaque"
+}
+
+// Should expand to:
+//
+//     impl<T> Display for StructNoBound<T>;
+//
+//     impl<T> Error for StructNoBound<T>
+//     where
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+#[error("{value} after {attempts} attempts", value = describe(&self.value))]
+pub struct StructNoBound<T> {
+    #[error(no_bound)]
+    value: T,
+    attempts: usize,
+}
+
+// Should expand to:
+//
+//     impl<T> Display for EnumNoBound<T>
+//     where
+//         T: Display;
+//
+//     impl<T> Error for EnumNoBound<T>
+//     where
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+pub enum EnumNoBound<T> {
+    #[error("{value}", value = describe(value))]
+    Opaque {
+        #[error(no_bound)]
+        value: T,
+    },
+    #[error("{0}")]
+    Shown(T),
+}
+
+#[test]
+fn test_no_bound() {
+    let error = StructNoBound {
+        value: NoFormat,
+        attempts: 3,
+    };
+    assert_eq!("opaque after 3 attempts", error.to_string());
+
+    let error = EnumNoBound::Opaque { value: DisplayOnly };
+    assert_eq!("opaque", error.to_string());
+    assert_eq!("display only", EnumNoBound::Shown(DisplayOnly).to_string(),);
+}
+
+pub trait Adapter: std::error::Error + 'static {}
+
+impl Adapter for ParseIntError {}
+
+// Should expand to:
+//
+//     impl<E> Display for StructSourceNoBound<E>
+//     where
+//         E: Adapter;
+//
+//     impl<E> Error for StructSourceNoBound<E>
+//     where
+//         E: Adapter,
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+#[error("adapted")]
+pub struct StructSourceNoBound<E>
+where
+    E: Adapter,
+{
+    #[source(no_bound)]
+    source: E,
+}
+
+// Should expand to:
+//
+//     impl<E: Adapter> Display for EnumSourceNoBound<E>
+//     where
+//         E: Display;
+//
+//     impl<E: Adapter> Error for EnumSourceNoBound<E>
+//     where
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+pub enum EnumSourceNoBound<E: Adapter> {
+    #[error("adapted: {0}")]
+    Adapted(#[source(no_bound)] E),
+}
+
+#[test]
+fn test_source_no_bound() {
+    let error = StructSourceNoBound {
+        source: "x".parse::<u8>().unwrap_err(),
+    };
+    assert_eq!("adapted", error.to_string());
+    assert!(std::error::Error::source(&error).is_some());
+
+    let error = EnumSourceNoBound::Adapted("x".parse::<u8>().unwrap_err());
+    assert_eq!("adapted: invalid digit found in string", error.to_string(),);
+    assert!(std::error::Error::source(&error).is_some());
+}
+
+// Should expand to:
+//
+//     impl<B> Display for StructPhantom<B>;
+//
+//     impl<B> Error for StructPhantom<B>
+//     where
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+#[error("key {key} missing from shard {shard:?}")]
+pub struct StructPhantom<B> {
+    key: String,
+    shard: (PhantomData<B>, usize),
+    source: PhantomData<fn() -> B>,
+}
+
+// Should expand to:
+//
+//     impl<B> Display for EnumPhantom<B>;
+//
+//     impl<B> Error for EnumPhantom<B>
+//     where
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+pub enum EnumPhantom<B> {
+    #[error("closed {0:?}")]
+    Closed(PhantomData<B>),
+    #[error("busy")]
+    Busy {
+        source: PhantomData<B>,
+        backtrace: PhantomData<fn() -> B>,
+    },
+}
+
+#[test]
+fn test_phantom_data() {
+    let error = StructPhantom::<DebugOnly> {
+        key: "k".to_owned(),
+        shard: (PhantomData, 2),
+        source: PhantomData,
+    };
+    assert_eq!(
+        "key k missing from shard (PhantomData<test_generics::DebugOnly>, 2)",
+        error.to_string(),
+    );
+    assert!(std::error::Error::source(&error).is_none());
+
+    let error = EnumPhantom::<DebugOnly>::Busy {
+        source: PhantomData,
+        backtrace: PhantomData,
+    };
+    assert_eq!("busy", error.to_string());
+    assert!(std::error::Error::source(&error).is_none());
+}
+
+// Should expand to:
+//
+//     impl<T: FromStr> Display for StructAssocSource<T>;
+//
+//     impl<T: FromStr> Error for StructAssocSource<T>
+//     where
+//         T::Err: Error + 'static,
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+#[error("failed to parse {input:?}")]
+pub struct StructAssocSource<T: FromStr> {
+    #[source]
+    source: T::Err,
+    input: String,
+}
+
+// Should expand to:
+//
+//     impl<T: FromStr> Display for EnumAssocField<T>
+//     where
+//         <T as FromStr>::Err: Display;
+//
+//     impl<T: FromStr> Error for EnumAssocField<T>
+//     where
+//         T::Err: Error + 'static,
+//         Box<T::Err>: Error + 'static,
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+pub enum EnumAssocField<T: FromStr> {
+    #[error("invalid: {0}")]
+    Invalid(<T as FromStr>::Err),
+    #[error("maybe invalid")]
+    Maybe(#[source] Option<T::Err>),
+    #[error("boxed invalid")]
+    Boxed(#[source] Box<T::Err>),
+}
+
+#[test]
+fn test_assoc_type_field() {
+    let error = StructAssocSource::<u8> {
+        source: "x".parse::<u8>().unwrap_err(),
+        input: "x".to_owned(),
+    };
+    assert_eq!("failed to parse \"x\"", error.to_string());
+    let source = std::error::Error::source(&error).unwrap();
+    assert!(source.is::<ParseIntError>());
+
+    let error = EnumAssocField::<u8>::Invalid("".parse::<u8>().unwrap_err());
+    assert_eq!(
+        "invalid: cannot parse integer from empty string",
+        error.to_string(),
+    );
+
+    let error = EnumAssocField::<u8>::Maybe(None);
+    assert!(std::error::Error::source(&error).is_none());
+
+    let error = EnumAssocField::<u8>::Boxed(Box::new("-".parse::<u8>().unwrap_err()));
+    let source = std::error::Error::source(&error).unwrap();
+    assert_eq!("invalid digit found in string", source.to_string());
+}
+
+// Should expand to:
+//
+//     impl<'a, T, E> Display for StructWhereDefault<'a, T, E>
+//     where
+//         T: Clone + Send + std::fmt::Display;
+//
+//     impl<'a, T, E> Error for StructWhereDefault<'a, T, E>
+//     where
+//         T: Clone + Send + std::fmt::Display,
+//         E: std::error::Error + 'static,
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+#[error("{name}: {value}")]
+pub struct StructWhereDefault<'a, T, E = std::io::Error>
+where
+    T: Clone + Send + std::fmt::Display,
+{
+    name: &'a str,
+    value: T,
+    #[source]
+    source: E,
+}
+
+// Should expand to:
+//
+//     impl<'a, T: Clone, E> From<E> for EnumWhereDefault<'a, T, E>
+//     where
+//         T: Send,
+//         E: std::error::Error + 'static;
+//
+//     impl<'a, T: Clone, E> Display for EnumWhereDefault<'a, T, E>
+//     where
+//         T: Send,
+//         E: std::error::Error + 'static,
+//         T: Display,
+//         E: Display;
+//
+//     impl<'a, T: Clone, E> Error for EnumWhereDefault<'a, T, E>
+//     where
+//         T: Send,
+//         E: std::error::Error + 'static,
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+pub enum EnumWhereDefault<'a, T: Clone, E = std::io::Error>
+where
+    T: Send,
+    E: std::error::Error + 'static,
+{
+    #[error("{0}: {1}")]
+    Value(&'a str, T),
+    #[error("io")]
+    Io(#[from] E),
+    #[error("{0}")]
+    Shown(#[source] E),
+}
+
+#[test]
+fn test_where_clause_and_default() {
+    let io = || std::io::Error::new(std::io::ErrorKind::Other, "oh no");
+
+    let error: StructWhereDefault<&str> = StructWhereDefault {
+        name: "load",
+        value: "cache",
+        source: io(),
+    };
+    assert_eq!("load: cache", error.to_string());
+    assert!(std::error::Error::source(&error).is_some());
+
+    let error: EnumWhereDefault<&str> = EnumWhereDefault::from(io());
+    assert_eq!("io", error.to_string());
+    let error: EnumWhereDefault<&str> = EnumWhereDefault::Value("v", "cache");
+    assert_eq!("v: cache", error.to_string());
+}
+
+#[allow(clippy::incompatible_msrv)]
+fn type_name<T>() -> &'static str {
+    std::any::type_name::<T>()
+}
+
+// Should expand to:
+//
+//     impl<T> Display for StructTypeName<T>;
+//
+//     impl<T> Error for StructTypeName<T>
+//     where
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+#[error("parse failed for {ty} at {offset}", ty = type_name::<T>())]
+pub struct StructTypeName<T> {
+    offset: usize,
+    marker: PhantomData<T>,
+}
+
+#[test]
+fn test_type_name() {
+    let error = StructTypeName::<NoFormat> {
+        offset: 3,
+        marker: PhantomData,
+    };
+    assert_eq!(
+        "parse failed for test_generics::NoFormat at 3",
+        error.to_string(),
+    );
+}
+
+// Should expand to:
+//
+//     impl<const N: usize> Display for StructConstParam<N>;
+//
+//     impl<const N: usize> Error for StructConstParam<N>
+//     where
+//         Self: Debug + Display;
+//
+#[derive(Error, Debug)]
+#[error("expected {N} bytes, got {got} (short by {})", N - .got.min(&N))]
+pub struct StructConstParam<const N: usize> {
+    got: usize,
+}
+
+#[derive(Error, Debug)]
+pub enum EnumConstParam<const N: usize, const TAG: char> {
+    #[error("{TAG:?}: expected at most {N}, got {0}")]
+    Long(usize),
+}
+
+#[test]
+fn test_const_param() {
+    let error = StructConstParam::<4> { got: 1 };
+    assert_eq!("expected 4 bytes, got 1 (short by 3)", error.to_string());
+
+    let error = EnumConstParam::<8, 'x'>::Long(9);
+    assert_eq!("'x': expected at most 8, got 9", error.to_string());
+}
@@ -0,0 +1,123 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(hash_ignore_source)]
+pub enum LintError {
+    #[error("unused variable `{name}` at line {line}")]
+    UnusedVariable { name: String, line: u32 },
+    #[error("failed to read {0}")]
+    Read(String, #[source] io::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("aborted")]
+    Aborted,
+}
+
+impl PartialEq for LintError {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                LintError::UnusedVariable { name, line },
+                LintError::UnusedVariable {
+                    name: other_name,
+                    line: other_line,
+                },
+            ) => name == other_name && line == other_line,
+            (LintError::Read(path, _), LintError::Read(other_path, _)) => path == other_path,
+            (LintError::Io(_), LintError::Io(_)) | (LintError::Aborted, LintError::Aborted) => true,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for LintError {}
+
+#[derive(Error, Debug)]
+#[error("{file}: {message}")]
+#[thiserror(hash_ignore_source)]
+pub struct Diagnostic<T> {
+    file: T,
+    message: &'static str,
+    source: io::Error,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(eq, hash_ignore_source)]
+pub enum Severity {
+    #[error("warning")]
+    Warning,
+    #[error("error")]
+    Error,
+}
+
+fn hash<T: Hash>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn other(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, message)
+}
+
+#[test]
+fn test_hash_set() {
+    let mut set = HashSet::new();
+    set.insert(LintError::UnusedVariable {
+        name: "x".to_owned(),
+        line: 1,
+    });
+    set.insert(LintError::UnusedVariable {
+        name: "x".to_owned(),
+        line: 1,
+    });
+    set.insert(LintError::UnusedVariable {
+        name: "x".to_owned(),
+        line: 2,
+    });
+    set.insert(LintError::Read("a.rs".to_owned(), other("denied")));
+    set.insert(LintError::Read("a.rs".to_owned(), other("busy")));
+    set.insert(LintError::Io(other("reset")));
+    set.insert(LintError::Io(other("closed")));
+    set.insert(LintError::Aborted);
+    assert_eq!(5, set.len());
+}
+
+#[test]
+fn test_ignores_source() {
+    let a = Diagnostic {
+        file: "main.rs",
+        message: "unreadable",
+        source: other("denied"),
+    };
+    let b = Diagnostic {
+        file: "main.rs",
+        message: "unreadable",
+        source: other("busy"),
+    };
+    assert_eq!(hash(&a), hash(&b));
+
+    let c = Diagnostic {
+        file: "lib.rs",
+        message: "unreadable",
+        source: other("denied"),
+    };
+    assert_ne!(hash(&a), hash(&c));
+}
+
+#[test]
+fn test_consistent_with_eq() {
+    assert_eq!(hash(&Severity::Warning), hash(&Severity::Warning));
+    assert_ne!(hash(&Severity::Warning), hash(&Severity::Error));
+
+    let set: HashSet<_> = vec![Severity::Error, Severity::Warning, Severity::Error]
+        .into_iter()
+        .collect();
+    assert_eq!(2, set.len());
+}
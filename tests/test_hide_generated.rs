@@ -0,0 +1,40 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(hide_generated, setters, downcast, fields)]
+#[error("failed to load {path}")]
+pub struct LoadError {
+    path: String,
+    #[source]
+    source: Option<io::Error>,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(hide_generated, variants, static_messages)]
+pub enum FlashError {
+    #[error("flash is write-protected")]
+    WriteProtected,
+    #[error("erase timed out")]
+    Timeout { sector: u32 },
+}
+
+#[test]
+fn test_struct_methods() {
+    let error = LoadError {
+        path: "config.toml".to_owned(),
+        source: None,
+    }
+    .with_source(io::Error::new(io::ErrorKind::NotFound, "missing"));
+    assert!(error.downcast_source::<io::Error>().is_some());
+    assert_eq!(vec![("path", "config.toml".to_owned())], error.fields(),);
+}
+
+#[test]
+fn test_enum_methods() {
+    assert_eq!(&["WriteProtected", "Timeout"], FlashError::VARIANTS);
+    assert_eq!(
+        "erase timed out",
+        FlashError::Timeout { sector: 1 }.message()
+    );
+}
@@ -0,0 +1,131 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::error::Error as _;
+use std::io;
+
+#[no_implicit_prelude]
+mod no_prelude {
+    use ::std::fmt::{Debug, Display};
+    use ::std::marker::{Send, Sync};
+    use ::std::string::String;
+    use ::std::vec::Vec;
+    use ::thiserror::{self, Error};
+
+    #[derive(Error, Debug)]
+    #[thiserror(context, predicates)]
+    pub enum ReadError {
+        #[error("failed to read {path}")]
+        Read {
+            path: String,
+            source: ::std::io::Error,
+        },
+    }
+
+    #[derive(Error, Debug)]
+    pub enum LoadError<T: Debug + Display + 'static> {
+        #[error(transparent)]
+        Read(#[from] ReadError),
+        #[error(transparent)]
+        Parse(#[from] ::std::num::ParseIntError),
+        #[error("bad value {0}")]
+        Value(T, #[source] ::std::fmt::Error),
+        #[error("{0:?} missing")]
+        Missing(::std::option::Option<T>),
+        #[error("unknown keys {keys:join}")]
+        Unknown { keys: Vec<String> },
+    }
+
+    #[derive(Error, Debug)]
+    #[error("sync of {name} failed")]
+    pub struct SyncError {
+        pub name: &'static str,
+        pub source: ::std::io::Error,
+    }
+
+    #[derive(Error, Debug)]
+    #[error(transparent)]
+    pub struct Opaque(#[from] pub ::std::boxed::Box<dyn ::std::error::Error + Send + Sync>);
+}
+
+macro_rules! error_type {
+    ($name:ident { $field:ident: $ty:ty } => $message:literal) => {
+        #[derive(thiserror::Error, Debug)]
+        #[error($message)]
+        pub struct $name {
+            $field: $ty,
+        }
+    };
+    ($name:ident { $($variant:ident($field:ident: $ty:ty),)* }) => {
+        #[derive(thiserror::Error, Debug)]
+        pub enum $name {
+            $(
+                #[error("{} is invalid: {}", stringify!($variant), $field)]
+                $variant { $field: $ty },
+            )*
+        }
+    };
+}
+
+// The message is written in the macro and the field name is pasted in by the
+// caller, so the two come from different hygiene contexts.
+macro_rules! missing_error {
+    ($name:ident, $field:ident) => {
+        #[derive(thiserror::Error, Debug)]
+        #[error("missing {field}")]
+        pub struct $name {
+            $field: &'static str,
+        }
+    };
+}
+
+error_type!(PortError { port: u16 } => "port {port} is taken");
+error_type!(ConfigError {
+    Host(host: String),
+    Retries(retries: u32),
+});
+missing_error!(MissingError, field);
+
+#[test]
+fn test_no_implicit_prelude() {
+    use no_prelude::{LoadError, Opaque, ReadErrorContext as _, SyncError};
+
+    let error = Err::<(), _>(io::Error::new(io::ErrorKind::Other, "denied"))
+        .context(no_prelude::ReadCtx { path: "a.toml" })
+        .unwrap_err();
+    assert!(error.is_read());
+    assert_eq!("failed to read a.toml", error.to_string());
+    assert_eq!("denied", error.source().unwrap().to_string());
+    let error = LoadError::<u8>::from(error);
+    assert_eq!("failed to read a.toml", error.to_string());
+
+    let error = LoadError::<u8>::from("x".parse::<u8>().unwrap_err());
+    assert_eq!("invalid digit found in string", error.to_string());
+    assert_eq!(
+        "bad value 7",
+        LoadError::Value(7, std::fmt::Error).to_string()
+    );
+    assert_eq!("None missing", LoadError::<u8>::Missing(None).to_string());
+    let error = LoadError::<u8>::Unknown {
+        keys: vec!["a".to_owned(), "b".to_owned()],
+    };
+    assert_eq!("unknown keys a, b", error.to_string());
+
+    let error = SyncError {
+        name: "origin",
+        source: io::Error::new(io::ErrorKind::Other, "reset"),
+    };
+    assert_eq!("sync of origin failed", error.to_string());
+    assert_eq!("oh no", Opaque::from(Box::from("oh no")).to_string());
+}
+
+#[test]
+fn test_macro_rules() {
+    assert_eq!("port 80 is taken", PortError { port: 80 }.to_string());
+    let error = ConfigError::Host {
+        host: "example".to_owned(),
+    };
+    assert_eq!("Host is invalid: example", error.to_string());
+    let error = ConfigError::Retries { retries: 3 };
+    assert_eq!("Retries is invalid: 3", error.to_string());
+    assert_eq!("missing token", MissingError { field: "token" }.to_string());
+}
@@ -0,0 +1,52 @@
+use std::collections::HashSet;
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(kind)]
+pub enum StoreError {
+    #[error("failed to read {0}")]
+    #[error(kind = Io)]
+    Read(String, #[source] io::Error),
+    #[error("failed to write {0}")]
+    #[error(kind = Io)]
+    Write(String, #[source] io::Error),
+    #[error("key {key} not found")]
+    NotFound { key: String },
+    #[error("store is read-only")]
+    ReadOnly,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(kind = "FrobKind")]
+pub enum FrobError<T> {
+    #[error("cannot frob {0}")]
+    Unfrobbable(T),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(kind)]
+pub enum Void {}
+
+#[test]
+fn test_kind() {
+    let io = || io::Error::new(io::ErrorKind::Other, "oh no!");
+    let error = StoreError::Read("a".to_owned(), io());
+    assert_eq!(StoreErrorKind::Io, error.kind());
+    let error = StoreError::Write("a".to_owned(), io());
+    assert_eq!(StoreErrorKind::Io, error.kind());
+    let error = StoreError::NotFound {
+        key: "a".to_owned(),
+    };
+    assert_eq!(StoreErrorKind::NotFound, error.kind());
+    assert_eq!(StoreErrorKind::ReadOnly, StoreError::ReadOnly.kind());
+    assert_eq!("Io", format!("{:?}", StoreErrorKind::Io));
+
+    let kinds: HashSet<StoreErrorKind> = [StoreErrorKind::Io, StoreErrorKind::ReadOnly]
+        .iter()
+        .cloned()
+        .collect();
+    assert!(kinds.contains(&StoreErrorKind::Io));
+
+    assert_eq!(FrobKind::Unfrobbable, FrobError::Unfrobbable(1).kind());
+}
@@ -0,0 +1,41 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+#![allow(deprecated)]
+
+use std::error::Error as _;
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(legacy_description)]
+pub enum Error {
+    #[error("failed to open {0}")]
+    Open(String),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("interrupted")]
+    r#Interrupted,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(legacy_description)]
+#[error("timed out after {0} ms")]
+pub struct Timeout(u64);
+
+#[derive(Error, Debug)]
+#[thiserror(legacy_description)]
+pub enum Never {}
+
+#[test]
+fn test_description() {
+    assert_eq!("Open", Error::Open("a.txt".to_owned()).description());
+    let io = io::Error::new(io::ErrorKind::Other, "oh no!");
+    assert_eq!("Io", Error::Io(io).description());
+    assert_eq!("Interrupted", Error::Interrupted.description());
+    assert_eq!("Timeout", Timeout(30).description());
+}
+
+#[test]
+fn test_dyn_description() {
+    let error: Box<dyn std::error::Error> = Box::new(Timeout(30));
+    assert_eq!("Timeout", error.description());
+}
@@ -0,0 +1,109 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::error::Error as _;
+use std::io;
+use std::marker::PhantomData;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("bad token {token}")]
+pub struct LexError<'src> {
+    token: &'src str,
+}
+
+#[derive(Error, Debug)]
+#[error("bad token {token} in {file}")]
+pub struct SpanError<'src, 'file> {
+    token: &'src str,
+    file: &'file str,
+    #[source]
+    source: io::Error,
+}
+
+#[derive(Error, Debug)]
+#[error("failed to read")]
+pub struct ReadError<'a> {
+    #[from]
+    source: io::Error,
+    buffer: PhantomData<&'a [u8]>,
+}
+
+#[derive(Error, Debug)]
+pub enum ParseError<'src> {
+    #[error("unexpected {0:?}")]
+    Unexpected(&'src str),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+pub enum ResolveError<'src, 'env> {
+    #[error("unknown name {name} in {scope}")]
+    Unknown { name: &'src str, scope: &'env str },
+    #[error("failed to load {path}")]
+    Load {
+        path: &'env str,
+        #[source]
+        source: io::Error,
+    },
+    #[error("interrupted")]
+    Interrupted(#[from] io::Error),
+}
+
+fn io_error() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "oh no")
+}
+
+#[test]
+fn test_struct() {
+    let text = String::from("let x = @");
+    let error = LexError { token: &text[8..] };
+    assert_eq!("bad token @", error.to_string());
+    assert!(error.source().is_none());
+
+    let file = String::from("main.rs");
+    let error = SpanError {
+        token: &text[..3],
+        file: &file,
+        source: io_error(),
+    };
+    assert_eq!("bad token let in main.rs", error.to_string());
+    assert_eq!("oh no", error.source().unwrap().to_string());
+}
+
+#[test]
+fn test_struct_from() {
+    let error = ReadError::from(io_error());
+    assert_eq!("failed to read", error.to_string());
+    assert!(error.source().unwrap().is::<io::Error>());
+}
+
+#[test]
+fn test_enum() {
+    let text = String::from("@");
+    let error = ParseError::Unexpected(&text);
+    assert_eq!("unexpected \"@\"", error.to_string());
+    assert!(error.source().is_none());
+
+    let error: ParseError = io_error().into();
+    assert_eq!("oh no", error.to_string());
+
+    let name = String::from("x");
+    let scope = String::from("main");
+    let error = ResolveError::Unknown {
+        name: &name,
+        scope: &scope,
+    };
+    assert_eq!("unknown name x in main", error.to_string());
+    assert!(error.source().is_none());
+
+    let error = ResolveError::Load {
+        path: &scope,
+        source: io_error(),
+    };
+    assert!(error.source().is_some());
+
+    let error: ResolveError = io_error().into();
+    assert_eq!("interrupted", error.to_string());
+    assert!(error.source().is_some());
+}
@@ -0,0 +1,103 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::io;
+use thiserror::Error;
+
+pub trait Localizer {
+    fn message(&self, code: u32, args: &[(&str, String)]) -> String;
+}
+
+pub trait LegacyLocalizer {
+    fn message(&self, code: i64, args: &[(&str, String)]) -> String;
+}
+
+#[derive(Error, Debug)]
+#[thiserror(codes, localize = crate::Localizer)]
+pub enum UploadError {
+    #[error("file {name} is {size} bytes too large")]
+    #[error(code = 1)]
+    TooLarge { name: String, size: u64 },
+    #[error("quota of {0} files reached")]
+    #[error(code = 2)]
+    Quota(u32),
+    #[error("failed to write {path}")]
+    #[error(code = 3)]
+    Write { path: String, source: io::Error },
+    #[error("cancelled")]
+    #[error(code = 4)]
+    Cancelled,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(discriminant_code, localize = LegacyLocalizer)]
+pub enum LookupError<K> {
+    #[error("no entry for {0:?}")]
+    #[error(code = 404)]
+    Missing(K),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(codes, localize = Localizer)]
+pub enum Never {}
+
+// Returns the French message for each code, with `{name}` replaced by the value
+// of the argument of that name.
+struct French;
+
+impl Localizer for French {
+    fn message(&self, code: u32, args: &[(&str, String)]) -> String {
+        let template = match code {
+            1 => "le fichier {name} dépasse de {size} octets",
+            2 => "quota de {0} fichiers atteint",
+            3 => "échec de l'écriture de {path}",
+            4 => "annulé",
+            _ => unreachable!(),
+        };
+        fill(template, args)
+    }
+}
+
+impl LegacyLocalizer for French {
+    fn message(&self, code: i64, args: &[(&str, String)]) -> String {
+        assert_eq!(404, code);
+        fill("aucune entrée pour {0}", args)
+    }
+}
+
+fn fill(template: &str, args: &[(&str, String)]) -> String {
+    let mut message = template.to_owned();
+    for (name, value) in args {
+        message = message.replace(&format!("{{{}}}", name), value);
+    }
+    message
+}
+
+#[test]
+fn test_localized() {
+    let error = UploadError::TooLarge {
+        name: "a.png".to_owned(),
+        size: 10,
+    };
+    assert_eq!("file a.png is 10 bytes too large", error.to_string());
+    assert_eq!(
+        "le fichier a.png dépasse de 10 octets",
+        error.localized(&French)
+    );
+
+    let error = UploadError::Quota(5);
+    assert_eq!("quota de 5 fichiers atteint", error.localized(&French));
+
+    let error = UploadError::Write {
+        path: "/tmp/a".to_owned(),
+        source: io::Error::new(io::ErrorKind::Other, "disk full"),
+    };
+    assert_eq!("échec de l'écriture de /tmp/a", error.localized(&French));
+
+    assert_eq!("annulé", UploadError::Cancelled.localized(&French));
+}
+
+#[test]
+fn test_localized_generic() {
+    let error = LookupError::Missing(vec![1, 2]);
+    assert_eq!("aucune entrée pour [1, 2]", error.localized(&French));
+}
@@ -0,0 +1,100 @@
+use std::error::Error as StdError;
+use std::fmt::Display;
+use thiserror::Error;
+
+// Neither needs Debug, which only the Error impl requires.
+#[derive(Error)]
+#[thiserror(no_error_impl)]
+pub enum Progress {
+    #[error("downloaded {done} of {total} bytes")]
+    Downloading { done: u64, total: u64 },
+    #[error("verifying {0}")]
+    Verifying(String),
+    #[error("done")]
+    Done,
+}
+
+#[derive(Error)]
+#[thiserror(no_error_impl)]
+#[error("status of {name}: {status}")]
+pub struct Status<T> {
+    name: &'static str,
+    status: T,
+}
+
+// An error that implements Error by hand, with only its message derived.
+#[derive(Error, Debug)]
+#[thiserror(no_error_impl)]
+#[error("request {id} failed")]
+pub struct RequestError {
+    id: u32,
+    cause: std::io::Error,
+}
+
+impl StdError for RequestError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        Some(&self.cause)
+    }
+}
+
+fn assert<T: Display>(expected: &str, value: T) {
+    assert_eq!(expected, value.to_string());
+}
+
+// Resolves to `true` only if T implements Error, by autoref specialization.
+struct Probe<T>(T);
+
+trait IsError {
+    fn is_error(&self) -> bool;
+}
+
+impl<T: StdError> IsError for Probe<&T> {
+    fn is_error(&self) -> bool {
+        true
+    }
+}
+
+trait NotError {
+    fn is_error(&self) -> bool;
+}
+
+impl<T> NotError for &Probe<&T> {
+    fn is_error(&self) -> bool {
+        false
+    }
+}
+
+#[test]
+fn test_display() {
+    assert(
+        "downloaded 5 of 10 bytes",
+        Progress::Downloading { done: 5, total: 10 },
+    );
+    assert("verifying sha256", Progress::Verifying("sha256".to_owned()));
+    assert("done", Progress::Done);
+    assert(
+        "status of db: 3 pending",
+        Status {
+            name: "db",
+            status: "3 pending",
+        },
+    );
+}
+
+#[test]
+fn test_no_error_impl() {
+    assert!(!(&Probe(&Progress::Done)).is_error());
+    let status = Status {
+        name: "db",
+        status: 0,
+    };
+    assert!(!(&Probe(&status)).is_error());
+
+    let error = RequestError {
+        id: 7,
+        cause: std::io::Error::new(std::io::ErrorKind::Other, "reset"),
+    };
+    assert!(Probe(&error).is_error());
+    assert_eq!("request 7 failed", error.to_string());
+    assert_eq!("reset", error.source().unwrap().to_string());
+}
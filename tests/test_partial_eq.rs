@@ -0,0 +1,144 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::error::Error as StdError;
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(partial_eq)]
+pub enum StoreError {
+    #[error("key {key:?} not found")]
+    NotFound { key: String },
+    #[error("failed to read {0}")]
+    Read(String, #[source] io::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("store is closed")]
+    Closed,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(partial_eq = "message")]
+pub enum LoadError {
+    #[error("failed to load {path}")]
+    Load {
+        path: String,
+        source: Box<dyn StdError + Send + Sync>,
+    },
+    #[error("invalid config")]
+    Invalid(#[source] Option<io::Error>),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[error("bad record {record:?}")]
+#[thiserror(partial_eq)]
+pub struct RecordError<T, S> {
+    record: T,
+    source: S,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(partial_eq = "message")]
+pub enum Wrapped<E> {
+    #[error("while syncing")]
+    Sync(#[source] E),
+}
+
+// Not PartialEq, so the impls above must not require it of a source.
+#[derive(Debug)]
+pub struct Opaque;
+
+impl std::fmt::Display for Opaque {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("opaque")
+    }
+}
+
+impl StdError for Opaque {}
+
+fn other(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, message)
+}
+
+#[test]
+fn test_ignores_sources() {
+    assert_eq!(
+        StoreError::NotFound {
+            key: "a".to_owned()
+        },
+        StoreError::NotFound {
+            key: "a".to_owned()
+        },
+    );
+    assert_ne!(
+        StoreError::NotFound {
+            key: "a".to_owned()
+        },
+        StoreError::NotFound {
+            key: "b".to_owned()
+        },
+    );
+    assert_eq!(
+        StoreError::Read("a".to_owned(), other("denied")),
+        StoreError::Read("a".to_owned(), other("busy")),
+    );
+    assert_ne!(
+        StoreError::Read("a".to_owned(), other("denied")),
+        StoreError::Read("b".to_owned(), other("denied")),
+    );
+    assert_eq!(
+        StoreError::Io(other("reset")),
+        StoreError::Io(other("closed"))
+    );
+    assert_eq!(StoreError::Closed, StoreError::Closed);
+    assert_ne!(StoreError::Closed, StoreError::Io(other("closed")));
+}
+
+#[test]
+fn test_compares_messages() {
+    let load = |path: &str, message: &str| LoadError::Load {
+        path: path.to_owned(),
+        source: message.into(),
+    };
+    assert_eq!(load("a.toml", "denied"), load("a.toml", "denied"));
+    assert_ne!(load("a.toml", "denied"), load("a.toml", "busy"));
+    assert_ne!(load("a.toml", "denied"), load("b.toml", "denied"));
+
+    assert_eq!(LoadError::Invalid(None), LoadError::Invalid(None));
+    assert_eq!(
+        LoadError::Invalid(Some(other("eof"))),
+        LoadError::Invalid(Some(other("eof"))),
+    );
+    assert_ne!(
+        LoadError::Invalid(Some(other("eof"))),
+        LoadError::Invalid(None),
+    );
+
+    assert_eq!(LoadError::Io(other("reset")), LoadError::Io(other("reset")));
+    assert_ne!(
+        LoadError::Io(other("reset")),
+        LoadError::Io(other("closed"))
+    );
+}
+
+#[test]
+fn test_generic_bounds() {
+    let a = RecordError {
+        record: 1,
+        source: Opaque,
+    };
+    let b = RecordError {
+        record: 1,
+        source: Opaque,
+    };
+    let c = RecordError {
+        record: 2,
+        source: Opaque,
+    };
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+
+    assert_eq!(Wrapped::Sync(Opaque), Wrapped::Sync(Opaque));
+}
@@ -1,4 +1,5 @@
 use ref_cast::RefCast;
+use std::ffi::{OsStr, OsString};
 use std::fmt::Display;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
@@ -22,6 +23,20 @@ enum EnumPathBuf {
     Read(PathBuf),
 }
 
+#[derive(Error, Debug)]
+enum EnumBorrowed<'a> {
+    #[error("failed to read '{0}'")]
+    Path(&'a Path),
+    #[error("no such file '{name}' in '{dir}'")]
+    Name { name: &'a OsStr, dir: &'a Path },
+}
+
+#[derive(Error, Debug)]
+#[error("invalid file name '{name}'")]
+struct StructOsString {
+    name: OsString,
+}
+
 fn assert<T: Display>(expected: &str, value: T) {
     assert_eq!(expected, value.to_string());
 }
@@ -35,3 +50,26 @@ fn test_display() {
     assert("failed to read '/thiserror'", EnumPathBuf::Read(file));
     assert("failed to read '/thiserror'", StructPath::ref_cast(path));
 }
+
+#[test]
+fn test_display_borrowed() {
+    let path = Path::new("/thiserror");
+    assert("failed to read '/thiserror'", EnumBorrowed::Path(path));
+    let name = OsStr::new("lib.rs");
+    assert(
+        "no such file 'lib.rs' in '/thiserror'",
+        EnumBorrowed::Name { name, dir: path },
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_display_non_utf8() {
+    use std::os::unix::ffi::OsStringExt;
+
+    let name = OsString::from_vec(b"bad\xFFname".to_vec());
+    assert(
+        "invalid file name 'bad\u{FFFD}name'",
+        StructOsString { name },
+    );
+}
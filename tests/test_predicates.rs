@@ -0,0 +1,47 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(predicates)]
+pub enum FetchError {
+    #[error("not found")]
+    NotFound,
+    #[error("HTTP status {0}")]
+    HTTPStatus(u16),
+    #[error("redirected to {location}")]
+    Redirect { location: String },
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(predicates)]
+pub enum Single<T> {
+    #[error("invalid {0}")]
+    Invalid(T),
+}
+
+#[test]
+fn test_predicates() {
+    let error = FetchError::NotFound;
+    assert!(error.is_not_found());
+    assert!(!error.is_http_status());
+
+    let error = FetchError::HTTPStatus(503);
+    assert!(error.is_http_status());
+    assert!(!error.is_redirect());
+
+    let error = FetchError::Redirect {
+        location: "/login".to_owned(),
+    };
+    assert!(error.is_redirect());
+    assert!(!error.is_io());
+
+    let error = FetchError::from(io::Error::new(io::ErrorKind::Other, "reset"));
+    assert!(error.is_io());
+    assert!(!error.is_not_found());
+
+    assert!(Single::Invalid(1).is_invalid());
+}
@@ -0,0 +1,145 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+#[cfg(thiserror_nightly_testing)]
+pub mod provide {
+    use std::backtrace::Backtrace;
+    use std::error;
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use thiserror::Error;
+
+    #[derive(Debug, PartialEq)]
+    pub struct RequestId(u64);
+
+    #[derive(Error, Debug)]
+    #[error("...")]
+    pub struct Inner {
+        #[provide]
+        id: RequestId,
+        backtrace: Backtrace,
+    }
+
+    #[derive(Error, Debug)]
+    #[error("...")]
+    pub struct Provided {
+        #[provide]
+        id: RequestId,
+        #[provide]
+        code: Option<u16>,
+    }
+
+    #[derive(Error, Debug)]
+    #[error(transparent)]
+    pub struct Transparent(Inner);
+
+    #[derive(Error, Debug)]
+    pub enum Enum {
+        #[error("...")]
+        Provided {
+            #[provide]
+            id: RequestId,
+            backtrace: Backtrace,
+        },
+        #[error(transparent)]
+        Transparent(Inner),
+        #[error("...")]
+        Nothing,
+    }
+
+    #[derive(Error, Debug)]
+    pub enum Context {
+        #[error("...")]
+        Http {
+            #[provide]
+            path: PathBuf,
+            #[provide(value)]
+            status: u16,
+            #[provide(value)]
+            retry: Option<Duration>,
+        },
+    }
+
+    #[derive(Error, Debug)]
+    #[error("...")]
+    pub struct Generic<T: std::fmt::Debug> {
+        #[provide]
+        data: T,
+    }
+
+    fn inner() -> Inner {
+        Inner {
+            id: RequestId(1),
+            backtrace: Backtrace::capture(),
+        }
+    }
+
+    #[test]
+    fn test_provide_field() {
+        let error = Provided {
+            id: RequestId(1),
+            code: Some(404),
+        };
+        assert_eq!(Some(&RequestId(1)), error::request_ref::<RequestId>(&error));
+        assert_eq!(Some(&404), error::request_ref::<u16>(&error));
+        assert!(error::request_ref::<Backtrace>(&error).is_none());
+
+        let error = Provided {
+            id: RequestId(1),
+            code: None,
+        };
+        assert!(error::request_ref::<u16>(&error).is_none());
+
+        let error = Generic { data: "data" };
+        assert_eq!(Some(&"data"), error::request_ref::<&str>(&error));
+    }
+
+    #[test]
+    fn test_provide_value() {
+        let error = Context::Http {
+            path: PathBuf::from("/index.html"),
+            status: 503,
+            retry: Some(Duration::from_secs(1)),
+        };
+        assert_eq!(
+            Some(&PathBuf::from("/index.html")),
+            error::request_ref::<PathBuf>(&error),
+        );
+        assert_eq!(Some(503), error::request_value::<u16>(&error));
+        assert!(error::request_ref::<u16>(&error).is_none());
+        assert_eq!(
+            Some(Duration::from_secs(1)),
+            error::request_value::<Duration>(&error),
+        );
+    }
+
+    #[test]
+    fn test_transparent() {
+        let error = Transparent(inner());
+        assert_eq!(Some(&RequestId(1)), error::request_ref::<RequestId>(&error));
+        assert!(std::ptr::eq(
+            error::request_ref::<Backtrace>(&error).unwrap(),
+            &error.0.backtrace,
+        ));
+    }
+
+    #[test]
+    fn test_enum() {
+        let error = Enum::Provided {
+            id: RequestId(2),
+            backtrace: Backtrace::capture(),
+        };
+        assert_eq!(Some(&RequestId(2)), error::request_ref::<RequestId>(&error));
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
+
+        let error = Enum::Transparent(inner());
+        assert_eq!(Some(&RequestId(1)), error::request_ref::<RequestId>(&error));
+        assert!(error::request_ref::<Backtrace>(&error).is_some());
+
+        let error = Enum::Nothing;
+        assert!(error::request_ref::<RequestId>(&error).is_none());
+    }
+}
+
+#[test]
+#[cfg_attr(not(thiserror_nightly_testing), ignore)]
+fn test_provide() {}
@@ -0,0 +1,139 @@
+use std::error::Error as StdError;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum EvalError {
+    #[error("in function {name}")]
+    InFunction {
+        name: String,
+        #[source]
+        cause: Box<EvalError>,
+    },
+    #[error("in shared closure")]
+    Shared(#[source] Arc<Self>),
+    #[error("nested")]
+    Nested(#[from] Box<Self>),
+    #[error("division by zero")]
+    DivisionByZero,
+}
+
+#[derive(Error, Debug)]
+#[error("at line {line}")]
+pub struct Trace {
+    line: usize,
+    #[source]
+    next: Option<Box<Trace>>,
+}
+
+#[derive(Error, Debug)]
+pub enum GenericEvalError<T> {
+    #[error("in {0}")]
+    Frame(String, #[source] Box<GenericEvalError<T>>),
+    #[error("{cause}")]
+    Again { cause: Box<Self> },
+    #[error("value {0:?}")]
+    Value(T),
+}
+
+// A type of the same name behind a module path is not the container, so its
+// field still gets the Error and Display bounds.
+pub mod shadowed {
+    pub mod other {
+        #[derive(thiserror::Error, Debug)]
+        #[error("other {0}")]
+        pub struct Error<T>(pub T);
+    }
+
+    #[derive(thiserror::Error, Debug)]
+    #[error("wrapped {inner}")]
+    pub struct Error<T> {
+        #[source]
+        pub inner: Box<other::Error<T>>,
+    }
+}
+
+fn chain(error: &dyn StdError) -> Vec<String> {
+    let mut chain = Vec::new();
+    let mut next = Some(error);
+    while let Some(error) = next {
+        chain.push(error.to_string());
+        next = error.source();
+    }
+    chain
+}
+
+#[test]
+fn test_three_deep() {
+    let error = EvalError::InFunction {
+        name: "main".to_owned(),
+        cause: Box::new(EvalError::Shared(Arc::new(EvalError::InFunction {
+            name: "div".to_owned(),
+            cause: Box::new(EvalError::DivisionByZero),
+        }))),
+    };
+
+    assert_eq!(
+        chain(&error),
+        [
+            "in function main",
+            "in shared closure",
+            "in function div",
+            "division by zero",
+        ],
+    );
+
+    // The source is the boxed error itself, not the Box around it.
+    let source = error.source().unwrap();
+    assert!(source.downcast_ref::<EvalError>().is_some());
+    assert!(source.downcast_ref::<Box<EvalError>>().is_none());
+}
+
+#[test]
+fn test_from_box() {
+    let error = EvalError::from(Box::new(EvalError::DivisionByZero));
+    let source = error.source().unwrap();
+    assert!(matches!(
+        source.downcast_ref::<EvalError>(),
+        Some(EvalError::DivisionByZero),
+    ));
+}
+
+#[test]
+fn test_optional() {
+    let error = Trace {
+        line: 1,
+        next: Some(Box::new(Trace {
+            line: 2,
+            next: Some(Box::new(Trace {
+                line: 3,
+                next: None,
+            })),
+        })),
+    };
+
+    assert_eq!(chain(&error), ["at line 1", "at line 2", "at line 3"]);
+    let source = error.source().unwrap();
+    assert_eq!(source.downcast_ref::<Trace>().unwrap().line, 2);
+}
+
+#[test]
+fn test_generic() {
+    let error = GenericEvalError::Frame(
+        "main".to_owned(),
+        Box::new(GenericEvalError::Again {
+            cause: Box::new(GenericEvalError::Value(1)),
+        }),
+    );
+
+    assert_eq!(chain(&error), ["in main", "value 1"]);
+}
+
+#[test]
+fn test_same_name_behind_path() {
+    let error = shadowed::Error {
+        inner: Box::new(shadowed::other::Error(1)),
+    };
+
+    assert_eq!(chain(&error), ["wrapped other 1", "other 1"]);
+}
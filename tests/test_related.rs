@@ -0,0 +1,90 @@
+use std::error::Error as StdError;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("invalid field {0}")]
+pub struct FieldError(&'static str);
+
+#[derive(Error, Debug)]
+#[thiserror(related)]
+#[error("validation failed")]
+pub struct ValidationError {
+    #[source]
+    first: FieldError,
+    #[related]
+    others: Vec<FieldError>,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(related)]
+pub enum BatchError<E> {
+    #[error("batch failed")]
+    Failed {
+        source: FieldError,
+        #[related]
+        rest: [FieldError; 2],
+    },
+    #[error("generic")]
+    Generic(#[related] Box<[E]>),
+    #[error("no related errors")]
+    Other,
+}
+
+// Without #[thiserror(related)] the attribute is left to other derives that use
+// it, such as miette's Diagnostic, and the field is an ordinary one.
+#[derive(Error, Debug)]
+#[error("{} notes", notes.len())]
+pub struct Unrelated {
+    #[related]
+    notes: Vec<String>,
+}
+
+fn messages<'a>(related: impl Iterator<Item = &'a (dyn StdError + 'static)>) -> Vec<String> {
+    related.map(ToString::to_string).collect()
+}
+
+#[test]
+fn test_related() {
+    let error = ValidationError {
+        first: FieldError("a"),
+        others: vec![FieldError("b"), FieldError("c")],
+    };
+    assert_eq!("invalid field a", error.source().unwrap().to_string());
+    assert_eq!(
+        ["invalid field b", "invalid field c"],
+        messages(error.related()).as_slice(),
+    );
+    assert_eq!("validation failed", error.to_string());
+
+    let error = ValidationError {
+        first: FieldError("a"),
+        others: Vec::new(),
+    };
+    assert_eq!(0, error.related().count());
+}
+
+#[test]
+fn test_related_enum() {
+    let error = BatchError::<FieldError>::Failed {
+        source: FieldError("a"),
+        rest: [FieldError("b"), FieldError("c")],
+    };
+    assert_eq!(2, error.related().count());
+    assert_eq!("invalid field a", error.source().unwrap().to_string());
+
+    let error = BatchError::Generic(vec![FieldError("x")].into_boxed_slice());
+    assert_eq!(["invalid field x"], messages(error.related()).as_slice());
+    assert!(error.source().is_none());
+
+    let error = BatchError::<FieldError>::Other;
+    assert_eq!(0, error.related().count());
+}
+
+#[test]
+fn test_not_opted_in() {
+    let error = Unrelated {
+        notes: vec!["a".to_owned()],
+    };
+    assert_eq!("1 notes", error.to_string());
+    assert!(error.source().is_none());
+}
@@ -0,0 +1,100 @@
+use std::io;
+use thiserror::{Error, Report};
+
+#[derive(Error, Debug)]
+#[thiserror(report, codes)]
+pub enum ApiError {
+    #[error("user {0} not found")]
+    #[error(code = 404)]
+    NotFound(String),
+    #[error("upstream failed")]
+    #[error(code = 502)]
+    Upstream(#[source] LoadError<String>),
+}
+
+#[derive(Error, Debug)]
+#[error("failed to load {path}")]
+#[thiserror(report)]
+pub struct LoadError<T> {
+    path: T,
+    source: io::Error,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(report, discriminant_code)]
+pub enum Status {
+    #[error("ok")]
+    Ok = 0,
+    #[error("interrupted")]
+    Interrupted = -4,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(report)]
+pub enum Never {}
+
+#[test]
+fn test_enum() {
+    let report = ApiError::NotFound("alice".to_owned()).to_report();
+    let expected = Report {
+        type_name: "ApiError",
+        variant: Some("NotFound"),
+        message: "user alice not found".to_owned(),
+        code: Some(404),
+        chain: Vec::new(),
+    };
+    assert_eq!(expected, report);
+}
+
+#[test]
+fn test_chain() {
+    let error = ApiError::Upstream(LoadError {
+        path: "users.db".to_owned(),
+        source: io::Error::new(io::ErrorKind::Other, "disk full"),
+    });
+    let report = error.to_report();
+    assert_eq!(Some("Upstream"), report.variant);
+    assert_eq!(Some(502), report.code);
+    assert_eq!(vec!["failed to load users.db", "disk full"], report.chain);
+    assert_eq!(
+        "upstream failed: failed to load users.db: disk full",
+        report.to_string(),
+    );
+}
+
+#[test]
+fn test_struct() {
+    let error = LoadError {
+        path: 7,
+        source: io::Error::new(io::ErrorKind::Other, "eof"),
+    };
+    let expected = Report {
+        type_name: "LoadError",
+        variant: None,
+        message: "failed to load 7".to_owned(),
+        code: None,
+        chain: vec!["eof".to_owned()],
+    };
+    assert_eq!(expected, error.to_report());
+}
+
+#[test]
+fn test_discriminant_code() {
+    assert_eq!(Some(0), Status::Ok.to_report().code);
+    // Negative discriminants do not fit the report's code.
+    assert_eq!(None, Status::Interrupted.to_report().code);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serialize() {
+    let report = ApiError::NotFound("bob".to_owned()).to_report();
+    let expected = serde_json::json!({
+        "type_name": "ApiError",
+        "variant": "NotFound",
+        "message": "user bob not found",
+        "code": 404,
+        "chain": [],
+    });
+    assert_eq!(expected, serde_json::to_value(&report).unwrap());
+}
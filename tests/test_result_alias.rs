@@ -0,0 +1,79 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::fmt::{Debug, Display};
+use std::io;
+
+mod store {
+    use std::io;
+    use thiserror::Error;
+
+    #[derive(Error, Debug)]
+    #[thiserror(result_alias)]
+    pub enum Error {
+        #[error("key {0} not found")]
+        NotFound(String),
+        #[error(transparent)]
+        Io(#[from] io::Error),
+    }
+
+    pub fn get(key: &str) -> Result<u32> {
+        Err(Error::NotFound(key.to_owned()))
+    }
+}
+
+mod parse {
+    use std::fmt::{Debug, Display};
+    use thiserror::Error;
+
+    #[derive(Error, Debug)]
+    #[thiserror(result_alias = "ParseResult")]
+    #[error("invalid {what} at {pos}")]
+    pub struct ParseError<'a, P: Display + Debug = usize> {
+        pub what: &'a str,
+        pub pos: P,
+    }
+
+    #[derive(Error, Debug)]
+    #[thiserror(result_alias)]
+    #[error("bad value {0}")]
+    pub struct ValueError<T>(pub T);
+}
+
+fn open() -> store::Result<()> {
+    Err(io::Error::new(io::ErrorKind::Other, "denied"))?;
+    Ok(())
+}
+
+fn parse_default(input: &str) -> parse::ParseResult<'_, u8> {
+    Err(parse::ParseError {
+        what: input,
+        pos: 3,
+    })
+}
+
+fn check(value: i32) -> parse::Result<&'static str, i32> {
+    Err(parse::ValueError(value))
+}
+
+fn parse_at<P: Display + Debug>(input: &str, pos: P) -> parse::ParseResult<'_, u8, P> {
+    Err(parse::ParseError { what: input, pos })
+}
+
+#[test]
+fn test_result_alias() {
+    let error = store::get("a").unwrap_err();
+    assert_eq!("key a not found", error.to_string());
+    assert!(matches!(open(), Err(store::Error::Io(_))));
+}
+
+#[test]
+fn test_generic_result_alias() {
+    let error: parse::ParseError<usize> = parse_default("digit").unwrap_err();
+    assert_eq!("invalid digit at 3", error.to_string());
+
+    let error = parse_at("sign", "1:4").unwrap_err();
+    assert_eq!("invalid sign at 1:4", error.to_string());
+
+    // The error's own parameter named T moves the Ok type to another name.
+    assert_eq!("bad value -1", check(-1).unwrap_err().to_string());
+}
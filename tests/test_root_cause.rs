@@ -0,0 +1,32 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(root_cause)]
+#[error("request failed")]
+pub struct RequestError {
+    #[from]
+    source: ConnectError,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(root_cause)]
+pub enum ConnectError {
+    #[error("connection refused")]
+    Io(#[source] io::Error),
+    #[error("no route to host")]
+    NoRoute,
+}
+
+#[test]
+fn test_root_cause() {
+    let io = io::Error::new(io::ErrorKind::ConnectionRefused, "refused by peer");
+    let error = RequestError::from(ConnectError::Io(io));
+    let root = error.root_cause();
+    assert_eq!("refused by peer", root.to_string());
+    assert!(root.downcast_ref::<io::Error>().is_some());
+
+    let error = ConnectError::NoRoute;
+    assert_eq!("no route to host", error.root_cause().to_string());
+    assert!(error.root_cause().downcast_ref::<ConnectError>().is_some());
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "serde")]
+
+use serde_json::json;
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(serialize)]
+pub enum RpcError {
+    #[error("user {user} not found")]
+    NotFound {
+        #[error(serialize)]
+        user: String,
+    },
+    #[error("login failed for {user} with password {password:?}")]
+    Login {
+        #[error(serialize)]
+        user: String,
+        #[sensitive]
+        password: String,
+    },
+    #[error("request {0} failed")]
+    Request(#[error(serialize)] u64, #[source] io::Error),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error("shutting down")]
+    Shutdown,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(serialize, discriminant_code)]
+pub enum CodedError {
+    #[error("timed out")]
+    Timeout = 408,
+    #[error("unavailable")]
+    Unavailable = 503,
+}
+
+#[derive(Error, Debug)]
+#[error("failed to open {path}")]
+#[thiserror(serialize)]
+pub struct OpenError<T> {
+    #[error(serialize)]
+    path: T,
+    source: RpcError,
+}
+
+#[derive(Error, Debug)]
+#[error("token {0} rejected")]
+#[thiserror(serialize)]
+pub struct TokenError(#[sensitive] String);
+
+#[derive(Error, Debug)]
+#[thiserror(serialize)]
+pub enum Never {}
+
+fn other(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, message)
+}
+
+#[test]
+fn test_enum() {
+    let error = RpcError::NotFound {
+        user: "alice".to_owned(),
+    };
+    let expected = json!({
+        "NotFound": {
+            "message": "user alice not found",
+            "chain": [],
+            "user": "alice",
+        },
+    });
+    assert_eq!(expected, serde_json::to_value(&error).unwrap());
+
+    let expected = json!({
+        "Shutdown": {
+            "message": "shutting down",
+            "chain": [],
+        },
+    });
+    assert_eq!(expected, serde_json::to_value(RpcError::Shutdown).unwrap());
+}
+
+#[test]
+fn test_chain() {
+    let error = RpcError::Request(7, other("connection reset"));
+    let expected = json!({
+        "Request": {
+            "message": "request 7 failed",
+            "chain": ["connection reset"],
+            "0": 7,
+        },
+    });
+    assert_eq!(expected, serde_json::to_value(&error).unwrap());
+
+    let error = RpcError::Io(other("broken pipe"));
+    let expected = json!({
+        "Io": {
+            "message": "broken pipe",
+            "chain": [],
+        },
+    });
+    assert_eq!(expected, serde_json::to_value(&error).unwrap());
+
+    let error = OpenError {
+        path: "/etc/app.toml".to_owned(),
+        source: RpcError::Request(8, other("eof")),
+    };
+    let expected = json!({
+        "message": "failed to open /etc/app.toml",
+        "chain": ["request 8 failed", "eof"],
+        "path": "/etc/app.toml",
+    });
+    assert_eq!(expected, serde_json::to_value(&error).unwrap());
+}
+
+#[test]
+fn test_sensitive() {
+    let error = RpcError::Login {
+        user: "bob".to_owned(),
+        password: "hunter2".to_owned(),
+    };
+    assert_eq!(
+        "login failed for bob with password \"hunter2\"",
+        error.to_string(),
+    );
+    let expected = json!({
+        "Login": {
+            "message": "login failed for bob with password <redacted>",
+            "chain": [],
+            "user": "bob",
+        },
+    });
+    let serialized = serde_json::to_string(&error).unwrap();
+    assert!(!serialized.contains("hunter2"));
+    assert_eq!(
+        expected,
+        serde_json::from_str::<serde_json::Value>(&serialized).unwrap()
+    );
+
+    let error = TokenError("abc123".to_owned());
+    let expected = json!({
+        "message": "token <redacted> rejected",
+        "chain": [],
+    });
+    assert_eq!(expected, serde_json::to_value(&error).unwrap());
+}
+
+#[test]
+fn test_code() {
+    let expected = json!({
+        "Unavailable": {
+            "message": "unavailable",
+            "code": 503,
+            "chain": [],
+        },
+    });
+    assert_eq!(
+        expected,
+        serde_json::to_value(CodedError::Unavailable).unwrap(),
+    );
+}
+
+#[test]
+fn test_round_trip() {
+    let error = OpenError {
+        path: 42,
+        source: RpcError::Shutdown,
+    };
+    let json = serde_json::to_string(&error).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!("failed to open 42", value["message"]);
+    assert_eq!(json!(["shutting down"]), value["chain"]);
+    assert_eq!(42, value["path"]);
+
+    let json = serde_json::to_string(&CodedError::Timeout).unwrap();
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(408, value["Timeout"]["code"]);
+    assert_eq!(serde_json::to_value(CodedError::Timeout).unwrap(), value);
+}
@@ -1,3 +1,5 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
 use std::error::Error as StdError;
 use std::io;
 use thiserror::Error;
@@ -23,6 +25,44 @@ pub struct BoxedSource {
     source: Box<dyn StdError + Send + 'static>,
 }
 
+#[derive(Error, Debug)]
+#[error("boxed thread-safe source")]
+pub struct BoxedSendSyncSource(#[from] Box<dyn StdError + Send + Sync + 'static>);
+
+#[derive(Error, Debug)]
+pub enum BoxedSendSyncSourceEnum {
+    #[error("request failed")]
+    Request {
+        source: Box<dyn StdError + Send + Sync>,
+    },
+    #[error("retry failed")]
+    Retry(#[source] Option<Box<dyn StdError + Send + Sync + 'static>>),
+    #[error(transparent)]
+    Other(Box<dyn StdError + Send + Sync>),
+}
+
+#[derive(Error, Debug)]
+#[error("failed to load config: {source}")]
+pub struct LoadError {
+    #[from]
+    source: io::Error,
+}
+
+#[derive(Error, Debug)]
+pub enum SyncError {
+    #[error("sync of {path} failed: {cause}")]
+    Sync {
+        path: String,
+        #[source]
+        cause: io::Error,
+    },
+    #[error("config: {source:?}")]
+    Config {
+        #[from]
+        source: LoadError,
+    },
+}
+
 #[test]
 fn test_implicit_source() {
     let io = io::Error::new(io::ErrorKind::Other, "oh no!");
@@ -40,6 +80,29 @@ fn test_explicit_source() {
     error.source().unwrap().downcast_ref::<io::Error>().unwrap();
 }
 
+#[test]
+fn test_source_in_display() {
+    let error = LoadError::from(io::Error::new(io::ErrorKind::Other, "denied"));
+    assert_eq!("failed to load config: denied", error.to_string());
+    assert_eq!("denied", error.source().unwrap().to_string());
+
+    let error = SyncError::Sync {
+        path: "a.db".to_owned(),
+        cause: io::Error::new(io::ErrorKind::Other, "disk full"),
+    };
+    assert_eq!("sync of a.db failed: disk full", error.to_string());
+    error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+
+    let io = io::Error::new(io::ErrorKind::Other, "denied");
+    let error = SyncError::from(LoadError::from(io));
+    assert!(error
+        .to_string()
+        .starts_with("config: LoadError { source: "));
+    let source = error.source().unwrap();
+    assert_eq!("failed to load config: denied", source.to_string());
+    assert!(source.downcast_ref::<LoadError>().is_some());
+}
+
 #[test]
 fn test_boxed_source() {
     let source = Box::new(io::Error::new(io::ErrorKind::Other, "oh no!"));
@@ -47,6 +110,35 @@ fn test_boxed_source() {
     error.source().unwrap().downcast_ref::<io::Error>().unwrap();
 }
 
+#[test]
+fn test_boxed_send_sync_source() {
+    let source: Box<dyn StdError + Send + Sync> = "oh no!".into();
+    let error = BoxedSendSyncSource::from(source);
+    assert_eq!("oh no!", error.source().unwrap().to_string());
+
+    let io = io::Error::new(io::ErrorKind::Other, "oh no!");
+    let error = BoxedSendSyncSourceEnum::Request {
+        source: Box::new(ImplicitSource { source: io }),
+    };
+    let source = error.source().unwrap();
+    source.downcast_ref::<ImplicitSource>().unwrap();
+    source
+        .source()
+        .unwrap()
+        .downcast_ref::<io::Error>()
+        .unwrap();
+
+    assert!(BoxedSendSyncSourceEnum::Retry(None).source().is_none());
+    let error =
+        BoxedSendSyncSourceEnum::Retry(Some(Box::new(io::Error::from(io::ErrorKind::TimedOut))));
+    error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+
+    let io = io::Error::new(io::ErrorKind::Other, "oh no!");
+    let error = BoxedSendSyncSourceEnum::Other(Box::new(ImplicitSource { source: io }));
+    assert_eq!("implicit source", error.to_string());
+    error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+}
+
 macro_rules! error_from_macro {
     ($($variants:tt)*) => {
         #[derive(Error)]
@@ -63,3 +155,261 @@ error_from_macro! {
     #[error("Something")]
     Variant(#[from] io::Error)
 }
+
+#[derive(Debug)]
+pub struct Unspecified;
+
+impl std::fmt::Display for Unspecified {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        formatter.write_str("unspecified")
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("wrapped source")]
+pub struct WrappedSource {
+    #[source(wrap)]
+    cause: Unspecified,
+}
+
+#[derive(Error, Debug)]
+pub enum WrappedSourceEnum<T> {
+    #[error("optional")]
+    Optional(#[source(wrap)] Option<Unspecified>),
+    #[error("generic")]
+    Generic {
+        #[from]
+        #[source(wrap)]
+        source: T,
+    },
+}
+
+#[test]
+fn test_wrapped_source() {
+    let error = WrappedSource { cause: Unspecified };
+    let source = error.source().unwrap();
+    assert_eq!("unspecified", source.to_string());
+    let wrapped = source
+        .downcast_ref::<thiserror::Wrapped<Unspecified>>()
+        .unwrap();
+    assert!(std::ptr::eq(&error.cause, wrapped.get_ref()));
+
+    let error = WrappedSourceEnum::<String>::Optional(None);
+    assert!(error.source().is_none());
+    let error = WrappedSourceEnum::<String>::Optional(Some(Unspecified));
+    assert_eq!("unspecified", error.source().unwrap().to_string());
+
+    let error = WrappedSourceEnum::from("not an error".to_owned());
+    assert_eq!("not an error", error.source().unwrap().to_string());
+}
+
+#[derive(Error, Debug)]
+#[error("invalid value for {key}")]
+#[thiserror(setters)]
+pub struct InvalidConfig {
+    key: String,
+    source: Option<io::Error>,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(setters)]
+pub enum ConfigError {
+    #[error("invalid value for {key}")]
+    Invalid {
+        key: String,
+        #[source]
+        cause: Option<io::Error>,
+    },
+    #[error("missing {0}")]
+    Missing(String, #[source] Option<io::Error>),
+    #[error("unknown")]
+    Unknown,
+}
+
+#[test]
+fn test_setters() {
+    let error = InvalidConfig {
+        key: "port".to_owned(),
+        source: None,
+    };
+    assert!(error.source().is_none());
+    let error = error.with_source(io::Error::new(io::ErrorKind::Other, "oh no!"));
+    error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+
+    let mut error = ConfigError::Invalid {
+        key: "port".to_owned(),
+        cause: None,
+    };
+    error.set_source(io::ErrorKind::NotFound);
+    let source = error.source().unwrap().downcast_ref::<io::Error>();
+    assert_eq!(source.unwrap().kind(), io::ErrorKind::NotFound);
+
+    let error = ConfigError::Missing("port".to_owned(), None).with_source(io::ErrorKind::Other);
+    assert!(error.source().is_some());
+
+    let error = ConfigError::Unknown.with_source(io::ErrorKind::Other);
+    assert!(error.source().is_none());
+}
+
+#[derive(Error, Debug)]
+#[error("request failed")]
+#[thiserror(downcast)]
+pub struct RequestError {
+    #[from]
+    source: ConnectError,
+}
+
+#[derive(Error, Debug)]
+#[error("connect failed")]
+pub struct ConnectError {
+    #[from]
+    source: io::Error,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(downcast)]
+pub enum Outer<T> {
+    #[error("generic")]
+    Generic(#[source] T),
+    #[error("none")]
+    None,
+}
+
+#[test]
+fn test_downcast_source() {
+    let io = io::Error::new(io::ErrorKind::TimedOut, "oh no!");
+    let error = RequestError::from(ConnectError::from(io));
+    assert!(error.downcast_source::<ConnectError>().is_some());
+    let io = error.downcast_source::<io::Error>().unwrap();
+    assert_eq!(io.kind(), io::ErrorKind::TimedOut);
+    assert!(error.downcast_source::<RequestError>().is_none());
+
+    let error = Outer::Generic(ConnectError::from(io::Error::from(io::ErrorKind::Other)));
+    assert!(error.downcast_source::<io::Error>().is_some());
+    let error = Outer::<ConnectError>::None;
+    assert!(error.downcast_source::<io::Error>().is_none());
+}
+
+#[derive(Debug)]
+pub struct MyBox<T>(Box<T>);
+
+impl<T> std::ops::Deref for MyBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("source error")]
+pub struct SourceError;
+
+#[derive(Error, Debug)]
+#[error("deref source")]
+pub struct DerefSource {
+    #[source(deref)]
+    cause: MyBox<SourceError>,
+}
+
+#[derive(Error, Debug)]
+pub enum DerefSourceEnum<T> {
+    #[error("optional")]
+    Optional(#[source(deref)] Option<MyBox<SourceError>>),
+    #[error("generic")]
+    Generic(#[source(deref)] MyBox<T>),
+}
+
+#[test]
+fn test_deref_source() {
+    let error = DerefSource {
+        cause: MyBox(Box::new(SourceError)),
+    };
+    error
+        .source()
+        .unwrap()
+        .downcast_ref::<SourceError>()
+        .unwrap();
+
+    let error = DerefSourceEnum::<SourceError>::Optional(None);
+    assert!(error.source().is_none());
+    let error = DerefSourceEnum::<SourceError>::Optional(Some(MyBox(Box::new(SourceError))));
+    error
+        .source()
+        .unwrap()
+        .downcast_ref::<SourceError>()
+        .unwrap();
+
+    let error = DerefSourceEnum::Generic(MyBox(Box::new(io::Error::from(io::ErrorKind::Other))));
+    error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+}
+
+// A container of an error that exposes it through a method rather than Deref.
+#[derive(Debug)]
+pub struct Slot<T> {
+    error: Option<T>,
+}
+
+impl<T: StdError + 'static> Slot<T> {
+    fn as_error(&self) -> Option<&(dyn StdError + 'static)> {
+        match &self.error {
+            Some(error) => Some(error),
+            None => None,
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("computed source")]
+pub struct ComputedSource {
+    #[source(with = self.slot.as_error())]
+    slot: Slot<io::Error>,
+}
+
+#[derive(Error, Debug)]
+#[error("computed generic source")]
+pub struct ComputedGenericSource<T: StdError + 'static> {
+    #[source(with = self.inner.as_error())]
+    inner: Slot<T>,
+}
+
+#[test]
+fn test_source_with() {
+    let error = ComputedSource {
+        slot: Slot {
+            error: Some(io::Error::new(io::ErrorKind::Other, "oh no!")),
+        },
+    };
+    assert_eq!("oh no!", error.source().unwrap().to_string());
+
+    let error = ComputedSource {
+        slot: Slot { error: None },
+    };
+    assert!(error.source().is_none());
+
+    let error = ComputedGenericSource {
+        inner: Slot {
+            error: Some(SourceError),
+        },
+    };
+    error
+        .source()
+        .unwrap()
+        .downcast_ref::<SourceError>()
+        .unwrap();
+}
+
+// No attribute is needed for a reference to a derived error to satisfy an
+// Error bound: the standard library implements Error for &E wherever E:
+// Error, forwarding source() to the error behind the reference.
+#[test]
+fn test_error_for_ref() {
+    fn source_message<E: StdError>(error: E) -> String {
+        error.source().unwrap().to_string()
+    }
+
+    let error = ImplicitSource {
+        source: io::Error::new(io::ErrorKind::Other, "oh no!"),
+    };
+    assert_eq!("oh no!", source_message(&error));
+}
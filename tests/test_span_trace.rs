@@ -0,0 +1,71 @@
+#![cfg(feature = "tracing")]
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::io;
+use thiserror::Error;
+use tracing_error::{ErrorLayer, SpanTrace};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::Registry;
+
+#[derive(Error, Debug)]
+#[error("io error")]
+pub struct IoError {
+    #[from]
+    source: io::Error,
+    #[span_trace]
+    span_trace: SpanTrace,
+}
+
+#[derive(Error, Debug)]
+pub enum RequestError {
+    #[error("io error")]
+    Io(#[from] io::Error, #[span_trace] SpanTrace),
+    #[error("fmt error")]
+    Fmt {
+        #[from]
+        source: std::fmt::Error,
+        #[span_trace]
+        span_trace: Option<SpanTrace>,
+    },
+    #[error("cancelled")]
+    Cancelled,
+}
+
+fn io() -> io::Error {
+    io::Error::new(io::ErrorKind::Other, "oh no!")
+}
+
+fn in_span<T>(f: impl FnOnce() -> T) -> T {
+    let subscriber = Registry::default().with(ErrorLayer::default());
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("handle_request");
+        let _entered = span.enter();
+        f()
+    })
+}
+
+#[test]
+fn test_struct() {
+    let error = in_span(|| IoError::from(io()));
+    assert!(error.span_trace().to_string().contains("handle_request"));
+}
+
+#[test]
+fn test_enum() {
+    let error = in_span(|| RequestError::from(io()));
+    let span_trace = error.span_trace().unwrap();
+    assert!(span_trace.to_string().contains("handle_request"));
+
+    let error = in_span(|| RequestError::from(std::fmt::Error));
+    assert!(error.span_trace().is_some());
+
+    assert!(RequestError::Cancelled.span_trace().is_none());
+}
+
+#[cfg(thiserror_nightly_testing)]
+#[test]
+fn test_provide() {
+    let error = in_span(|| IoError::from(io()));
+    let provided = std::error::request_ref::<SpanTrace>(&error).unwrap();
+    assert!(std::ptr::eq(provided, error.span_trace()));
+}
@@ -0,0 +1,42 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(static_messages)]
+pub enum FlashError {
+    #[error("flash is write-protected")]
+    WriteProtected,
+    #[error("erase timed out")]
+    Timeout { sector: u32 },
+    #[error("bus error")]
+    Bus(#[source] io::Error),
+    #[error("{{unaligned}} write")]
+    Unaligned(usize),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(static_messages)]
+#[error("watchdog reset")]
+pub struct WatchdogReset;
+
+const TIMEOUT: &str = FlashError::Timeout { sector: 0 }.message();
+
+#[test]
+fn test_message() {
+    assert_eq!(
+        "flash is write-protected",
+        FlashError::WriteProtected.message()
+    );
+    assert_eq!("erase timed out", TIMEOUT);
+    assert_eq!("{unaligned} write", FlashError::Unaligned(3).message());
+    assert_eq!("watchdog reset", WatchdogReset.message());
+}
+
+#[test]
+fn test_display() {
+    let error = FlashError::Bus(io::Error::new(io::ErrorKind::Other, "nack"));
+    assert_eq!("bus error", error.to_string());
+    assert!(std::error::Error::source(&error).is_some());
+    assert_eq!("{unaligned} write", FlashError::Unaligned(3).to_string());
+    assert_eq!("watchdog reset", WatchdogReset.to_string());
+}
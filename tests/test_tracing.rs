@@ -0,0 +1,48 @@
+#![cfg(feature = "tracing")]
+
+use thiserror::Error;
+use tracing::Level;
+
+#[derive(Error, Debug)]
+pub enum RequestError {
+    #[error("request was cancelled")]
+    #[error(level = "info")]
+    Cancelled,
+    #[error("retrying after timeout")]
+    #[error(level = "WARN")]
+    Timeout,
+    #[error("connection refused")]
+    Refused,
+}
+
+#[derive(Error, Debug)]
+#[error(level = "debug")]
+pub enum Recoverable<T> {
+    #[error("stale {0}")]
+    Stale(T),
+    #[error("corrupt")]
+    #[error(level = "error")]
+    Corrupt,
+}
+
+#[derive(Error, Debug)]
+#[error("cache miss")]
+#[error(level = "trace")]
+pub struct CacheMiss;
+
+#[test]
+fn test_tracing_level() {
+    assert_eq!(Level::INFO, RequestError::Cancelled.tracing_level());
+    assert_eq!(Level::WARN, RequestError::Timeout.tracing_level());
+    assert_eq!(Level::ERROR, RequestError::Refused.tracing_level());
+    assert_eq!(Level::DEBUG, Recoverable::Stale(1).tracing_level());
+    assert_eq!(Level::ERROR, Recoverable::<u8>::Corrupt.tracing_level());
+    assert_eq!(Level::TRACE, CacheMiss.tracing_level());
+}
+
+#[test]
+fn test_emit() {
+    RequestError::Timeout.emit();
+    Recoverable::Stale("entry").emit();
+    CacheMiss.emit();
+}
@@ -1,3 +1,5 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
 use anyhow::anyhow;
 use std::error::Error as _;
 use std::io;
@@ -76,3 +78,107 @@ fn test_non_static() {
     assert_eq!("unexpected token: \"error\"", error.to_string());
     assert!(error.source().is_none());
 }
+
+#[test]
+fn test_debug_transparent() {
+    #[derive(Error, Debug)]
+    #[error("inner")]
+    struct Inner {
+        code: u8,
+    }
+
+    #[derive(Error, Debug)]
+    #[error(transparent)]
+    struct Derived(Inner);
+
+    #[derive(Error)]
+    #[error(transparent)]
+    #[thiserror(debug_transparent)]
+    struct Delegated(Inner);
+
+    #[derive(Error)]
+    #[thiserror(debug_transparent)]
+    enum Enum<T: std::fmt::Display + 'static> {
+        #[error(transparent)]
+        Inner(Inner),
+        #[error("{0}")]
+        Tuple(T, u8),
+        #[error("named {r#type}")]
+        Named { r#type: T },
+        #[error("unit")]
+        Unit,
+    }
+
+    assert_eq!(
+        "Derived(Inner { code: 1 })",
+        format!("{:?}", Derived(Inner { code: 1 }))
+    );
+    assert_eq!(
+        "Inner { code: 1 }",
+        format!("{:?}", Delegated(Inner { code: 1 }))
+    );
+    assert_eq!(
+        "Inner { code: 1 }",
+        format!("{:?}", Enum::<&str>::Inner(Inner { code: 1 })),
+    );
+    assert_eq!("Tuple(\"t\", 2)", format!("{:?}", Enum::Tuple("t", 2)));
+    assert_eq!(
+        "Named { type: \"t\" }",
+        format!("{:?}", Enum::Named { r#type: "t" })
+    );
+    assert_eq!("Unit", format!("{:?}", Enum::<&str>::Unit));
+}
+
+#[test]
+fn test_auto_transparent() {
+    #[derive(Error, Debug)]
+    #[thiserror(auto_transparent)]
+    struct Wrapper(Inner);
+
+    #[derive(Error, Debug)]
+    #[thiserror(auto_transparent)]
+    #[error("wrapped")]
+    struct Described(Inner);
+
+    #[derive(Error, Debug)]
+    enum Inner {
+        #[error("reading failed")]
+        Read(#[source] io::Error),
+    }
+
+    let io = io::Error::new(io::ErrorKind::Other, "oh no!");
+    let error = Wrapper(Inner::Read(io));
+    assert_eq!("reading failed", error.to_string());
+    error.source().unwrap().downcast_ref::<io::Error>().unwrap();
+
+    let io = io::Error::new(io::ErrorKind::Other, "oh no!");
+    let error = Described(Inner::Read(io));
+    assert_eq!("wrapped", error.to_string());
+    assert!(error.source().is_none());
+}
+
+#[test]
+fn test_transparent_enum_default() {
+    #[derive(Error, Debug)]
+    #[error(transparent)]
+    enum Facade {
+        Io(#[from] io::Error),
+        Parse(std::num::ParseIntError),
+        #[error("facade closed")]
+        Closed,
+        #[error("retry after {0}s")]
+        Retry(u64),
+    }
+
+    let io = io::Error::new(io::ErrorKind::Other, "oh no!");
+    let error = Facade::from(io);
+    assert_eq!("oh no!", error.to_string());
+    assert!(error.source().is_none());
+
+    let error = Facade::Parse("x".parse::<u8>().unwrap_err());
+    assert_eq!("invalid digit found in string", error.to_string());
+
+    assert_eq!("facade closed", Facade::Closed.to_string());
+    assert_eq!("retry after 3s", Facade::Retry(3).to_string());
+    assert!(Facade::Retry(3).source().is_none());
+}
@@ -0,0 +1,107 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use thiserror::Error;
+
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+#[derive(Error, Debug)]
+#[error(transparent_str)]
+pub struct StaticError(&'static str);
+
+#[derive(Error, Debug)]
+#[error(transparent_str)]
+pub struct MessageError<'a> {
+    message: Cow<'a, str>,
+}
+
+#[derive(Error, Debug)]
+#[error(transparent_str)]
+pub struct SharedError<T>(T);
+
+#[derive(Error, Debug)]
+#[error("{message}")]
+pub struct InterpolatedError {
+    message: Cow<'static, str>,
+}
+
+#[derive(Error, Debug)]
+pub enum ServiceError {
+    #[error(transparent_str)]
+    Rejected(Box<str>),
+    #[error(transparent_str)]
+    Custom { reason: String },
+    #[error("unavailable")]
+    Unavailable,
+}
+
+// Formats into a buffer with room to spare, returning how many allocations
+// formatting made.
+fn allocations(error: &dyn std::error::Error) -> usize {
+    let mut buf = String::with_capacity(64);
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    write!(buf, "{}", error).unwrap();
+    ALLOCATIONS.load(Ordering::SeqCst) - before
+}
+
+// A single test, so that no other test allocates while allocations are being
+// counted.
+#[test]
+fn test_transparent_str() {
+    let errors: Vec<Box<dyn std::error::Error>> = vec![
+        Box::new(StaticError("no route")),
+        Box::new(MessageError {
+            message: Cow::Owned("disk full".to_owned()),
+        }),
+        Box::new(InterpolatedError {
+            message: Cow::Borrowed("timed out"),
+        }),
+        Box::new(ServiceError::Rejected("bad token".into())),
+    ];
+    for error in &errors {
+        assert_eq!(0, allocations(&**error));
+    }
+
+    assert_eq!("no route", StaticError("no route").to_string());
+
+    let owned = "disk full".to_owned();
+    let error = MessageError {
+        message: Cow::Borrowed(&owned),
+    };
+    assert_eq!("disk full", error.to_string());
+
+    let error = SharedError(Arc::<str>::from("shared"));
+    assert_eq!("shared", error.to_string());
+
+    assert_eq!(
+        "bad token",
+        ServiceError::Rejected("bad token".into()).to_string(),
+    );
+    let error = ServiceError::Custom {
+        reason: "over quota".to_owned(),
+    };
+    assert_eq!("over quota", error.to_string());
+    assert_eq!("unavailable", ServiceError::Unavailable.to_string());
+
+    // Width and padding would require the Display machinery; the string is
+    // written as is.
+    assert_eq!("no route", format!("{:>12}", StaticError("no route")));
+}
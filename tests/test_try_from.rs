@@ -0,0 +1,60 @@
+use std::convert::TryFrom;
+use thiserror::Error;
+
+#[derive(Debug, PartialEq)]
+pub struct StatusCode(u16);
+
+#[derive(Error, Debug)]
+pub enum ApiError {
+    #[error("rate limited, retry after {0}s")]
+    #[try_from(StatusCode, with = rate_limited)]
+    RateLimited(u64),
+    #[error("server error {code} ({reason})")]
+    #[try_from(u16, with = server_error)]
+    Server { code: u16, reason: &'static str },
+}
+
+fn rate_limited(status: StatusCode) -> Result<u64, StatusCode> {
+    if status.0 == 429 {
+        Ok(30)
+    } else {
+        Err(status)
+    }
+}
+
+fn server_error(code: u16) -> Result<(u16, &'static str), u16> {
+    match code {
+        500 => Ok((code, "internal")),
+        503 => Ok((code, "unavailable")),
+        _ => Err(code),
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("unit")]
+#[try_from(bool, with = unit)]
+pub struct Unit;
+
+fn unit(b: bool) -> Result<(), bool> {
+    if b {
+        Ok(())
+    } else {
+        Err(b)
+    }
+}
+
+#[test]
+fn test_try_from() {
+    let error = ApiError::try_from(StatusCode(429)).unwrap();
+    assert_eq!("rate limited, retry after 30s", error.to_string());
+
+    let status = ApiError::try_from(StatusCode(200)).unwrap_err();
+    assert_eq!(StatusCode(200), status);
+
+    let error = ApiError::try_from(503).unwrap();
+    assert_eq!("server error 503 (unavailable)", error.to_string());
+    assert_eq!(404, ApiError::try_from(404).unwrap_err());
+
+    assert!(Unit::try_from(true).is_ok());
+    assert!(!Unit::try_from(false).unwrap_err());
+}
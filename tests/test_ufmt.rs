@@ -0,0 +1,94 @@
+#![cfg(feature = "ufmt")]
+
+use thiserror::Error;
+use ufmt::{uDebug, uDisplay, uWrite};
+
+#[derive(Error, Debug)]
+#[thiserror(ufmt)]
+#[error("invalid register {register:#x} at {{offset {offset}}}")]
+pub struct InvalidRegister {
+    register: u8,
+    offset: u16,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(ufmt)]
+pub enum BusError {
+    #[error("nack")]
+    Nack,
+    #[error("timed out after {0} polls, last status {1:?}")]
+    Timeout(u32, Status),
+    #[error(transparent)]
+    InvalidRegister(#[from] InvalidRegister),
+}
+
+#[derive(Debug)]
+pub struct Status(u8);
+
+impl uDebug for Status {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut ufmt::Formatter<W>) -> Result<(), W::Error> {
+        f.debug_tuple("Status")?.field(&self.0)?.finish()
+    }
+}
+
+#[derive(Error, Debug)]
+#[thiserror(ufmt)]
+#[error("device {id}: {source}")]
+pub struct DeviceError<E: std::error::Error + 'static> {
+    id: u8,
+    source: E,
+}
+
+struct Buffer(String);
+
+impl uWrite for Buffer {
+    type Error = ();
+
+    fn write_str(&mut self, s: &str) -> Result<(), ()> {
+        self.0.push_str(s);
+        Ok(())
+    }
+}
+
+fn render<T: uDisplay + ?Sized>(value: &T) -> String {
+    let mut buffer = Buffer(String::new());
+    ufmt::uwrite!(buffer, "{}", value).unwrap();
+    buffer.0
+}
+
+#[derive(Error, Debug)]
+#[error("nack")]
+pub struct Nack;
+
+impl uDisplay for Nack {
+    fn fmt<W: uWrite + ?Sized>(&self, f: &mut ufmt::Formatter<W>) -> Result<(), W::Error> {
+        f.write_str("nack")
+    }
+}
+
+#[test]
+fn test_ufmt() {
+    let error = InvalidRegister {
+        register: 0x2a,
+        offset: 7,
+    };
+    assert_eq!("invalid register 0x2a at {offset 7}", render(&error));
+    assert_eq!(error.to_string(), render(&error));
+
+    assert_eq!("nack", render(&BusError::Nack));
+    assert_eq!(
+        "timed out after 3 polls, last status Status(1)",
+        render(&BusError::Timeout(3, Status(1))),
+    );
+    let error = BusError::from(InvalidRegister {
+        register: 1,
+        offset: 0,
+    });
+    assert_eq!("invalid register 0x1 at {offset 0}", render(&error));
+
+    let error = DeviceError {
+        id: 4,
+        source: Nack,
+    };
+    assert_eq!("device 4: nack", render(&error));
+}
@@ -0,0 +1,48 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(variant_names)]
+pub enum UploadError {
+    #[error("timed out")]
+    #[error(name = "timeout")]
+    TimedOut,
+    #[error("quota of {0} bytes exceeded")]
+    QuotaExceeded(u64),
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[error("invalid {0}")]
+#[thiserror(variant_names)]
+pub struct InvalidError<T>(T);
+
+#[derive(Error, Debug)]
+#[thiserror(variant_names)]
+pub enum Never {}
+
+#[test]
+fn test_variant_name() {
+    assert_eq!("timeout", UploadError::TimedOut.variant_name());
+    assert_eq!(
+        "QuotaExceeded",
+        UploadError::QuotaExceeded(10).variant_name()
+    );
+    let error = UploadError::from(io::Error::new(io::ErrorKind::Other, "reset"));
+    assert_eq!("Io", error.variant_name());
+
+    assert_eq!("InvalidError", InvalidError(1).variant_name());
+}
+
+#[test]
+fn test_variant_names_const() {
+    assert_eq!(
+        ["timeout", "QuotaExceeded", "Io"],
+        UploadError::VARIANT_NAMES,
+    );
+    assert_eq!(["InvalidError"], InvalidError::<u8>::VARIANT_NAMES);
+    assert!(Never::VARIANT_NAMES.is_empty());
+}
@@ -0,0 +1,170 @@
+#![cfg_attr(thiserror_nightly_testing, feature(error_generic_member_access))]
+
+// One enum mixing unit, tuple and struct variants, each shape combined with
+// #[from], #[source] and #[backtrace].
+#[rustversion::since(1.65)]
+#[allow(clippy::incompatible_msrv)]
+pub mod shapes {
+    use std::backtrace::{Backtrace, BacktraceStatus};
+    use std::error::Error as _;
+    use std::fmt;
+    use std::io;
+    use std::num::{ParseFloatError, ParseIntError};
+    use std::str::Utf8Error;
+    use std::sync::Arc;
+    use thiserror::Error;
+
+    #[derive(Error, Debug)]
+    #[error("inner")]
+    pub struct Inner;
+
+    #[derive(Error, Debug)]
+    pub enum Mixed<T: fmt::Debug + fmt::Display> {
+        #[error("unit")]
+        Unit,
+        #[error("tuple {0}")]
+        Tuple(u8, &'static str),
+        #[error("struct {id}")]
+        Struct { id: u8, name: &'static str },
+        #[error("io")]
+        Io {
+            #[from]
+            source: io::Error,
+            #[backtrace]
+            bt: Backtrace,
+        },
+        #[error("fmt")]
+        Fmt(#[backtrace] Backtrace, #[from] fmt::Error),
+        #[error("int {source}")]
+        Int {
+            #[backtrace]
+            backtrace: Option<Backtrace>,
+            #[from]
+            source: ParseIntError,
+        },
+        #[error("float")]
+        Float(#[from] ParseFloatError),
+        #[error("utf8 {r#type}")]
+        Utf8 {
+            #[from]
+            r#type: Utf8Error,
+            #[backtrace]
+            shared: Option<Arc<Backtrace>>,
+        },
+        #[error("tuple source {0}")]
+        TupleSource(&'static str, #[source] Inner, Backtrace),
+        #[error("struct source {path}")]
+        StructSource {
+            path: &'static str,
+            #[source]
+            cause: Inner,
+            #[backtrace]
+            trace: Option<Backtrace>,
+        },
+        #[error("generic {0}")]
+        Generic(T),
+        #[error("generic struct {value}")]
+        GenericStruct { value: T, source: Inner },
+    }
+
+    fn io() -> io::Error {
+        io::Error::new(io::ErrorKind::Other, "oh no!")
+    }
+
+    #[test]
+    fn test_from() {
+        match Mixed::<u8>::from(io()) {
+            Mixed::Io { source, bt } => {
+                assert_eq!("oh no!", source.to_string());
+                assert_ne!(bt.status(), BacktraceStatus::Unsupported);
+            }
+            _ => unreachable!(),
+        }
+
+        match Mixed::<u8>::from(fmt::Error) {
+            Mixed::Fmt(bt, fmt::Error) => {
+                assert_ne!(bt.status(), BacktraceStatus::Unsupported);
+            }
+            _ => unreachable!(),
+        }
+
+        let error = Mixed::<u8>::from("x".parse::<u8>().unwrap_err());
+        assert_eq!("int invalid digit found in string", error.to_string());
+        match &error {
+            Mixed::Int { backtrace, .. } => assert!(backtrace.is_some()),
+            _ => unreachable!(),
+        }
+
+        let error = Mixed::<u8>::from("x".parse::<f32>().unwrap_err());
+        assert!(matches!(error, Mixed::Float(_)));
+
+        let utf8 = String::from_utf8(vec![0xff]).unwrap_err().utf8_error();
+        let error = Mixed::<u8>::from(utf8);
+        assert!(error.to_string().starts_with("utf8 invalid utf-8"));
+        match &error {
+            Mixed::Utf8 { shared, .. } => assert!(shared.is_some()),
+            _ => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!("unit", Mixed::<u8>::Unit.to_string());
+        assert_eq!("tuple 1", Mixed::<u8>::Tuple(1, "a").to_string());
+        let error = Mixed::<u8>::Struct { id: 2, name: "b" };
+        assert_eq!("struct 2", error.to_string());
+        assert_eq!("generic 3", Mixed::Generic(3).to_string());
+        let error = Mixed::GenericStruct {
+            value: 4,
+            source: Inner,
+        };
+        assert_eq!("generic struct 4", error.to_string());
+    }
+
+    #[test]
+    fn test_source() {
+        assert!(Mixed::<u8>::Unit.source().is_none());
+        assert!(Mixed::<u8>::Tuple(1, "a").source().is_none());
+        assert!(Mixed::Generic(3).source().is_none());
+
+        let error = Mixed::<u8>::from(io());
+        assert_eq!("oh no!", error.source().unwrap().to_string());
+
+        let error = Mixed::<u8>::TupleSource("a", Inner, Backtrace::disabled());
+        assert_eq!("tuple source a", error.to_string());
+        assert_eq!("inner", error.source().unwrap().to_string());
+
+        let error = Mixed::<u8>::StructSource {
+            path: "b",
+            cause: Inner,
+            trace: None,
+        };
+        assert_eq!("struct source b", error.to_string());
+        assert!(error.source().unwrap().is::<Inner>());
+
+        let error = Mixed::GenericStruct {
+            value: 4,
+            source: Inner,
+        };
+        assert!(error.source().unwrap().is::<Inner>());
+    }
+
+    #[cfg(thiserror_nightly_testing)]
+    #[test]
+    fn test_provide() {
+        let error = Mixed::<u8>::from(io());
+        assert!(std::error::request_ref::<Backtrace>(&error).is_some());
+
+        let error = Mixed::<u8>::from(fmt::Error);
+        assert!(std::error::request_ref::<Backtrace>(&error).is_some());
+
+        let error = Mixed::<u8>::StructSource {
+            path: "b",
+            cause: Inner,
+            trace: None,
+        };
+        assert!(std::error::request_ref::<Backtrace>(&error).is_none());
+
+        assert!(std::error::request_ref::<Backtrace>(&Mixed::<u8>::Unit).is_none());
+    }
+}
@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(accessors)]
+pub enum Collision {
+    #[error("...")]
+    Io(u8),
+    #[error("...")]
+    IO(u16),
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(accessors)]
+pub struct Struct;
+
+fn main() {}
@@ -0,0 +1,19 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(auto_transparent)]
+pub struct Named {
+    inner: std::io::Error,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(auto_transparent)]
+pub struct Pair(std::io::Error, u8);
+
+#[derive(Error, Debug)]
+#[thiserror(auto_transparent)]
+pub enum Enum {
+    Io(std::io::Error),
+}
+
+fn main() {}
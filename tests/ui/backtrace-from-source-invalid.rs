@@ -0,0 +1,21 @@
+use std::backtrace::Backtrace;
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct NoFrom {
+    source: io::Error,
+    #[backtrace(from_source)]
+    backtrace: Backtrace,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct OnSource {
+    #[from]
+    #[backtrace(from_source)]
+    source: io::Error,
+}
+
+fn main() {}
@@ -0,0 +1,7 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("{0}")]
+pub struct Error<T>(#[error(bound(T: std::fmt::Display))] T);
+
+fn main() {}
@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error<T> {
+    #[error("{0}")]
+    #[error(display_bound(U: std::fmt::Display))]
+    Value(T),
+}
+
+fn main() {}
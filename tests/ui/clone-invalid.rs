@@ -0,0 +1,28 @@
+use std::backtrace::Backtrace;
+use std::io;
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(clone = "boxed")]
+pub enum UnknownStrategy {
+    #[error("...")]
+    Unit,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(clone = "arc_sources")]
+pub enum NotArc {
+    #[error("...")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(clone = "arc_sources")]
+pub struct WithBacktrace {
+    source: Arc<io::Error>,
+    backtrace: Backtrace,
+}
+
+fn main() {}
@@ -0,0 +1,45 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(codes)]
+pub enum Missing {
+    #[error("...")]
+    #[error(code = 1)]
+    Coded,
+    #[error("...")]
+    Uncoded,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(codes)]
+pub enum Duplicate {
+    #[error("...")]
+    #[error(code = 7)]
+    First,
+    #[error("...")]
+    #[error(code = 7)]
+    Second,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(codes)]
+pub enum NotLiteral {
+    #[error("...")]
+    #[error(code = 1 + 1)]
+    Computed,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(codes, discriminant_code)]
+pub enum Both {
+    #[error("...")]
+    #[error(code = 1)]
+    Unit,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(codes)]
+pub struct Struct;
+
+fn main() {}
@@ -0,0 +1,22 @@
+mod a {
+    #[derive(thiserror::Error, Debug)]
+    #[error("a")]
+    pub struct Error;
+
+    thiserror::compose! {
+        #[derive(Debug)]
+        pub enum Bare from (Error);
+    }
+}
+
+thiserror::compose! {
+    #[derive(Debug)]
+    pub enum Duplicate from (std::io::Error, a::Error as Io);
+}
+
+thiserror::compose! {
+    #[derive(Debug)]
+    pub enum Empty from ();
+}
+
+fn main() {}
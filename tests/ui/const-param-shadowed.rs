@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("expected {N} bytes")]
+#[allow(non_snake_case)]
+pub struct LengthError<const N: usize> {
+    N: usize,
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum NotEnabled {
+    #[error("...")]
+    #[error(constructor = "create")]
+    Unit,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(constructors)]
+pub enum Keyword {
+    #[error("...")]
+    Crate,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(constructors)]
+pub enum OnField {
+    #[error("...")]
+    Unit(#[error(constructor = "create")] u8),
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(constructors)]
+pub struct Struct;
+
+fn main() {}
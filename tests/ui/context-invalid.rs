@@ -0,0 +1,25 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(context)]
+pub struct Struct {
+    source: io::Error,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(context)]
+pub enum Generic<T> {
+    #[error("...")]
+    Read { value: T, source: io::Error },
+}
+
+#[derive(Error, Debug)]
+pub enum OnVariant {
+    #[error("...")]
+    #[thiserror(context)]
+    Read { source: io::Error },
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(context_setter)]
+pub struct MissingField {
+    path: String,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(context_setter)]
+pub struct NotString {
+    context: Vec<String>,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(context_setter)]
+pub enum Enum {
+    #[error("...")]
+    Failed { context: String },
+}
+
+fn main() {}
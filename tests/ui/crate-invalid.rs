@@ -0,0 +1,20 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(crate = "not a path")]
+#[error("...")]
+pub struct NotPath;
+
+#[derive(Error, Debug)]
+#[thiserror(crate = "thiserror", crate = "thiserror")]
+#[error("...")]
+pub struct Duplicate;
+
+#[derive(Error, Debug)]
+pub enum Misplaced {
+    #[error("...")]
+    #[thiserror(crate = "thiserror")]
+    Variant,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use thiserror::Error;
+
+#[derive(Error)]
+#[error("...")]
+#[thiserror(debug_transparent)]
+pub struct Error(std::io::Error);
+
+fn main() {}
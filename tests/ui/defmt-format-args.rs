@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(defmt)]
+#[error("invalid digit {}", .digit)]
+pub struct Error {
+    digit: char,
+}
+
+fn main() {}
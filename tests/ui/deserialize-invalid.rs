@@ -0,0 +1,28 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(deserialize)]
+pub enum WithFields {
+    #[error("not found")]
+    NotFound,
+    #[error("invalid {0}")]
+    Invalid(String),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(deserialize)]
+pub enum Collision {
+    #[error("...")]
+    #[error(name = "denied")]
+    Refused,
+    #[error("...")]
+    #[error(name = "denied")]
+    Denied,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(deserialize)]
+pub struct Struct;
+
+fn main() {}
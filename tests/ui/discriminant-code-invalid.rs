@@ -0,0 +1,34 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(discriminant_code)]
+pub enum MissingCode {
+    #[error("...")]
+    Coded(#[error(code = 1)] u8),
+    #[error("...")]
+    Uncoded(u8),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(discriminant_code)]
+pub enum Uncoded {
+    #[error("...")]
+    #[error(code = 1)]
+    Coded(u8),
+    #[error("...")]
+    Uncoded(u8),
+}
+
+#[derive(Error, Debug)]
+pub enum NotEnabled {
+    #[error("...")]
+    #[error(code = 1)]
+    Coded,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(discriminant_code)]
+pub struct Struct;
+
+fn main() {}
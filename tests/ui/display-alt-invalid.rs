@@ -0,0 +1,18 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(alt = "multi\nline")]
+pub struct MissingMessage;
+
+#[derive(Error, Debug)]
+#[error(match = .0, 0 => "zero", _ => "many")]
+#[error(alt = "{0} items")]
+pub struct WithMatch(usize);
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(alt = "first")]
+#[error(alt = "second")]
+pub struct Duplicate;
+
+fn main() {}
@@ -0,0 +1,17 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(document_messages)]
+pub enum Error {
+    #[error("disk full")]
+    DiskFull,
+}
+
+#[derive(Error, Debug)]
+pub enum Misplaced {
+    #[error("disk full")]
+    #[thiserror(document_messages)]
+    DiskFull,
+}
+
+fn main() {}
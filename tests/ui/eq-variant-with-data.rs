@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(eq)]
+pub enum Error {
+    #[error("missing field")]
+    MissingField,
+    #[error("io error")]
+    Io(#[from] std::io::Error),
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum OutOfRange {
+    #[error("...")]
+    #[error(exit_code = 256)]
+    Unit,
+}
+
+#[derive(Error, Debug)]
+pub enum Duplicate {
+    #[error("...")]
+    #[error(exit_code = 64)]
+    #[error(exit_code = 65)]
+    Unit,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct OnField {
+    #[error(exit_code = 64)]
+    code: u8,
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum StorageError {
+    #[error("io")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[flatten]
+pub struct Struct(StorageError);
+
+#[derive(Error, Debug)]
+pub enum Message {
+    #[flatten]
+    #[error("storage")]
+    Storage(#[from] StorageError),
+}
+
+#[derive(Error, Debug)]
+pub enum NoFrom {
+    #[flatten(io::Error)]
+    Storage(StorageError),
+}
+
+#[derive(Error, Debug)]
+pub enum Conflict {
+    #[flatten(io::Error)]
+    Storage(#[from] StorageError),
+    #[error("io")]
+    Io(#[from] io::Error),
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Wrapper<E> {
+    #[error("wrapped")]
+    Inner(#[from] E),
+    #[error("io")]
+    Io(#[from] std::io::Error),
+}
+
+fn main() {}
@@ -0,0 +1,18 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct WithRef {
+    #[from(ref, ctor = |source| WithRef { source })]
+    source: io::Error,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Empty {
+    #[from(ctor = )]
+    source: io::Error,
+}
+
+fn main() {}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("{0}")]
+    Message(#[from(into = String)] String),
+    #[error("code {0}")]
+    Code(#[from(into = u64)] u64),
+}
+
+fn main() {}
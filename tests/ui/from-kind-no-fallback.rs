@@ -0,0 +1,12 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("not found")]
+    NotFound(#[from(kind = NotFound)] io::Error),
+    #[error("permission denied")]
+    Denied(#[from(kind = PermissionDenied)] io::Error),
+}
+
+fn main() {}
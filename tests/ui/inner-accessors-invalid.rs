@@ -0,0 +1,32 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(inner_accessors)]
+pub struct NotTransparent(io::Error);
+
+#[derive(Error, Debug)]
+#[thiserror(inner_accessors)]
+pub enum NoTransparentVariant {
+    #[error("...")]
+    Io(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(accessors, inner_accessors)]
+pub enum WithAccessors {
+    #[error(transparent)]
+    Io(#[from] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(inner_accessors)]
+pub enum Collision {
+    #[error(transparent)]
+    Io(io::Error),
+    #[error(transparent)]
+    IO(std::fmt::Error),
+}
+
+fn main() {}
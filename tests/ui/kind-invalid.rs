@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum NotEnabled {
+    #[error("...")]
+    #[error(kind = Other)]
+    Unit,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(kind)]
+pub struct Struct;
+
+#[derive(Error, Debug)]
+#[thiserror(kind = "not a name")]
+pub enum BadName {
+    #[error("...")]
+    Unit,
+}
+
+fn main() {}
@@ -19,6 +19,12 @@ enum Enum<'a> {
 #[error("{0:?}")]
 struct Generic<T: Debug>(T);
 
+#[derive(Error, Debug)]
+#[error("error")]
+struct Boxed<'a> {
+    source: Box<dyn std::error::Error + 'a>,
+}
+
 fn main() -> Result<(), Error<'static>> {
     Err(Error(Inner("some text")))
 }
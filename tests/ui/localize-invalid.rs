@@ -0,0 +1,27 @@
+use thiserror::Error;
+
+pub trait Localizer {
+    fn message(&self, code: u32, args: &[(&str, String)]) -> String;
+}
+
+#[derive(Error, Debug)]
+#[thiserror(localize = Localizer)]
+pub enum Uncoded {
+    #[error("...")]
+    Variant,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(localize = Localizer)]
+#[error("...")]
+pub struct Struct;
+
+#[derive(Error, Debug)]
+#[thiserror(codes, localize = "Localizer")]
+pub enum Quoted {
+    #[error("...")]
+    #[error(code = 1)]
+    Variant,
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(match = .kind, Kind::A => "got A")]
+    Sub { kind: Kind },
+}
+
+#[derive(Debug)]
+pub enum Kind {
+    A,
+    B,
+}
+
+fn main() {}
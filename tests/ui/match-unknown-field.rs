@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(match = .kind, Kind::A => "a", Kind::B => "b")]
+pub struct Error {
+    category: Kind,
+}
+
+#[derive(Debug)]
+pub enum Kind {
+    A,
+    B,
+}
+
+fn main() {}
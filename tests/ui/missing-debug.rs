@@ -0,0 +1,7 @@
+use thiserror::Error;
+
+#[derive(Error)]
+#[error("...")]
+pub struct ParseError;
+
+fn main() {}
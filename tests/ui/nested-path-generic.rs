@@ -0,0 +1,14 @@
+use thiserror::Error;
+
+#[derive(Debug)]
+pub struct Inner<T> {
+    code: T,
+}
+
+#[derive(Error, Debug)]
+#[error("code {inner.code}")]
+pub struct Error<T> {
+    inner: Inner<T>,
+}
+
+fn main() {}
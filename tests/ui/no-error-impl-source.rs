@@ -0,0 +1,18 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(no_error_impl)]
+#[error("...")]
+pub struct Source {
+    #[source]
+    cause: std::io::Error,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(no_error_impl)]
+pub enum From {
+    #[error("...")]
+    Io(#[from] std::io::Error),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(no_std)]
+#[error("...")]
+pub struct Error {
+    backtrace: std::backtrace::Backtrace,
+}
+
+fn main() {}
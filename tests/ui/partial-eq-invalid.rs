@@ -0,0 +1,31 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(partial_eq = "display")]
+pub enum UnknownMode {
+    #[error("...")]
+    Unit,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(eq, partial_eq)]
+pub enum WithEq {
+    #[error("...")]
+    Unit,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(no_std, partial_eq = "message")]
+pub enum NoStd {
+    #[error("...")]
+    Unit,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct OnField {
+    #[thiserror(partial_eq)]
+    code: u8,
+}
+
+fn main() {}
@@ -0,0 +1,48 @@
+#![feature(error_generic_member_access)]
+#![allow(unused_features)]
+
+mod app {
+    // Modules that a plain `std::` or `core::` path in the generated code
+    // would resolve to.
+    #[allow(dead_code)]
+    mod std {}
+    #[allow(dead_code)]
+    mod core {}
+    #[allow(dead_code)]
+    mod fmt {}
+
+    use thiserror::Error;
+
+    #[derive(Error, Debug)]
+    #[thiserror(context, kind)]
+    pub enum LoadError {
+        #[error("failed to read {path}")]
+        Read {
+            path: String,
+            source: ::std::io::Error,
+        },
+        #[error(transparent)]
+        Parse(#[from] ::std::num::ParseIntError),
+        #[error("empty file")]
+        Empty,
+    }
+
+    #[derive(Error, Debug)]
+    #[error("value {0:?} out of range")]
+    pub struct RangeError<T>(pub T);
+
+    #[derive(Error, Debug)]
+    #[error("sync failed")]
+    pub struct SyncError {
+        #[from]
+        source: LoadError,
+    }
+}
+
+fn main() {
+    let error = app::LoadError::from("x".parse::<u8>().unwrap_err());
+    assert_eq!(app::LoadErrorKind::Parse, error.kind());
+    let error = app::SyncError::from(error);
+    assert_eq!("sync failed", error.to_string());
+    assert_eq!("value 300 out of range", app::RangeError(300).to_string());
+}
@@ -0,0 +1,27 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(predicates)]
+pub enum Collision {
+    #[error("...")]
+    Io,
+    #[error("...")]
+    IO,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(predicates)]
+#[allow(non_camel_case_types)]
+pub enum Underscore {
+    #[error("...")]
+    NotFound,
+    #[error("...")]
+    Not_Found,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(predicates)]
+pub struct Struct;
+
+fn main() {}
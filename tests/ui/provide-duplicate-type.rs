@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[provide]
+    host: String,
+    #[provide]
+    path: String,
+}
+
+fn main() {}
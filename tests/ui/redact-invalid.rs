@@ -0,0 +1,23 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("login failed")]
+pub struct NoDebug {
+    #[redact]
+    password: String,
+}
+
+#[derive(Error)]
+#[thiserror(debug, debug_transparent)]
+#[error(transparent)]
+pub struct Combined(std::io::Error);
+
+#[derive(Error)]
+#[thiserror(debug)]
+pub enum Misplaced {
+    #[error("login failed")]
+    #[redact]
+    Login,
+}
+
+fn main() {}
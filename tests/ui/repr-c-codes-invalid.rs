@@ -0,0 +1,37 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(repr_c_codes)]
+pub enum MissingCode {
+    #[error("...")]
+    #[error(code = 1)]
+    First,
+    #[error("...")]
+    Second,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(repr_c_codes)]
+pub enum OutOfRange {
+    #[error("...")]
+    #[error(code = 4294967295)]
+    Unit,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(repr_c_codes)]
+pub enum Duplicate {
+    #[error("...")]
+    #[error(code = -1)]
+    First,
+    #[error("...")]
+    #[error(code = -1)]
+    Second,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(repr_c_codes)]
+pub struct Struct;
+
+fn main() {}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(require_debug)]
+pub struct Generic<T>(T);
+
+#[derive(Error)]
+#[error("...")]
+#[thiserror(debug, require_debug)]
+pub struct Derived;
+
+fn main() {}
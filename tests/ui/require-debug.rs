@@ -0,0 +1,8 @@
+use thiserror::Error;
+
+#[derive(Error)]
+#[error("...")]
+#[thiserror(require_debug)]
+pub struct ParseError;
+
+fn main() {}
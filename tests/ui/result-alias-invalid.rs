@@ -0,0 +1,15 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("...")]
+    #[thiserror(result_alias)]
+    Variant,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(result_alias = "not an ident")]
+#[error("...")]
+pub struct NotIdent;
+
+fn main() {}
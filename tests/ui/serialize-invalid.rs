@@ -0,0 +1,40 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("missing {0}")]
+pub struct NotOptedIn(#[error(serialize)] String);
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(serialize)]
+pub struct Both(#[sensitive] #[error(serialize)] String);
+
+#[derive(Error, Debug)]
+#[thiserror(serialize)]
+pub enum SensitiveSource {
+    #[error("read failed")]
+    Read(#[source] #[sensitive] io::Error),
+}
+
+#[derive(Error, Debug)]
+#[thiserror(serialize)]
+pub enum NoMessage {
+    #[error(transparent_str)]
+    Secret(#[sensitive] String),
+}
+
+#[derive(Error, Debug)]
+#[error("{message}")]
+#[thiserror(serialize)]
+pub struct Reserved {
+    #[error(serialize)]
+    message: String,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[sensitive]
+pub struct OnContainer;
+
+fn main() {}
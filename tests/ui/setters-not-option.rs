@@ -0,0 +1,20 @@
+use std::io;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[thiserror(setters)]
+pub struct Error {
+    source: io::Error,
+}
+
+#[derive(Error, Debug)]
+#[thiserror(setters)]
+pub enum Mixed {
+    #[error("...")]
+    Optional(#[source] Option<io::Error>),
+    #[error("...")]
+    Required(#[source] io::Error),
+}
+
+fn main() {}
@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error<E> {
+    #[source(wrap, err_as_ref)]
+    source: E,
+}
+
+fn main() {}
@@ -0,0 +1,33 @@
+use std::error::Error as StdError;
+use thiserror::Error;
+
+#[derive(Debug)]
+pub struct Slot;
+
+impl Slot {
+    fn as_error(&self) -> Option<&(dyn StdError + 'static)> {
+        None
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum Variant {
+    #[error("variant")]
+    Failed(#[source(with = self.as_error())] Slot),
+}
+
+#[derive(Error, Debug)]
+#[error("deref")]
+pub struct Deref {
+    #[source(deref, with = self.slot.as_error())]
+    slot: Box<Slot>,
+}
+
+#[derive(Error, Debug)]
+#[error("empty")]
+pub struct Empty {
+    #[source(with =)]
+    slot: Slot,
+}
+
+fn main() {}
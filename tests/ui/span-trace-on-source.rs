@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error("...")]
+pub struct Error {
+    #[source]
+    #[span_trace]
+    inner: std::io::Error,
+}
+
+fn main() {}
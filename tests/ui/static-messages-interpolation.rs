@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(static_messages)]
+pub enum Error {
+    #[error("erase timed out")]
+    Timeout,
+    #[error("invalid sector {0}")]
+    InvalidSector(u32),
+}
+
+fn main() {}
@@ -0,0 +1,24 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(transparent)]
+#[error("...")]
+pub struct TransparentFirst(std::io::Error);
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(transparent)]
+pub struct DisplayFirst(std::io::Error);
+
+#[derive(Error, Debug)]
+pub enum Enum {
+    #[error(transparent)]
+    #[error("...")]
+    Variant(std::io::Error),
+}
+
+#[derive(Error, Debug)]
+#[error(transparent, "...")]
+pub struct SameAttribute(std::io::Error);
+
+fn main() {}
@@ -0,0 +1,12 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(transparent)]
+pub enum Error {
+    Io(std::io::Error),
+    #[error("not found")]
+    NotFound,
+    Other(anyhow::Error, String),
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Other(Box<Error>),
+}
+
+#[derive(Error, Debug)]
+#[error(transparent)]
+pub struct Wrapper(std::sync::Arc<Self>);
+
+fn main() {}
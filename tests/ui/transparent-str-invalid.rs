@@ -0,0 +1,22 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[error(transparent_str)]
+pub struct TwoFields(String, u8);
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(transparent_str)]
+pub struct WithMessage(String);
+
+#[derive(Error, Debug)]
+#[error(transparent_str)]
+pub enum OnEnum {
+    Unit(String),
+}
+
+#[derive(Error, Debug)]
+#[error(transparent_str, .0)]
+pub struct WithArgs(String);
+
+fn main() {}
@@ -0,0 +1,16 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("io")]
+    Io(#[from] std::io::Error),
+    #[error("other")]
+    #[try_from(std::io::Error, with = other)]
+    Other(std::io::Error),
+}
+
+fn other(error: std::io::Error) -> Result<std::io::Error, std::io::Error> {
+    Err(error)
+}
+
+fn main() {}
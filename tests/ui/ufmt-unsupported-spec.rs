@@ -0,0 +1,10 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(ufmt)]
+#[error("sensor read {value:>8.2}")]
+pub struct Error {
+    value: f32,
+}
+
+fn main() {}
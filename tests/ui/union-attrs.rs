@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Error)]
+#[error("{msg}")]
+pub union U {
+    #[source]
+    msg: &'static str,
+    num: usize,
+}
+
+fn main() {}
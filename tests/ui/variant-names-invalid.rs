@@ -0,0 +1,26 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+#[thiserror(variant_names)]
+pub enum Collision {
+    #[error("...")]
+    Timeout,
+    #[error("...")]
+    #[error(name = "Timeout")]
+    TimedOut,
+}
+
+#[derive(Error, Debug)]
+pub enum NotOptedIn {
+    #[error("...")]
+    #[error(name = "timeout")]
+    TimedOut,
+}
+
+#[derive(Error, Debug)]
+#[error("...")]
+#[error(name = "invalid")]
+#[thiserror(variant_names)]
+pub struct Struct;
+
+fn main() {}